@@ -0,0 +1,128 @@
+import { PublicKey } from "@solana/web3.js";
+
+// Client side of per-attempt answer shuffling (AnswerShuffle in the program).
+//
+// Call start_shuffled_attempt first: it fixes a seed from the most recent slot
+// hash on the QuizAttempt account, so nobody can work out the order before the
+// attempt starts. Render the shuffled order, then submit answers exactly as
+// shown, passing the QuizAttempt to submit_quiz_answers: answers[p] answers
+// shown question p, and option indices (and MultiSelect bitmask bits) are shown
+// positions. quiz_evaluation maps them back to the canonical order the answer
+// key uses.
+
+export const QUIZ_ITEMS = 2;
+export const MAX_OPTIONS_PER_QUESTION = 10;
+
+// Anchor decodes enums as `{ variantName: fields }`
+type Variant = Record<string, unknown>;
+
+type Grading = { exact?: unknown; multiSelect?: unknown } & Variant;
+
+// Anchor-decoded QuestionData with its grading (see gradingFor), reduced to
+// what the shuffle depends on
+export interface ShuffleQuestion {
+  questionType: { multipleChoice?: { options: string[] } } & Variant;
+  grading: Grading;
+}
+
+// Anchor-decoded QuestionGrading (Survey.questionGrading)
+export interface QuestionGrading {
+  questionId: number;
+  grading: Grading;
+}
+
+// Grading of one question; a question without an entry is graded Exact
+export function gradingFor(
+  survey: { questionGrading: QuestionGrading[] },
+  questionId: number
+): Grading {
+  return (
+    survey.questionGrading.find((entry) => entry.questionId === questionId)
+      ?.grading ?? { exact: { negativeMarks: 0 } }
+  );
+}
+
+export interface AnswerShuffle {
+  // Canonical index of the graded question shown at each position
+  questionOrder: number[];
+  // [canonical question][shown position] -> canonical option index
+  optionOrder: number[][];
+}
+
+// PDA of a student's QuizAttempt, which holds `shuffleSeed`
+export function quizAttemptPda(
+  programId: PublicKey,
+  quiz: PublicKey,
+  student: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("quiz_attempt"), quiz.toBuffer(), student.toBuffer()],
+    programId
+  )[0];
+}
+
+const U64_MASK = (1n << 64n) - 1n;
+
+// SplitMix64 over the XOR of the seed's four little-endian words (ShuffleRng)
+function shuffleRng(seed: Uint8Array): () => bigint {
+  let state = 0n;
+  for (let word = 0; word < 4; word++) {
+    let value = 0n;
+    for (let byte = 7; byte >= 0; byte--) {
+      value = (value << 8n) | BigInt(seed[word * 8 + byte]);
+    }
+    state ^= value;
+  }
+  return () => {
+    state = (state + 0x9e3779b97f4a7c15n) & U64_MASK;
+    let z = state;
+    z = ((z ^ (z >> 30n)) * 0xbf58476d1ce4e5b9n) & U64_MASK;
+    z = ((z ^ (z >> 27n)) * 0x94d049bb133111ebn) & U64_MASK;
+    return z ^ (z >> 31n);
+  };
+}
+
+const identity = (length: number) => Array.from({ length }, (_, i) => i);
+
+// Order one attempt shows the graded questions and options in.
+// Pass the QuizAttempt's `shuffleSeed` and the quiz account's `shuffleAnswers`
+// flag; when it is off the order is canonical.
+export function answerShuffle(
+  shuffleSeed: number[] | Uint8Array,
+  questions: ShuffleQuestion[],
+  shuffleAnswers: boolean
+): AnswerShuffle {
+  const optionOrder = Array.from({ length: QUIZ_ITEMS }, () =>
+    identity(MAX_OPTIONS_PER_QUESTION)
+  );
+  if (!shuffleAnswers) {
+    return { questionOrder: identity(QUIZ_ITEMS), optionOrder };
+  }
+
+  const next = shuffleRng(Uint8Array.from(shuffleSeed));
+  const swapped = questions.length >= QUIZ_ITEMS && next() % 2n === 1n;
+
+  questions.slice(0, QUIZ_ITEMS).forEach((question, q) => {
+    // Only option indices move; true/false, ratings and numeric answers keep
+    // their meaning
+    const options = question.questionType.multipleChoice?.options;
+    const optionGrading =
+      question.grading.exact !== undefined ||
+      question.grading.multiSelect !== undefined;
+    const optionCount =
+      options && optionGrading
+        ? Math.min(options.length, MAX_OPTIONS_PER_QUESTION)
+        : 0;
+    // Fisher-Yates
+    const order = optionOrder[q];
+    for (let i = optionCount - 1; i >= 1; i--) {
+      const j = Number(next() % BigInt(i + 1));
+      [order[i], order[j]] = [order[j], order[i]];
+    }
+  });
+
+  return {
+    questionOrder: swapped ? [1, 0] : identity(QUIZ_ITEMS),
+    optionOrder,
+  };
+}
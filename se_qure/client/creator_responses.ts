@@ -0,0 +1,124 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { RescueCipher, x25519 } from "@arcium-hq/client";
+import { randomBytes } from "crypto";
+
+// Client side of CreatorOnly surveys (CreatorEncryptedResponse in the program).
+//
+// No MPC is involved: each answer is one field element, encrypted with Rescue
+// under the x25519 secret shared by a fresh respondent key and the survey's
+// creator_arcium_pubkey, in question order. Only the creator can read them and
+// there are no aggregate analytics; answer NOT_ANSWERED for skipped questions.
+// The response account is addressed by that one-time key (creatorResponsePda),
+// so it can't be found from the wallet; a separate receipt keeps one response
+// per wallet.
+
+export const MAX_CREATOR_RESPONSE_CIPHERTEXTS = 64;
+export const NOT_ANSWERED = 0xffffffff;
+
+// Account of one response; the respondent key comes from encryptCreatorResponse
+export function creatorResponsePda(
+  programId: PublicKey,
+  survey: PublicKey,
+  respondentPubKey: Uint8Array
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("creator_response"),
+      survey.toBuffer(),
+      Buffer.from(respondentPubKey),
+    ],
+    programId
+  )[0];
+}
+
+// Per-wallet receipt (TallyReceipt) that stops a second response
+export function creatorResponseReceiptPda(
+  programId: PublicKey,
+  survey: PublicKey,
+  respondent: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("creator_response_receipt"),
+      survey.toBuffer(),
+      respondent.toBuffer(),
+    ],
+    programId
+  )[0];
+}
+
+// Arguments of submit_creator_encrypted_response
+export interface CreatorEncryptedAnswers {
+  respondentPubKey: Uint8Array;
+  nonce: Uint8Array;
+  ciphertexts: number[][];
+}
+
+export function encryptCreatorResponse(
+  answers: (number | bigint)[],
+  creatorPubKey: Uint8Array
+): CreatorEncryptedAnswers {
+  if (
+    answers.length === 0 ||
+    answers.length > MAX_CREATOR_RESPONSE_CIPHERTEXTS
+  ) {
+    throw new Error(
+      `Expected 1 to ${MAX_CREATOR_RESPONSE_CIPHERTEXTS} answers`
+    );
+  }
+  const respondentSecret = x25519.utils.randomPrivateKey();
+  const cipher = new RescueCipher(
+    x25519.getSharedSecret(respondentSecret, creatorPubKey)
+  );
+  const nonce = randomBytes(16);
+  return {
+    respondentPubKey: x25519.getPublicKey(respondentSecret),
+    nonce,
+    ciphertexts: cipher.encrypt(answers.map(BigInt), nonce),
+  };
+}
+
+// Anchor-decoded CreatorEncryptedResponse account
+export interface CreatorEncryptedResponseAccount {
+  survey: PublicKey;
+  respondentPubKey: number[];
+  nonce: BN;
+  submittedAt: BN;
+  ciphertexts: number[][];
+}
+
+export function decryptCreatorResponse(
+  creatorSecret: Uint8Array,
+  response: CreatorEncryptedResponseAccount
+): bigint[] {
+  const cipher = new RescueCipher(
+    x25519.getSharedSecret(
+      creatorSecret,
+      Uint8Array.from(response.respondentPubKey)
+    )
+  );
+  const nonce = response.nonce.toArrayLike(Buffer, "le", 16);
+  return cipher.decrypt(response.ciphertexts, nonce);
+}
+
+// Every response to a CreatorOnly survey, decrypted with the creator's secret.
+// Respondent wallets aren't stored; only the response PDA address is returned.
+export async function decryptCreatorResponses(
+  program: Program<any>,
+  creatorSecret: Uint8Array,
+  survey: PublicKey
+): Promise<{ address: PublicKey; submittedAt: number; answers: bigint[] }[]> {
+  // Discriminator, then CreatorEncryptedResponse.survey
+  const accounts = await program.account.creatorEncryptedResponse.all([
+    { memcmp: { offset: 8, bytes: survey.toBase58() } },
+  ]);
+  return accounts.map(({ publicKey, account }) => {
+    const response = account as unknown as CreatorEncryptedResponseAccount;
+    return {
+      address: publicKey,
+      submittedAt: response.submittedAt.toNumber(),
+      answers: decryptCreatorResponse(creatorSecret, response),
+    };
+  });
+}
@@ -0,0 +1,122 @@
+import { Program } from "@coral-xyz/anchor";
+import { createHash } from "crypto";
+
+// Client side of off-chain question content (QuestionContentCommitment in the
+// program).
+//
+// The document is a JSON array of QuestionData whose ids equal their index.
+// Sha256 commits to the exact document bytes. MerkleRoot commits to one leaf
+// per question over its Borsh encoding, so per-question tallies can prove a
+// single question on-chain (CommittedQuestion). A level with an odd number of
+// nodes pairs its last node with itself.
+
+const MERKLE_LEAF_PREFIX = 0x00;
+const MERKLE_NODE_PREFIX = 0x01;
+
+// Anchor-decoded QuestionContentCommitment
+export interface QuestionContentCommitment {
+  scheme: { sha256?: object; merkleRoot?: object };
+  contentHash: number[];
+  contentUri: string;
+  questionCount: number;
+}
+
+// Argument of the init_* tally instructions for committed surveys
+export interface CommittedQuestion {
+  question: object;
+  proof: number[][];
+}
+
+function sha256(...parts: Uint8Array[]): Buffer {
+  const digest = createHash("sha256");
+  parts.forEach((part) => digest.update(part));
+  return digest.digest();
+}
+
+function sameHash(a: Uint8Array, b: number[] | Uint8Array): boolean {
+  return Buffer.from(a).equals(Buffer.from(b));
+}
+
+// Check a downloaded document against a Sha256 commitment
+export function verifyQuestionDocument(
+  commitment: QuestionContentCommitment,
+  content: Uint8Array
+): boolean {
+  return (
+    commitment.scheme.sha256 !== undefined &&
+    sameHash(sha256(content), commitment.contentHash)
+  );
+}
+
+export function encodeQuestion(
+  program: Program<any>,
+  question: object
+): Buffer {
+  return program.coder.types.encode("QuestionData", question);
+}
+
+// question_leaf_hash in the program
+export function questionLeafHash(index: number, encoded: Uint8Array): Buffer {
+  const indexBytes = Buffer.alloc(4);
+  indexBytes.writeUInt32LE(index);
+  return sha256(Uint8Array.of(MERKLE_LEAF_PREFIX), indexBytes, encoded);
+}
+
+function parentHash(left: Uint8Array, right: Uint8Array): Buffer {
+  return sha256(Uint8Array.of(MERKLE_NODE_PREFIX), left, right);
+}
+
+// Root for set_question_content_commitment and one proof per question
+export function questionMerkleTree(
+  program: Program<any>,
+  questions: object[]
+): { root: Buffer; proofs: number[][][] } {
+  let level = questions.map((question, index) =>
+    questionLeafHash(index, encodeQuestion(program, question))
+  );
+  const proofs: number[][][] = questions.map(() => []);
+  const positions = questions.map((_, index) => index);
+  while (level.length > 1) {
+    if (level.length % 2 === 1) {
+      level.push(level[level.length - 1]);
+    }
+    positions.forEach((position, index) => {
+      proofs[index].push(Array.from(level[position ^ 1]));
+      positions[index] = position >> 1;
+    });
+    const next: Buffer[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(parentHash(level[i], level[i + 1]));
+    }
+    level = next;
+  }
+  return { root: level[0], proofs };
+}
+
+// Check one downloaded question against a MerkleRoot commitment
+export function verifyCommittedQuestion(
+  program: Program<any>,
+  commitment: QuestionContentCommitment,
+  index: number,
+  committed: CommittedQuestion
+): boolean {
+  if (
+    commitment.scheme.merkleRoot === undefined ||
+    index >= commitment.questionCount
+  ) {
+    return false;
+  }
+  let node = questionLeafHash(
+    index,
+    encodeQuestion(program, committed.question)
+  );
+  let position = index;
+  for (const sibling of committed.proof) {
+    node =
+      position % 2 === 0
+        ? parentHash(node, Uint8Array.from(sibling))
+        : parentHash(Uint8Array.from(sibling), node);
+    position >>= 1;
+  }
+  return sameHash(node, commitment.contentHash);
+}
@@ -0,0 +1,64 @@
+// Client side of skip logic (DisplayCondition in the program).
+//
+// add_questions only checks that conditions point at earlier questions with
+// in-range values; deciding what to show happens here. Hidden questions are
+// submitted as NOT_ANSWERED. `required` is a form hint: the MXE cannot reject
+// an encrypted NOT_ANSWERED, so the circuits count it as a skip either way.
+
+export const NOT_ANSWERED = 0xffffffff;
+
+// Anchor-decoded DisplayCondition
+export interface DisplayCondition {
+  questionId: number;
+  operator: {
+    equals?: object;
+    notEquals?: object;
+    greaterThan?: object;
+    lessThan?: object;
+  };
+  value: number;
+}
+
+export function isConditionSatisfied(
+  condition: DisplayCondition,
+  answer: number
+): boolean {
+  const { operator, value } = condition;
+  if (operator.equals !== undefined) return answer === value;
+  if (operator.notEquals !== undefined) return answer !== value;
+  if (operator.greaterThan !== undefined) return answer > value;
+  return answer < value;
+}
+
+// Anchor-decoded QuestionDisplayConditions (Survey.questionDisplayConditions)
+export interface QuestionDisplayConditions {
+  questionId: number;
+  conditions: DisplayCondition[];
+}
+
+// Conditions of one question; a question without an entry is always shown
+export function conditionsFor(
+  survey: { questionDisplayConditions: QuestionDisplayConditions[] },
+  questionId: number
+): DisplayCondition[] {
+  return (
+    survey.questionDisplayConditions.find((entry) => entry.questionId === questionId)
+      ?.conditions ?? []
+  );
+}
+
+// `answerFor(id)` is the plaintext answer to an earlier question, or
+// undefined if it was skipped; a skipped question satisfies no condition.
+export function isDisplayed(
+  conditions: DisplayCondition[],
+  answerFor: (questionId: number) => number | undefined
+): boolean {
+  return conditions.every((condition) => {
+    const answer = answerFor(condition.questionId);
+    return (
+      answer !== undefined &&
+      answer !== NOT_ANSWERED &&
+      isConditionSatisfied(condition, answer)
+    );
+  });
+}
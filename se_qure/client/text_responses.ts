@@ -0,0 +1,262 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { RescueCipher, deserializeLE, x25519 } from "@arcium-hq/client";
+import { createHash, randomBytes } from "crypto";
+
+// Client side of encrypted TextInput answers (TextResponse in the program).
+//
+// The text is encrypted with Rescue under the x25519 secret shared by a fresh
+// respondent key and the survey's creator_arcium_pubkey, so only the creator
+// can read it. Block 0 holds the UTF-8 byte length, then 31 text bytes per
+// block (little-endian, always below the field modulus), padded with zero
+// blocks to a multiple of TEXT_PAD_BLOCKS. Submit blank answers too: padded,
+// they look like any short answer.
+
+export const TEXT_BLOCK_BYTES = 31;
+export const TEXT_PAD_BLOCKS = 4;
+export const MAX_INLINE_TEXT_CIPHERTEXTS = 16;
+export const NOT_ANSWERED = 0xffffffff;
+
+export function encodeText(text: string): bigint[] {
+  const bytes = Buffer.from(text, "utf8");
+  const blocks = [BigInt(bytes.length)];
+  for (let start = 0; start < bytes.length; start += TEXT_BLOCK_BYTES) {
+    blocks.push(leToBigInt(bytes.subarray(start, start + TEXT_BLOCK_BYTES)));
+  }
+  while (blocks.length % TEXT_PAD_BLOCKS !== 0) {
+    blocks.push(0n);
+  }
+  return blocks;
+}
+
+export function decodeText(blocks: bigint[]): string {
+  const length = Number(blocks[0]);
+  const bytes = Buffer.concat(
+    blocks.slice(1).map((block) => bigIntToLe(block, TEXT_BLOCK_BYTES))
+  );
+  return bytes.subarray(0, length).toString("utf8");
+}
+
+// sha256 over the ciphertext blocks (TextResponse::ciphertext_hash)
+export function ciphertextHash(ciphertexts: number[][]): Buffer {
+  const hash = createHash("sha256");
+  ciphertexts.forEach((block) => hash.update(Uint8Array.from(block)));
+  return hash.digest();
+}
+
+export interface EncryptedText {
+  respondentSecret: Uint8Array; // Keep to encrypt the length for MPC
+  respondentPubKey: Uint8Array;
+  nonce: Uint8Array;
+  ciphertexts: number[][];
+  ciphertextHash: Buffer;
+  inline: boolean; // Fits in TextResponseBody::Inline
+}
+
+export function encryptTextResponse(
+  text: string,
+  creatorPubKey: Uint8Array
+): EncryptedText {
+  const respondentSecret = x25519.utils.randomPrivateKey();
+  const cipher = new RescueCipher(
+    x25519.getSharedSecret(respondentSecret, creatorPubKey)
+  );
+  const nonce = randomBytes(16);
+  const ciphertexts = cipher.encrypt(encodeText(text), nonce);
+  return {
+    respondentSecret,
+    respondentPubKey: x25519.getPublicKey(respondentSecret),
+    nonce,
+    ciphertexts,
+    ciphertextHash: ciphertextHash(ciphertexts),
+    inline: ciphertexts.length <= MAX_INLINE_TEXT_CIPHERTEXTS,
+  };
+}
+
+// The `encrypted_length` / `length_nonce` arguments of submit_text_response:
+// trimmed UTF-8 length (0 if blank) encrypted to the MXE with the same key.
+export function encryptTextLength(
+  text: string | null,
+  encrypted: EncryptedText,
+  mxePublicKey: Uint8Array
+): { ciphertext: number[]; nonce: Uint8Array } {
+  const length =
+    text === null ? NOT_ANSWERED : Buffer.byteLength(text.trim(), "utf8");
+  const cipher = new RescueCipher(
+    x25519.getSharedSecret(encrypted.respondentSecret, mxePublicKey)
+  );
+  const nonce = randomBytes(16);
+  return { ciphertext: cipher.encrypt([BigInt(length)], nonce)[0], nonce };
+}
+
+// Anchor-decoded TextResponse account
+export interface TextResponseAccount {
+  respondent: PublicKey;
+  questionId: number;
+  respondentPubKey: number[];
+  nonce: BN;
+  ciphertextHash: number[];
+  contentUri: string;
+  ciphertexts: number[][];
+}
+
+export function decryptTextResponse(
+  creatorSecret: Uint8Array,
+  response: TextResponseAccount,
+  ciphertexts: number[][] = response.ciphertexts
+): string {
+  const expected = Buffer.from(response.ciphertextHash);
+  if (!ciphertextHash(ciphertexts).equals(expected)) {
+    throw new Error("Text response ciphertext does not match its hash");
+  }
+  const cipher = new RescueCipher(
+    x25519.getSharedSecret(
+      creatorSecret,
+      Uint8Array.from(response.respondentPubKey)
+    )
+  );
+  const nonce = response.nonce.toArrayLike(Buffer, "le", 16);
+  return decodeText(cipher.decrypt(ciphertexts, nonce));
+}
+
+export interface DecryptedTextResponse {
+  respondent: PublicKey;
+  questionId: number;
+  text: string;
+}
+
+// Every text answer to a survey (optionally one question), decrypted with the
+// creator's x25519 secret. Off-chain answers are downloaded with
+// `fetchCiphertexts` and checked against their on-chain hash.
+export async function decryptTextResponses(
+  program: Program<any>,
+  creatorSecret: Uint8Array,
+  survey: PublicKey,
+  options: {
+    questionId?: number;
+    fetchCiphertexts?: (contentUri: string) => Promise<number[][]>;
+  } = {}
+): Promise<DecryptedTextResponse[]> {
+  // Discriminator, then TextResponse.survey
+  const accounts = await program.account.textResponse.all([
+    { memcmp: { offset: 8, bytes: survey.toBase58() } },
+  ]);
+
+  const results: DecryptedTextResponse[] = [];
+  for (const { account } of accounts) {
+    const response = account as unknown as TextResponseAccount;
+    if (
+      options.questionId !== undefined &&
+      response.questionId !== options.questionId
+    ) {
+      continue;
+    }
+    let ciphertexts = response.ciphertexts;
+    if (response.contentUri) {
+      if (!options.fetchCiphertexts) {
+        throw new Error(`No fetcher for answer at ${response.contentUri}`);
+      }
+      ciphertexts = await options.fetchCiphertexts(response.contentUri);
+    }
+    results.push({
+      respondent: response.respondent,
+      questionId: response.questionId,
+      text: decryptTextResponse(creatorSecret, response, ciphertexts),
+    });
+  }
+  return results;
+}
+
+// Keyword counters (KeywordTallyStorage). The vocabulary has one keyword per
+// line; a keyword's id is its line number; the hash is sha256 of the text.
+export const MAX_KEYWORD_VOCABULARY = 32;
+export const MAX_ANSWER_KEYWORDS = 8;
+export const NO_KEYWORD = 0xffffffff;
+
+export function keywordVocabularyHash(vocabulary: string[]): Buffer {
+  return createHash("sha256").update(vocabulary.join("\n"), "utf8").digest();
+}
+
+// Distinct vocabulary ids mentioned in `text`, in order of first mention,
+// padded with NO_KEYWORD. Matching is by lowercased word.
+export function tokenizeKeywords(
+  text: string,
+  vocabulary: string[]
+): number[] {
+  const ids = new Map(vocabulary.map((word, id) => [word.toLowerCase(), id]));
+  const tokens: number[] = [];
+  for (const word of text.toLowerCase().split(/[^\p{L}\p{N}]+/u)) {
+    const id = ids.get(word);
+    if (id !== undefined && !tokens.includes(id)) {
+      tokens.push(id);
+    }
+  }
+  const padded = tokens.slice(0, MAX_ANSWER_KEYWORDS);
+  while (padded.length < MAX_ANSWER_KEYWORDS) {
+    padded.push(NO_KEYWORD);
+  }
+  return padded;
+}
+
+// The `encrypted_keywords` / `keywords_nonce` args of submit_text_keywords.
+// Must use the answer's respondent key: the program passes the stored one.
+export function encryptTextKeywords(
+  text: string,
+  vocabulary: string[],
+  encrypted: EncryptedText,
+  mxePublicKey: Uint8Array
+): { ciphertexts: number[][]; nonce: Uint8Array } {
+  const tokens = tokenizeKeywords(text, vocabulary).map(BigInt);
+  const cipher = new RescueCipher(
+    x25519.getSharedSecret(encrypted.respondentSecret, mxePublicKey)
+  );
+  const nonce = randomBytes(16);
+  return { ciphertexts: cipher.encrypt(tokens, nonce), nonce };
+}
+
+// Decrypt a KeywordTableRevealed event into (keyword, answers) rows
+export function decryptKeywordTable(
+  creatorSecret: Uint8Array,
+  event: { encryptionKey: number[]; nonce: BN; ciphertexts: number[][] },
+  vocabulary: string[]
+): { thresholdMet: boolean; answers: number; rows: [string, number][] } {
+  const cipher = new RescueCipher(
+    x25519.getSharedSecret(
+      creatorSecret,
+      Uint8Array.from(event.encryptionKey)
+    )
+  );
+  const nonce = event.nonce.toArrayLike(Buffer, "le", 16);
+  const [met, answers, ...rest] = cipher
+    .decrypt(event.ciphertexts, nonce)
+    .map(Number);
+  const keywords = rest.slice(0, rest.length / 2);
+  const counts = rest.slice(rest.length / 2);
+  const rows: [string, number][] = keywords
+    .map((id, i): [number, number] => [id, counts[i]])
+    .filter(([id]) => id !== NO_KEYWORD)
+    .map(([id, count]) => [vocabulary[id], count]);
+  return { thresholdMet: met === 1, answers, rows };
+}
+
+// Nonces go on-chain as u128 instruction arguments
+export function nonceToBN(nonce: Uint8Array): BN {
+  return new BN(deserializeLE(nonce).toString());
+}
+
+function leToBigInt(bytes: Uint8Array): bigint {
+  let value = 0n;
+  for (let i = bytes.length - 1; i >= 0; i--) {
+    value = (value << 8n) | BigInt(bytes[i]);
+  }
+  return value;
+}
+
+function bigIntToLe(value: bigint, length: number): Buffer {
+  const bytes = Buffer.alloc(length);
+  for (let i = 0; i < length; i++) {
+    bytes[i] = Number(value & 0xffn);
+    value >>= 8n;
+  }
+  return bytes;
+}
@@ -3,6 +3,11 @@ use arcis_imports::*;
 #[encrypted]
 mod circuits {
     use arcis_imports::*;
+    use crate::fixed_point::{abs_diff, correlation, div_round, isqrt, scale_for};
+    use crate::safe_math::{clamp_to_u32, sat_add_u32, sat_add_u64, sat_mul_u128, sat_mul_u32};
+
+    // Sentinel the client encrypts for questions hidden by skip logic (see NOT_ANSWERED in the program)
+    const NOT_ANSWERED: u32 = u32::MAX;
 
     // Data structures for analytics computation
     #[derive(Copy, Clone)]
@@ -16,17 +21,88 @@ mod circuits {
     #[derive(Copy, Clone)]
     pub struct BusinessMetrics {
         pub conversion_rate: u32,
-        pub user_satisfaction: u32,
+        pub user_satisfaction: u32,  // Average answered response, scaled by 10^report_decimals
         pub retention_metrics: u32,
         pub growth_indicators: u32,
     }
 
+    // Pearson correlation between response1 (x) and response2 (y) over every respondent
+    // so far, plus the t statistic for H0: r = 0 (n - 2 degrees of freedom).
+    // Fixed-point values use STAT_SCALE (10^4), i.e. 4 decimal places.
     #[derive(Copy, Clone)]
     pub struct ResearchInsights {
-        pub statistical_significance: u32,
-        pub correlation_analysis: u32,
-        pub predictive_models: u32,
-        pub research_recommendations: u32,
+        pub sample_size: u32,           // n: respondents who answered both questions
+        pub correlation_plus_one: u32,  // (r + 1) * 10^4, so 0..=20000 maps to r in -1..=1
+        pub t_statistic: u32,           // |t| * 10^4, saturating at u32::MAX; sign follows r
+        pub positive_correlation: bool, // r >= 0
+    }
+
+    // Running sums for the correlation, kept encrypted under the MXE between respondents
+    #[derive(Copy, Clone)]
+    pub struct RunningSums {
+        pub n: u64,
+        pub sum_x: u64,
+        pub sum_y: u64,
+        pub sum_xy: u64,
+        pub sum_x2: u64,
+        pub sum_y2: u64,
+    }
+
+    // Fixed-point scale of the correlation and t statistic (fixed at MAX_DECIMALS, 10^4)
+    const STAT_SCALE: u128 = 10_000;
+
+    fn correlation_insights(sums: &RunningSums) -> ResearchInsights {
+        let n = sums.n as u128;
+
+        // r = (nΣxy − ΣxΣy) / sqrt((nΣx² − (Σx)²)(nΣy² − (Σy)²))
+        // Products saturate rather than wrap once the sums grow past ~2^64
+        let (covariance, positive) = abs_diff(
+            sat_mul_u128(n, sums.sum_xy as u128),
+            sat_mul_u128(sums.sum_x as u128, sums.sum_y as u128),
+        );
+        let (variance_x, _) = abs_diff(
+            sat_mul_u128(n, sums.sum_x2 as u128),
+            sat_mul_u128(sums.sum_x as u128, sums.sum_x as u128),
+        );
+        let (variance_y, _) = abs_diff(
+            sat_mul_u128(n, sums.sum_y2 as u128),
+            sat_mul_u128(sums.sum_y as u128, sums.sum_y as u128),
+        );
+        let r_capped = correlation(covariance, variance_x, variance_y, STAT_SCALE);
+
+        // t = r * sqrt((n - 2) / (1 - r²)), all at STAT_SCALE
+        let enough = (n > 2u128) as u128;
+        let degrees_of_freedom = (n * enough + 2u128 * (1u128 - enough)) - 2u128;
+        let one_minus_r2 = STAT_SCALE * STAT_SCALE - r_capped * r_capped;
+        let one_minus_r2_zero = (one_minus_r2 == 0u128) as u128;
+        // r² · STAT_SCALE² is at most 10^16, so only the degrees of freedom can push this past u128
+        let t_squared = sat_mul_u128(r_capped * r_capped * STAT_SCALE * STAT_SCALE, degrees_of_freedom) / (one_minus_r2 + one_minus_r2_zero);
+        let t_scaled = isqrt(t_squared);
+        let t_max = u32::MAX as u128;
+        let t_saturated = t_scaled * ((t_scaled <= t_max) as u128) + t_max * ((t_scaled > t_max) as u128);
+
+        let signed_offset = (positive as u128) * (STAT_SCALE + r_capped) + (1u128 - positive as u128) * (STAT_SCALE - r_capped);
+
+        ResearchInsights {
+            sample_size: clamp_to_u32(sums.n),
+            correlation_plus_one: signed_offset as u32,
+            t_statistic: t_saturated as u32,
+            positive_correlation: positive,
+        }
+    }
+
+    // ✅ NEW: Start empty correlation sums owned by the MXE
+    #[instruction]
+    pub fn init_correlation_sums(mxe: Mxe) -> Enc<Mxe, RunningSums> {
+        let sums = RunningSums {
+            n: 0u64,
+            sum_x: 0u64,
+            sum_y: 0u64,
+            sum_xy: 0u64,
+            sum_x2: 0u64,
+            sum_y2: 0u64,
+        };
+        mxe.from_arcis(sums)
     }
 
     // ✅ IMPLEMENTED: Analytics computation with stakeholder-specific results
@@ -35,21 +111,27 @@ mod circuits {
         response1: Enc<Shared, u32>,
         response2: Enc<Shared, u32>,
         survey_type: Enc<Mxe, u32>,  // Only MXE knows survey type for proper analysis
+        running_sums: Enc<Mxe, RunningSums>, // Correlation state across respondents
+        report_decimals: u8,         // Public precision for averages (0-4 decimal places)
         data_analyst: Shared,        // Re-encrypt comprehensive analytics for data analyst
         business_stakeholder: Shared, // Re-encrypt business metrics for stakeholders
         researcher: Shared          // Re-encrypt research insights for researchers
-    ) -> (Enc<Shared, ComprehensiveAnalytics>, Enc<Shared, BusinessMetrics>, Enc<Shared, ResearchInsights>) {
-        // Decrypt inputs
-        let r1 = response1.to_arcis();
-        let r2 = response2.to_arcis();
+    ) -> (Enc<Shared, ComprehensiveAnalytics>, Enc<Shared, BusinessMetrics>, Enc<Shared, ResearchInsights>, Enc<Mxe, RunningSums>) {
+        // Decrypt inputs; skipped questions (NOT_ANSWERED) contribute nothing rather than a huge value
+        let raw1 = response1.to_arcis();
+        let raw2 = response2.to_arcis();
+        let answered1 = (raw1 != NOT_ANSWERED) as u32;
+        let answered2 = (raw2 != NOT_ANSWERED) as u32;
+        let r1 = raw1 * answered1;
+        let r2 = raw2 * answered2;
         let st = survey_type.to_arcis();
         
         // Compute comprehensive analytics for data analysts
         let comprehensive_analytics = ComprehensiveAnalytics {
-            response_trends: r1 + r2,
-            user_engagement: (r1 + r2) * 2u32,
-            content_effectiveness: r1 * r2,
-            demographic_insights: st + r1 + r2,
+            response_trends: sat_add_u32(r1, r2),
+            user_engagement: sat_mul_u32(sat_add_u32(r1, r2), 2u32),
+            content_effectiveness: sat_mul_u32(r1, r2),
+            demographic_insights: sat_add_u32(st, sat_add_u32(r1, r2)),
         };
         
         // Compute business metrics for stakeholders using constant-time operations
@@ -63,29 +145,33 @@ mod circuits {
         
         let business_metrics = BusinessMetrics {
             conversion_rate,
-            user_satisfaction: (r1 + r2) * 10u32,
+            user_satisfaction: clamp_to_u32(div_round((r1 as u64) + (r2 as u64), (answered1 + answered2) as u64, scale_for(report_decimals))),
             retention_metrics,
-            growth_indicators: r1 + r2 + st,
+            growth_indicators: sat_add_u32(sat_add_u32(r1, r2), st),
         };
         
-        // Compute research insights for researchers using constant-time operations
-        let high_total = ((r1 + r2) > 10u32) as u32;
-        let statistical_significance = 80u32 + (high_total * 15u32); // 80 + (1 * 15) = 95, or 80 + (0 * 15) = 80
-        
-        let survey_type_2 = (st == 2u32) as u32;
-        
-        let research_insights = ResearchInsights {
-            statistical_significance,
-            correlation_analysis: r1 * r2,
-            predictive_models: (r1 + r2) * 3u32,
-            research_recommendations: survey_type_2,
-        };
+        // Fold this respondent into the running sums (only if both questions were answered)
+        let both = (answered1 * answered2) as u64;
+        let x = r1 as u64;
+        let y = r2 as u64;
+        let mut sums = running_sums.to_arcis();
+        // x, y < 2^32, so each product fits in u64; only the running totals can saturate
+        sums.n = sat_add_u64(sums.n, both);
+        sums.sum_x = sat_add_u64(sums.sum_x, x * both);
+        sums.sum_y = sat_add_u64(sums.sum_y, y * both);
+        sums.sum_xy = sat_add_u64(sums.sum_xy, x * y * both);
+        sums.sum_x2 = sat_add_u64(sums.sum_x2, x * x * both);
+        sums.sum_y2 = sat_add_u64(sums.sum_y2, y * y * both);
+
+        // Compute research insights for researchers from the updated sums
+        let research_insights = correlation_insights(&sums);
         
         // Re-encrypt results for different stakeholders
         let analyst_result = data_analyst.from_arcis(comprehensive_analytics);
         let business_result = business_stakeholder.from_arcis(business_metrics);
         let research_result = researcher.from_arcis(research_insights);
+        let sums_result = running_sums.owner.from_arcis(sums);
         
-        (analyst_result, business_result, research_result)
+        (analyst_result, business_result, research_result, sums_result)
     }
 }
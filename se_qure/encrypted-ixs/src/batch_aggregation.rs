@@ -0,0 +1,106 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+    use crate::fixed_point::{div_round, scale_for};
+    use crate::safe_math::{clamp_to_u32, sat_add_u32, sat_add_u64};
+
+    // Sentinel the client encrypts for questions hidden by skip logic (see NOT_ANSWERED in the program)
+    const NOT_ANSWERED: u32 = u32::MAX;
+
+    // Responses per batch (must match RESPONSE_BATCH_SIZE in the program)
+    const RESPONSE_BATCH_SIZE: usize = 4;
+
+    // Running totals over every batched response, kept encrypted under the MXE
+    #[derive(Copy, Clone)]
+    pub struct BatchTotals {
+        pub responses: u32,
+        pub answered_questions: u32,
+        pub answer_sum: u64,       // Over answered questions only
+        pub completion_sum: u64,
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct BatchAnalytics {
+        pub total_responses: u32,
+        pub answered_questions: u32,
+        pub average_rating: u32,      // Scaled by 10^report_decimals
+        pub average_completion: u32,  // Same scale
+    }
+
+    // ✅ NEW: Start empty batch totals owned by the MXE
+    #[instruction]
+    pub fn init_batch_totals(mxe: Mxe) -> Enc<Mxe, BatchTotals> {
+        let totals = BatchTotals {
+            responses: 0u32,
+            answered_questions: 0u32,
+            answer_sum: 0u64,
+            completion_sum: 0u64,
+        };
+        mxe.from_arcis(totals)
+    }
+
+    // ✅ NEW: Fold up to RESPONSE_BATCH_SIZE buffered responses into the totals
+    //
+    // Each response is the same [answer1, answer2, question_type1, question_type2,
+    // total_responses, completion_rate] array survey_analytics takes. Slots from
+    // batch_size on are padding and ignored. batch_number is handed back so the
+    // program can tell this batch's output from a stale retry.
+    #[instruction]
+    pub fn process_response_batch(
+        response0: Enc<Shared, [u32; 6]>,
+        response1: Enc<Shared, [u32; 6]>,
+        response2: Enc<Shared, [u32; 6]>,
+        response3: Enc<Shared, [u32; 6]>,
+        batch_size: u8,
+        batch_number: u32,
+        totals: Enc<Mxe, BatchTotals>,
+    ) -> (Enc<Mxe, BatchTotals>, u32) {
+        let responses: [[u32; 6]; RESPONSE_BATCH_SIZE] = [
+            response0.to_arcis(),
+            response1.to_arcis(),
+            response2.to_arcis(),
+            response3.to_arcis(),
+        ];
+        let mut state = totals.to_arcis();
+
+        for slot in 0..RESPONSE_BATCH_SIZE {
+            let data = responses[slot];
+            let used = (slot as u8) < batch_size;
+
+            let answered1 = used && data[0] != NOT_ANSWERED;
+            let answered2 = used && data[1] != NOT_ANSWERED;
+            let v1 = if answered1 { data[0] as u64 } else { 0u64 };
+            let v2 = if answered2 { data[1] as u64 } else { 0u64 };
+            let completion = if used { data[5] as u64 } else { 0u64 };
+
+            state.responses = sat_add_u32(state.responses, used as u32);
+            state.answered_questions = sat_add_u32(state.answered_questions, answered1 as u32 + answered2 as u32);
+            state.answer_sum = sat_add_u64(state.answer_sum, v1 + v2);
+            state.completion_sum = sat_add_u64(state.completion_sum, completion);
+        }
+
+        (totals.owner.from_arcis(state), batch_number)
+    }
+
+    // ✅ NEW: Re-encrypt averages over all processed batches for the survey creator
+    #[instruction]
+    pub fn reveal_batch_analytics(
+        totals: Enc<Mxe, BatchTotals>,
+        report_decimals: u8,
+        creator: Shared,
+    ) -> Enc<Shared, BatchAnalytics> {
+        let state = totals.to_arcis();
+        let scale = scale_for(report_decimals);
+
+        let analytics = BatchAnalytics {
+            total_responses: state.responses,
+            answered_questions: state.answered_questions,
+            average_rating: clamp_to_u32(div_round(state.answer_sum, state.answered_questions as u64, scale)),
+            average_completion: clamp_to_u32(div_round(state.completion_sum, state.responses as u64, scale)),
+        };
+
+        creator.from_arcis(analytics)
+    }
+}
@@ -0,0 +1,109 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+    use crate::safe_math::sat_add_u32;
+
+    // Encrypted answer value for questions hidden by skip logic (must match the program)
+    const NOT_ANSWERED: u32 = u32::MAX;
+
+    // Must match MAX_CROSSTAB_CATEGORIES in the se_qure program
+    const MAX_CROSSTAB_CATEGORIES: usize = 8;
+
+    // Revealed in place of a count that is too small to publish
+    const SUPPRESSED: u32 = u32::MAX;
+
+    // Contingency table, row-major: row = category of question A, column = category of question B
+    #[derive(Copy, Clone)]
+    pub struct CrosstabTable {
+        pub cells: [u32; MAX_CROSSTAB_CATEGORIES * MAX_CROSSTAB_CATEGORIES],
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct CrosstabReport {
+        pub cells: [u32; MAX_CROSSTAB_CATEGORIES * MAX_CROSSTAB_CATEGORIES],
+        pub suppressed_cells: u32,
+    }
+
+    // Category index of a raw answer, or MAX_CROSSTAB_CATEGORIES if it falls outside the question
+    fn category_index(answer: u32, offset: u32, categories: u8) -> u32 {
+        let answered = (answer != NOT_ANSWERED) as u32;
+        let below = (answer < offset) as u32;
+        // Clamp before subtracting so the shift can't underflow
+        let shifted = answer * (1u32 - below) + offset * below - offset;
+        let valid = answered * (1u32 - below) * (shifted < categories as u32) as u32;
+        shifted * valid + (MAX_CROSSTAB_CATEGORIES as u32) * (1u32 - valid)
+    }
+
+    // ✅ NEW: Start an empty contingency table owned by the MXE
+    #[instruction]
+    pub fn init_crosstab(mxe: Mxe) -> Enc<Mxe, CrosstabTable> {
+        let table = CrosstabTable {
+            cells: [0u32; MAX_CROSSTAB_CATEGORIES * MAX_CROSSTAB_CATEGORIES],
+        };
+        mxe.from_arcis(table)
+    }
+
+    // ✅ NEW: Count one respondent's (A, B) answer pair
+    //
+    // Answers arrive raw (option index for MultipleChoice, value for Rating) and are
+    // shifted by the public per-question offset. A pair where either side was skipped
+    // or out of range counts nowhere.
+    #[instruction]
+    pub fn accumulate_crosstab_response(
+        answers: Enc<Shared, [u32; 2]>,
+        offset_a: u32,
+        categories_a: u8,
+        offset_b: u32,
+        categories_b: u8,
+        table: Enc<Mxe, CrosstabTable>,
+    ) -> Enc<Mxe, CrosstabTable> {
+        let pair = answers.to_arcis();
+        let mut state = table.to_arcis();
+
+        let row = category_index(pair[0], offset_a, categories_a);
+        let column = category_index(pair[1], offset_b, categories_b);
+
+        for r in 0..MAX_CROSSTAB_CATEGORIES {
+            let row_hit = (row == r as u32) as u32;
+            for c in 0..MAX_CROSSTAB_CATEGORIES {
+                let hit = row_hit * (column == c as u32) as u32;
+                let cell = r * MAX_CROSSTAB_CATEGORIES + c;
+                state.cells[cell] = sat_add_u32(state.cells[cell], hit);
+            }
+        }
+
+        table.owner.from_arcis(state)
+    }
+
+    // ✅ NEW: Reveal the table to the creator with small-cell suppression
+    //
+    // Any non-zero cell below `suppression_threshold` is replaced by SUPPRESSED.
+    // Row/column totals are deliberately not revealed: together with the
+    // published cells they would let the suppressed counts be recomputed.
+    #[instruction]
+    pub fn reveal_crosstab(
+        table: Enc<Mxe, CrosstabTable>,
+        suppression_threshold: u32,
+        creator: Shared,
+    ) -> Enc<Shared, CrosstabReport> {
+        let state = table.to_arcis();
+
+        let mut cells = [0u32; MAX_CROSSTAB_CATEGORIES * MAX_CROSSTAB_CATEGORIES];
+        let mut suppressed_cells = 0u32;
+        for i in 0..(MAX_CROSSTAB_CATEGORIES * MAX_CROSSTAB_CATEGORIES) {
+            let count = state.cells[i];
+            let suppress = ((count > 0u32) && (count < suppression_threshold)) as u32;
+            cells[i] = count * (1u32 - suppress) + SUPPRESSED * suppress;
+            suppressed_cells = suppressed_cells + suppress;
+        }
+
+        let report = CrosstabReport {
+            cells,
+            suppressed_cells,
+        };
+
+        creator.from_arcis(report)
+    }
+}
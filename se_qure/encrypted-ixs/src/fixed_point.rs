@@ -0,0 +1,180 @@
+use arcis_imports::*;
+
+// Shared fixed-point helpers for the circuits.
+//
+// A value with `decimals` places is carried as an integer scaled by 10^decimals
+// (e.g. 4.25 at 2 decimals is 425). The precision is always public, so only the
+// scaled values themselves are secret.
+#[encrypted]
+pub mod fixed_point {
+    use arcis_imports::*;
+    use crate::safe_math::{sat_mul_u128, sat_mul_u64};
+
+    // Largest supported precision: scale 10^4
+    pub const MAX_DECIMALS: u8 = 4;
+
+    // 10^decimals, capped at MAX_DECIMALS. `decimals` is plaintext.
+    pub fn scale_for(decimals: u8) -> u64 {
+        let mut scale = 1u64;
+        for i in 0..MAX_DECIMALS {
+            if i < decimals {
+                scale = scale * 10u64;
+            }
+        }
+        scale
+    }
+
+    // numerator / denominator at `scale`, rounded half up; 0 when denominator is 0.
+    // Computed in u128 and clamped at u64::MAX, so no input can overflow.
+    pub fn div_round(numerator: u64, denominator: u64, scale: u64) -> u64 {
+        // Constant-time division by zero protection
+        let is_zero = (denominator == 0u64) as u128;
+        let safe_denominator = (denominator as u128) + is_zero;
+        // (2^64 - 1)^2 + 2^63 still fits in u128
+        let quotient = ((numerator as u128) * (scale as u128) + safe_denominator / 2u128) / safe_denominator;
+        clamp_u64(quotient * (1u128 - is_zero))
+    }
+
+    // part / whole as a percentage at `scale` (50% at 2 decimals is 5000)
+    pub fn percentage(part: u64, whole: u64, scale: u64) -> u64 {
+        div_round(sat_mul_u64(part, 100u64), whole, scale)
+    }
+
+    // Product of two values already at `scale`, rounded back to `scale`
+    pub fn mul(a: u64, b: u64, scale: u64) -> u64 {
+        let safe_scale = (scale as u128) + (scale == 0u64) as u128;
+        clamp_u64(((a as u128) * (b as u128) + safe_scale / 2u128) / safe_scale)
+    }
+
+    // Convert a value from one scale to another, rounded half up
+    pub fn rescale(value: u64, from_scale: u64, to_scale: u64) -> u64 {
+        div_round(value, from_scale, to_scale)
+    }
+
+    // u128 intermediate back to u64, saturating
+    fn clamp_u64(value: u128) -> u64 {
+        let over = (value > u64::MAX as u128) as u128;
+        (value * (1u128 - over) + (u64::MAX as u128) * over) as u64
+    }
+
+    // Integer square root (floor), digit-by-digit with a fixed number of rounds
+    pub fn isqrt(value: u128) -> u128 {
+        let mut remainder = value;
+        let mut root = 0u128;
+        let mut bit = 1u128 << 126;
+        for _ in 0..64 {
+            let candidate = root + bit;
+            let take = (remainder >= candidate) as u128;
+            remainder = remainder - candidate * take;
+            root = (root >> 1) + bit * take;
+            bit = bit >> 2;
+        }
+        root
+    }
+
+    // |r| at `scale` (capped at 1.0) from the n²-scaled moments
+    // nΣxy − ΣxΣy, nΣx² − (Σx)² and nΣy² − (Σy)²; 0 when either variance is 0
+    pub fn correlation(covariance: u128, variance_x: u128, variance_y: u128, scale: u128) -> u128 {
+        // sqrt(vx * vy) taken as sqrt(vx) * sqrt(vy) so the product can't overflow u128;
+        // each side is pre-scaled by scale² to keep the digits through the truncation
+        let denominator = sat_mul_u128(
+            isqrt(sat_mul_u128(variance_x, scale * scale)),
+            isqrt(sat_mul_u128(variance_y, scale * scale)),
+        );
+        let denominator_zero = (denominator == 0u128) as u128;
+        let r_scaled = sat_mul_u128(covariance, scale * scale * scale) / (denominator + denominator_zero);
+        // Rounding in the square roots can push |r| a hair past 1
+        r_scaled * ((r_scaled <= scale) as u128) + scale * ((r_scaled > scale) as u128)
+    }
+
+    // |a - b| without underflow, plus whether a >= b
+    pub fn abs_diff(a: u128, b: u128) -> (u128, bool) {
+        let a_ge_b = a >= b;
+        let ge = a_ge_b as u128;
+        let larger = a * ge + b * (1u128 - ge);
+        let smaller = b * ge + a * (1u128 - ge);
+        (larger - smaller, a_ge_b)
+    }
+}
+
+pub use fixed_point::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_for_caps_at_max_decimals() {
+        assert_eq!(scale_for(0), 1);
+        assert_eq!(scale_for(2), 100);
+        assert_eq!(scale_for(MAX_DECIMALS), 10_000);
+        assert_eq!(scale_for(MAX_DECIMALS + 3), 10_000);
+    }
+
+    #[test]
+    fn test_div_round_half_up() {
+        for numerator in 0..200u64 {
+            for denominator in 1..20u64 {
+                for scale in [1u64, 10, 100, 10_000] {
+                    let exact = numerator * scale * 2;
+                    let expected = (exact + denominator) / (2 * denominator);
+                    assert_eq!(div_round(numerator, denominator, scale), expected);
+                }
+            }
+        }
+        assert_eq!(div_round(5, 0, 100), 0);
+        assert_eq!(div_round(u64::MAX, 1, 10_000), u64::MAX);
+        assert_eq!(div_round(u64::MAX, u64::MAX, 10_000), 10_000);
+    }
+
+    #[test]
+    fn test_percentage_and_rescale() {
+        assert_eq!(percentage(1, 2, 100), 5_000);
+        assert_eq!(percentage(1, 3, 100), 3_333);
+        assert_eq!(percentage(2, 3, 100), 6_667);
+        assert_eq!(percentage(3, 0, 100), 0);
+        assert_eq!(percentage(u64::MAX, 1, 1), u64::MAX);
+
+        assert_eq!(rescale(425, 100, 10), 43);
+        assert_eq!(rescale(42, 10, 10_000), 42_000);
+    }
+
+    #[test]
+    fn test_mul_rounds_back_to_scale() {
+        // 1.5 * 2.25 = 3.375 -> 3.38 at 2 decimals
+        assert_eq!(mul(150, 225, 100), 338);
+        assert_eq!(mul(7, 9, 1), 63);
+        assert_eq!(mul(7, 9, 0), 63);
+        assert_eq!(mul(u64::MAX, u64::MAX, 1), u64::MAX);
+    }
+
+    #[test]
+    fn test_isqrt_is_floor() {
+        let mut values: Vec<u128> = (0..2_000).collect();
+        values.extend([u64::MAX as u128, (u64::MAX as u128) * (u64::MAX as u128), u128::MAX - 1, u128::MAX]);
+        for value in values {
+            let root = isqrt(value);
+            assert!(root * root <= value, "{}", value);
+            assert!(root == u64::MAX as u128 || (root + 1) * (root + 1) > value, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_correlation() {
+        // x = y = [1, 2, 3]: every moment is 3·14 − 6² = 6
+        assert_eq!(correlation(6, 6, 6, 10_000), 10_000);
+        // Half the covariance of a perfect fit
+        assert_eq!(correlation(3, 6, 6, 10_000), 5_000);
+        // A constant x has no variance, and so no covariance either
+        assert_eq!(correlation(0, 0, 6, 10_000), 0);
+        assert!(correlation(u128::MAX, u128::MAX, u128::MAX, 10_000) <= 10_000);
+    }
+
+    #[test]
+    fn test_abs_diff() {
+        assert_eq!(abs_diff(7, 3), (4, true));
+        assert_eq!(abs_diff(3, 7), (4, false));
+        assert_eq!(abs_diff(5, 5), (0, true));
+        assert_eq!(abs_diff(0, u128::MAX), (u128::MAX, false));
+    }
+}
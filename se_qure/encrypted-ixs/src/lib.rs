@@ -1,5 +1,17 @@
+// Shared fixed-point and overflow-safe helpers used by the circuits
+pub mod fixed_point;
+pub mod safe_math;
+
 // Include all circuit modules
 pub mod survey_analytics;
 pub mod quiz_evaluation;
 pub mod analytics_computation;
 pub mod quiz_threshold_check;
+pub mod matrix_aggregation;
+pub mod ranking_aggregation;
+pub mod nps_computation;
+pub mod percentile_computation;
+pub mod crosstab_computation;
+pub mod poll_tally;
+pub mod text_response_stats;
+pub mod batch_aggregation;
@@ -0,0 +1,67 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+    use crate::safe_math::sat_add_u32;
+
+    // Must match MAX_MATRIX_ROWS / MAX_MATRIX_COLUMNS in the se_qure program
+    const MAX_MATRIX_ROWS: usize = 5;
+    const MAX_MATRIX_COLUMNS: usize = 5;
+
+    // Running per-cell counts for one Likert grid question, row-major
+    #[derive(Copy, Clone)]
+    pub struct MatrixTally {
+        pub cell_counts: [u32; MAX_MATRIX_ROWS * MAX_MATRIX_COLUMNS],
+        pub respondents: u32,
+    }
+
+    // ✅ NEW: Start an empty tally owned by the MXE
+    #[instruction]
+    pub fn init_matrix_tally(mxe: Mxe) -> Enc<Mxe, MatrixTally> {
+        let tally = MatrixTally {
+            cell_counts: [0u32; MAX_MATRIX_ROWS * MAX_MATRIX_COLUMNS],
+            respondents: 0u32,
+        };
+        mxe.from_arcis(tally)
+    }
+
+    // ✅ NEW: Fold one respondent's grid into the tally
+    //
+    // `response[row]` is the chosen column for that row. Rows the respondent skipped
+    // (or sent an out-of-range column for) simply don't land in any cell, so the
+    // circuit never branches on the answer itself.
+    #[instruction]
+    pub fn accumulate_matrix_response(
+        response: Enc<Shared, [u8; MAX_MATRIX_ROWS]>,
+        row_count: u8,    // Public question shape from the survey account
+        column_count: u8,
+        tally: Enc<Mxe, MatrixTally>,
+    ) -> Enc<Mxe, MatrixTally> {
+        let answers = response.to_arcis();
+        let mut state = tally.to_arcis();
+
+        for row in 0..MAX_MATRIX_ROWS {
+            let row_in_shape = ((row as u8) < row_count) as u32;
+            for column in 0..MAX_MATRIX_COLUMNS {
+                let column_in_shape = ((column as u8) < column_count) as u32;
+                let hit = (answers[row] == column as u8) as u32;
+                let cell = row * MAX_MATRIX_COLUMNS + column;
+                state.cell_counts[cell] = sat_add_u32(state.cell_counts[cell], hit * row_in_shape * column_in_shape);
+            }
+        }
+        state.respondents = sat_add_u32(state.respondents, 1u32);
+
+        tally.owner.from_arcis(state)
+    }
+
+    // ✅ NEW: Re-encrypt the aggregate counts for the survey creator
+    #[instruction]
+    pub fn reveal_matrix_tally(
+        tally: Enc<Mxe, MatrixTally>,
+        creator: Shared,
+    ) -> Enc<Shared, MatrixTally> {
+        let state = tally.to_arcis();
+        creator.from_arcis(state)
+    }
+}
@@ -0,0 +1,105 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+    use crate::safe_math::{clamp_to_u32, sat_add_u32};
+
+    // Encrypted answer value for questions hidden by skip logic (must match the program)
+    const NOT_ANSWERED: u32 = u32::MAX;
+
+    // Standard NPS buckets on a 0-10 scale
+    const PROMOTER_MIN: u32 = 9;
+    const DETRACTOR_MAX: u32 = 6;
+    const NPS_SCALE_MAX: u32 = 10;
+
+    #[derive(Copy, Clone)]
+    pub struct NpsTally {
+        pub promoters: u32,  // 9-10
+        pub passives: u32,   // 7-8
+        pub detractors: u32, // 0-6
+    }
+
+    // What the creator gets back. NPS ranges -100..=100, so it is shifted by +100
+    // to stay unsigned: nps_plus_100 = 100 * (2 * promoters + passives) / total
+    #[derive(Copy, Clone)]
+    pub struct NpsReport {
+        pub threshold_met: bool,
+        pub nps_plus_100: u32,
+        pub total_responses: u32,
+        pub promoters: u32,
+        pub passives: u32,
+        pub detractors: u32,
+    }
+
+    // ✅ NEW: Start an empty NPS tally owned by the MXE
+    #[instruction]
+    pub fn init_nps_tally(mxe: Mxe) -> Enc<Mxe, NpsTally> {
+        let tally = NpsTally {
+            promoters: 0u32,
+            passives: 0u32,
+            detractors: 0u32,
+        };
+        mxe.from_arcis(tally)
+    }
+
+    // ✅ NEW: Bucket one respondent's 0-10 rating
+    //
+    // Same per-respondent encoding as survey_analytics: NOT_ANSWERED (or any
+    // out-of-range value) lands in no bucket.
+    #[instruction]
+    pub fn accumulate_nps_response(
+        response: Enc<Shared, u32>,
+        tally: Enc<Mxe, NpsTally>,
+    ) -> Enc<Mxe, NpsTally> {
+        let rating = response.to_arcis();
+        let mut state = tally.to_arcis();
+
+        let answered = ((rating != NOT_ANSWERED) && (rating <= NPS_SCALE_MAX)) as u32;
+        let is_promoter = (rating >= PROMOTER_MIN) as u32 * answered;
+        let is_detractor = (rating <= DETRACTOR_MAX) as u32 * answered;
+        let is_passive = answered - is_promoter - is_detractor;
+
+        state.promoters = sat_add_u32(state.promoters, is_promoter);
+        state.passives = sat_add_u32(state.passives, is_passive);
+        state.detractors = sat_add_u32(state.detractors, is_detractor);
+
+        tally.owner.from_arcis(state)
+    }
+
+    // ✅ NEW: Reveal NPS to the creator once enough ratings have been counted
+    //
+    // Below `min_responses` every field is zeroed, so a creator can't read a
+    // single respondent's bucket off an early reveal.
+    #[instruction]
+    pub fn reveal_nps(
+        tally: Enc<Mxe, NpsTally>,
+        min_responses: u32,
+        include_buckets: bool,
+        creator: Shared,
+    ) -> Enc<Shared, NpsReport> {
+        let state = tally.to_arcis();
+
+        let total = sat_add_u32(sat_add_u32(state.promoters, state.passives), state.detractors);
+        let threshold_met = total >= min_responses;
+        let met = threshold_met as u32;
+        let show_buckets = met * (include_buckets as u32);
+
+        // Constant-time division by zero protection
+        // Widened: 100 * (2P + Pa) passes u32::MAX long before the counts themselves do
+        let is_zero = (total == 0u32) as u64;
+        let weighted = 2u64 * (state.promoters as u64) + (state.passives as u64);
+        let nps_plus_100 = clamp_to_u32((100u64 * weighted) / ((total as u64) + is_zero));
+
+        let report = NpsReport {
+            threshold_met,
+            nps_plus_100: nps_plus_100 * met,
+            total_responses: total * met,
+            promoters: state.promoters * show_buckets,
+            passives: state.passives * show_buckets,
+            detractors: state.detractors * show_buckets,
+        };
+
+        creator.from_arcis(report)
+    }
+}
@@ -0,0 +1,138 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+    use crate::safe_math::sat_add_u32;
+
+    // Encrypted answer value for questions hidden by skip logic (must match the program)
+    const NOT_ANSWERED: u32 = u32::MAX;
+
+    // Must match HISTOGRAM_BUCKETS in the se_qure program
+    const HISTOGRAM_BUCKETS: usize = 16;
+
+    // Bucket b covers [bucket_min + b * bucket_width, bucket_min + (b + 1) * bucket_width).
+    // Values below the range land in the first bucket, values above it in the last.
+    #[derive(Copy, Clone)]
+    pub struct Histogram {
+        pub counts: [u32; HISTOGRAM_BUCKETS],
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct PercentileReport {
+        pub threshold_met: bool,
+        pub total_responses: u32,
+        pub q1: u32,
+        pub median: u32,
+        pub q3: u32,
+        pub percentile_a: u32,
+        pub percentile_b: u32,
+    }
+
+    // Value at percentile `p` (0-100), interpolated linearly inside its bucket.
+    // Constant-time: every bucket is visited and only the matching one contributes.
+    fn percentile_value(
+        counts: &[u32; HISTOGRAM_BUCKETS],
+        total: u32,
+        p: u32,
+        bucket_min: u32,
+        bucket_width: u32,
+    ) -> u32 {
+        // 1-based rank of the target response (nearest-rank method)
+        let mut rank = (((p as u64) * (total as u64) + 99u64) / 100u64) as u32;
+        rank = rank + (rank == 0u32) as u32;
+
+        let mut value = 0u32;
+        let mut before = 0u32;
+        for b in 0..HISTOGRAM_BUCKETS {
+            let through = sat_add_u32(before, counts[b]);
+            let is_target = ((before < rank) && (rank <= through)) as u32;
+
+            // 0-based position of the rank inside this bucket (non-targets clamp to 0)
+            let reached = rank * is_target + before * (1u32 - is_target);
+            let is_empty = (counts[b] == 0u32) as u32;
+            let position = (reached - before - is_target) as u64;
+            let offset = (((bucket_width as u64) * position) / ((counts[b] + is_empty) as u64)) as u32;
+
+            let lower_edge = bucket_min + (b as u32) * bucket_width;
+            value = value + is_target * (lower_edge + offset);
+            before = through;
+        }
+        value
+    }
+
+    // ✅ NEW: Start an empty histogram owned by the MXE
+    #[instruction]
+    pub fn init_histogram(mxe: Mxe) -> Enc<Mxe, Histogram> {
+        let histogram = Histogram {
+            counts: [0u32; HISTOGRAM_BUCKETS],
+        };
+        mxe.from_arcis(histogram)
+    }
+
+    // ✅ NEW: Drop one encrypted answer into its bucket
+    #[instruction]
+    pub fn accumulate_histogram_response(
+        response: Enc<Shared, u32>,
+        bucket_min: u32,   // Public bucket layout from the storage account
+        bucket_width: u32,
+        histogram: Enc<Mxe, Histogram>,
+    ) -> Enc<Mxe, Histogram> {
+        let value = response.to_arcis();
+        let mut state = histogram.to_arcis();
+
+        let answered = (value != NOT_ANSWERED) as u32;
+
+        // Clamp to the first bucket before subtracting so the offset can't underflow
+        let below = (value < bucket_min) as u32;
+        let clamped = value * (1u32 - below) + bucket_min * below;
+        let raw_index = (clamped - bucket_min) / bucket_width;
+
+        let last = (HISTOGRAM_BUCKETS - 1) as u32;
+        let over = (raw_index >= last) as u32;
+        let index = raw_index * (1u32 - over) + last * over;
+
+        for b in 0..HISTOGRAM_BUCKETS {
+            let hit = (index == b as u32) as u32;
+            state.counts[b] = sat_add_u32(state.counts[b], hit * answered);
+        }
+
+        histogram.owner.from_arcis(state)
+    }
+
+    // ✅ NEW: Reveal median, quartiles and two chosen percentiles to the creator
+    //
+    // Only the summary values leave MPC, never the bucket counts, and everything is
+    // zeroed until `min_responses` answers have been counted.
+    #[instruction]
+    pub fn reveal_percentiles(
+        histogram: Enc<Mxe, Histogram>,
+        bucket_min: u32,
+        bucket_width: u32,
+        min_responses: u32,
+        percentile_a: u8,
+        percentile_b: u8,
+        creator: Shared,
+    ) -> Enc<Shared, PercentileReport> {
+        let state = histogram.to_arcis();
+
+        let mut total = 0u32;
+        for b in 0..HISTOGRAM_BUCKETS {
+            total = sat_add_u32(total, state.counts[b]);
+        }
+        let threshold_met = total >= min_responses;
+        let met = threshold_met as u32;
+
+        let report = PercentileReport {
+            threshold_met,
+            total_responses: total * met,
+            q1: percentile_value(&state.counts, total, 25u32, bucket_min, bucket_width) * met,
+            median: percentile_value(&state.counts, total, 50u32, bucket_min, bucket_width) * met,
+            q3: percentile_value(&state.counts, total, 75u32, bucket_min, bucket_width) * met,
+            percentile_a: percentile_value(&state.counts, total, percentile_a as u32, bucket_min, bucket_width) * met,
+            percentile_b: percentile_value(&state.counts, total, percentile_b as u32, bucket_min, bucket_width) * met,
+        };
+
+        creator.from_arcis(report)
+    }
+}
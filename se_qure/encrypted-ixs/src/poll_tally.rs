@@ -0,0 +1,253 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+    use crate::safe_math::sat_add_u32;
+
+    // Upper bound on poll options (must match MAX_POLL_OPTIONS in the program)
+    const MAX_POLL_OPTIONS: usize = 8;
+
+    // Ranked-choice polls (must match MAX_IRV_CANDIDATES / IRV_PROFILES in the program).
+    // A ranked ballot packs its preference order 4 bits per rank, first choice in the
+    // low nibble, with UNRANKED filling the rest: [2, 0] -> 0xFF02.
+    const MAX_IRV_CANDIDATES: usize = 4;
+    const IRV_PROFILES: usize = 64;  // Orderings of 1..=4 distinct candidates: 4 + 12 + 24 + 24
+    const UNRANKED: u32 = 0xF;
+    const RANK_BITS: u32 = 4;
+    const NO_CANDIDATE: u32 = u32::MAX;
+
+    #[derive(Copy, Clone)]
+    pub struct PollTally {
+        pub counts: [u32; MAX_POLL_OPTIONS],
+    }
+
+    // Ballot count per distinct ranking (see ranking_profiles); IRV only needs these
+    #[derive(Copy, Clone)]
+    pub struct RankedTally {
+        pub profile_counts: [u32; IRV_PROFILES],
+    }
+
+    // Eliminated candidate per round (NO_CANDIDATE once decided) and the winner
+    #[derive(Copy, Clone)]
+    pub struct IrvResult {
+        pub winner: u32,
+        pub eliminated: [u32; MAX_IRV_CANDIDATES - 1],
+    }
+
+    // Returned to the voter only: confirms how MPC read the ballot
+    #[derive(Copy, Clone)]
+    pub struct BallotReceipt {
+        pub counted: bool,  // false = spoiled (not a valid option / ranking)
+        pub choice: u32,    // Option index, or packed ranking for ranked ballots
+    }
+
+    // Every ordering of 1..=MAX_IRV_CANDIDATES distinct candidates, padded with UNRANKED
+    fn ranking_profiles() -> [[u32; MAX_IRV_CANDIDATES]; IRV_PROFILES] {
+        let mut profiles = [[UNRANKED; MAX_IRV_CANDIDATES]; IRV_PROFILES];
+        let mut n = 0;
+        for a in 0..MAX_IRV_CANDIDATES as u32 {
+            profiles[n] = [a, UNRANKED, UNRANKED, UNRANKED];
+            n += 1;
+            for b in 0..MAX_IRV_CANDIDATES as u32 {
+                if b != a {
+                    profiles[n] = [a, b, UNRANKED, UNRANKED];
+                    n += 1;
+                    for c in 0..MAX_IRV_CANDIDATES as u32 {
+                        if c != a && c != b {
+                            profiles[n] = [a, b, c, UNRANKED];
+                            n += 1;
+                            let d = 6 - a - b - c;  // The one candidate left
+                            profiles[n] = [a, b, c, d];
+                            n += 1;
+                        }
+                    }
+                }
+            }
+        }
+        profiles
+    }
+
+    fn ranking_code(profile: [u32; MAX_IRV_CANDIDATES]) -> u32 {
+        let mut code = 0u32;
+        for rank in 0..MAX_IRV_CANDIDATES {
+            code = code | (profile[rank] << (RANK_BITS * rank as u32));
+        }
+        code
+    }
+
+    // ✅ NEW: Start an empty poll tally owned by the MXE
+    #[instruction]
+    pub fn init_poll_tally(mxe: Mxe) -> Enc<Mxe, PollTally> {
+        let tally = PollTally {
+            counts: [0u32; MAX_POLL_OPTIONS],
+        };
+        mxe.from_arcis(tally)
+    }
+
+    // ✅ NEW: Add one ballot to the running tally
+    //
+    // The ballot is an option index. Anything outside 0..option_count is spoiled:
+    // it adds nothing, and only the voter's receipt says so.
+    #[instruction]
+    pub fn cast_poll_vote(
+        ballot: Enc<Shared, u32>,
+        tally: Enc<Mxe, PollTally>,
+        option_count: u8,
+        voter: Shared,
+    ) -> (Enc<Mxe, PollTally>, Enc<Shared, BallotReceipt>) {
+        let choice = ballot.to_arcis();
+        let mut state = tally.to_arcis();
+
+        let counted = choice < option_count as u32;
+        for i in 0..MAX_POLL_OPTIONS {
+            let hit = (choice == i as u32) && counted;
+            state.counts[i] = sat_add_u32(state.counts[i], hit as u32);
+        }
+
+        let receipt = BallotReceipt { counted, choice };
+        (tally.owner.from_arcis(state), voter.from_arcis(receipt))
+    }
+
+    // ✅ NEW: Reveal the final per-option counts in plaintext
+    #[instruction]
+    pub fn reveal_poll_tally(tally: Enc<Mxe, PollTally>) -> (u32, [u32; MAX_POLL_OPTIONS]) {
+        let state = tally.to_arcis();
+
+        let mut total = 0u32;
+        let mut counts = [0u32; MAX_POLL_OPTIONS];
+        for i in 0..MAX_POLL_OPTIONS {
+            total = sat_add_u32(total, state.counts[i]);
+            counts[i] = state.counts[i].reveal();
+        }
+
+        (total.reveal(), counts)
+    }
+
+    // ✅ NEW: Start an empty ranked-choice tally owned by the MXE
+    #[instruction]
+    pub fn init_ranked_tally(mxe: Mxe) -> Enc<Mxe, RankedTally> {
+        let tally = RankedTally {
+            profile_counts: [0u32; IRV_PROFILES],
+        };
+        mxe.from_arcis(tally)
+    }
+
+    // ✅ NEW: Add one ranked ballot to the tally
+    //
+    // A ballot must rank at least one candidate, never repeat one, and only name
+    // candidates below option_count; anything else is spoiled.
+    #[instruction]
+    pub fn cast_ranked_ballot(
+        ballot: Enc<Shared, u32>,
+        tally: Enc<Mxe, RankedTally>,
+        option_count: u8,
+        voter: Shared,
+    ) -> (Enc<Mxe, RankedTally>, Enc<Shared, BallotReceipt>) {
+        let ranking = ballot.to_arcis();
+        let mut state = tally.to_arcis();
+        let profiles = ranking_profiles();
+
+        let mut counted = false;
+        for p in 0..IRV_PROFILES {
+            let mut in_range = true;
+            for rank in 0..MAX_IRV_CANDIDATES {
+                let candidate = profiles[p][rank];
+                in_range = in_range && (candidate == UNRANKED || candidate < option_count as u32);
+            }
+            if in_range {
+                let hit = ranking == ranking_code(profiles[p]);
+                state.profile_counts[p] = sat_add_u32(state.profile_counts[p], hit as u32);
+                counted = counted || hit;
+            }
+        }
+
+        let receipt = BallotReceipt { counted, choice: ranking };
+        (tally.owner.from_arcis(state), voter.from_arcis(receipt))
+    }
+
+    // ✅ NEW: Run instant-runoff and reveal only eliminations and the winner
+    //
+    // Each round counts every ballot for its highest-ranked remaining candidate.
+    // A candidate with more than half of the non-exhausted ballots wins; otherwise
+    // the candidate with the fewest votes is eliminated (ties: the higher index
+    // goes). With no valid ballots there is no winner.
+    #[instruction]
+    pub fn run_instant_runoff(
+        tally: Enc<Mxe, RankedTally>,
+        option_count: u8,
+        public_viewer: Shared,
+    ) -> Enc<Shared, IrvResult> {
+        let state = tally.to_arcis();
+        let profiles = ranking_profiles();
+
+        let mut eliminated = [false; MAX_IRV_CANDIDATES];
+        for c in 0..MAX_IRV_CANDIDATES {
+            eliminated[c] = c >= option_count as usize;
+        }
+
+        let mut decided = false;
+        let mut winner = NO_CANDIDATE;
+        let mut eliminations = [NO_CANDIDATE; MAX_IRV_CANDIDATES - 1];
+        let mut any_ballots = false;
+
+        for round in 0..MAX_IRV_CANDIDATES - 1 {
+            if round + 1 < option_count as usize {
+                let mut votes = [0u32; MAX_IRV_CANDIDATES];
+                for p in 0..IRV_PROFILES {
+                    let mut looking = true;
+                    for rank in 0..MAX_IRV_CANDIDATES {
+                        let candidate = profiles[p][rank];
+                        if candidate != UNRANKED {
+                            let c = candidate as usize;
+                            let top = looking && !eliminated[c];
+                            votes[c] = sat_add_u32(votes[c], state.profile_counts[p] * top as u32);
+                            looking = looking && eliminated[c];
+                        }
+                    }
+                }
+
+                let mut active = 0u32;
+                for c in 0..MAX_IRV_CANDIDATES {
+                    active = sat_add_u32(active, votes[c]);
+                }
+                if round == 0 {
+                    any_ballots = active > 0;
+                }
+
+                for c in 0..MAX_IRV_CANDIDATES {
+                    let majority = !decided && (votes[c] as u64) * 2 > active as u64;
+                    winner = if majority { c as u32 } else { winner };
+                    decided = decided || majority;
+                }
+
+                let mut fewest = u32::MAX;
+                let mut loser = 0u32;
+                for c in 0..MAX_IRV_CANDIDATES {
+                    let lower = !eliminated[c] && votes[c] <= fewest;
+                    fewest = if lower { votes[c] } else { fewest };
+                    loser = if lower { c as u32 } else { loser };
+                }
+                for c in 0..MAX_IRV_CANDIDATES {
+                    eliminated[c] = eliminated[c] || (!decided && c as u32 == loser);
+                }
+                eliminations[round] = if decided { NO_CANDIDATE } else { loser };
+            }
+        }
+
+        // Undecided after every round: the last candidate standing wins
+        for c in 0..MAX_IRV_CANDIDATES {
+            let last = !decided && !eliminated[c];
+            winner = if last { c as u32 } else { winner };
+        }
+
+        for round in 0..MAX_IRV_CANDIDATES - 1 {
+            eliminations[round] = if any_ballots { eliminations[round] } else { NO_CANDIDATE };
+        }
+        let result = IrvResult {
+            winner: if any_ballots { winner } else { NO_CANDIDATE },
+            eliminated: eliminations,
+        };
+        public_viewer.from_arcis(result)
+    }
+}
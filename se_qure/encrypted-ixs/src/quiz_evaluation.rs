@@ -3,52 +3,321 @@ use arcis_imports::*;
 #[encrypted]
 mod circuits {
     use arcis_imports::*;
+    use crate::fixed_point::{abs_diff, correlation, div_round, percentage, scale_for};
+    use crate::safe_math::{
+        abs_diff_u32, clamp_to_u32, eq_u32, sat_add_u32, sat_add_u64, sat_mul_u128, sat_mul_u32, sat_mul_u64, sat_sub_u32,
+        select_u32,
+    };
+
+    // Below this percentage (whole percent) students and classes get study recommendations
+    const RECOMMENDATION_THRESHOLD: u64 = 70;
+
+    // Sentinel the client encrypts for skipped questions (see NOT_ANSWERED in the program)
+    const NOT_ANSWERED: u32 = u32::MAX;
+
+    // Grading schemes (must match GRADING_SCHEME_* in the program)
+    const SCHEME_EXACT: u32 = 0;
+    const SCHEME_MULTI_SELECT: u32 = 1;
+    const SCHEME_NUMERIC_TOLERANCE: u32 = 2;
+
+    // Option bits a multi-select answer can carry (MAX_OPTIONS_PER_QUESTION in the program)
+    const MAX_SELECT_OPTIONS: usize = 10;
+
+    // Questions graded per attempt (must match QUIZ_ITEMS in the program)
+    const QUIZ_ITEMS: usize = 2;
+
+    // Fixed-point scale of item difficulty and discrimination (10^4)
+    const ITEM_STAT_SCALE: u128 = 10_000;
+
+    // Leaderboard shape (must match LEADERBOARD_* in the program)
+    const LEADERBOARD_TOP_K: usize = 3;
+    const LEADERBOARD_BUCKETS: usize = 10;
+    // Leaderboard scores are percentages with two decimals (0..=10000); each bucket spans 10%
+    const LEADERBOARD_SCORE_SCALE: u64 = 100;
+    const LEADERBOARD_BUCKET_WIDTH: u32 = 1_000;
+
+    // Place values in the public `answer_shuffle` code (see answer_shuffle_code in the program)
+    const SHUFFLE_BITMASK_BIT: u128 = 2;  // Bits 1..=QUIZ_ITEMS flag MultiSelect questions
+    const SHUFFLE_OPTIONS_BIT: u128 = 8;  // From bit 3: 4 bits per shown option, one group per question
+    const SHUFFLE_NIBBLE: u128 = 16;
+
+    // Question bank size for pooled quizzes (must match MAX_POOL_QUESTIONS in the program)
+    const MAX_POOL_QUESTIONS: usize = 8;
+    // Place values of one packed bank key:
+    // correct (bits 0-31) | tolerance (32-63) | points (64-71) | scheme (72-79) | penalty (80-87)
+    const POOL_KEY_TOLERANCE: u128 = 1 << 32;
+    const POOL_KEY_POINTS: u128 = 1 << 64;
+    const POOL_KEY_SCHEME: u128 = 1 << 72;
+    const POOL_KEY_PENALTY: u128 = 1 << 80;
+    const POOL_KEY_WORD: u128 = 1 << 32;
+    const POOL_KEY_BYTE: u128 = 1 << 8;
+
+    // Adaptive placement grid: step s asks question s * ADAPTIVE_TIERS + tier
+    // (must match ADAPTIVE_* in the program). Tiers run easiest (0) to hardest.
+    const ADAPTIVE_TIERS: usize = 3;
+    const ADAPTIVE_STEPS: usize = 3;
+    const ADAPTIVE_START_TIER: u32 = 1;
+    const NO_NEXT_QUESTION: u32 = u32::MAX;
+
+    // Recent submissions kept for similarity checks (must match SIMILARITY_HISTORY in the program)
+    const SIMILARITY_HISTORY: usize = 8;
 
     // Data structures for quiz evaluation
     #[derive(Copy, Clone)]
     pub struct QuizResult {
         pub score: u32,
         pub total_points: u32,
-        pub percentage: u32,
+        pub percentage: u32,      // Scaled by 10^report_decimals
         pub passed: bool,
         pub feedback: u32, // Encoded feedback message
     }
 
     #[derive(Copy, Clone)]
     pub struct InstructorAnalytics {
-        pub class_average: u32,
-        pub difficulty_rating: u32,
+        pub class_average: u32,      // Percentage scaled by 10^report_decimals
+        pub difficulty_rating: u32,  // Average points per question, same scale
         pub question_analysis: u32,
         pub improvement_suggestions: u32,
     }
 
     #[derive(Copy, Clone)]
     pub struct StudentFeedback {
-        pub personal_score: u32,
+        pub personal_score: u32,     // Percentage scaled by 10^report_decimals
         pub correct_answers: u32,
         pub study_recommendations: u32,
         pub next_quiz_suggestions: u32,
     }
 
+    // Running per-question totals across every graded attempt (item analysis).
+    // Skipped questions count as attempts that weren't answered correctly.
+    #[derive(Copy, Clone)]
+    pub struct ItemStats {
+        pub graded_attempts: u64,
+        pub score_sum: u64,                        // Σ total score
+        pub score_sq_sum: u64,                     // Σ total score²
+        pub answered: [u64; QUIZ_ITEMS],
+        pub correct: [u64; QUIZ_ITEMS],
+        pub correct_score_sum: [u64; QUIZ_ITEMS],  // Σ total score of attempts that got the item right
+    }
+
+    // Only aggregates leave MPC; everything is zeroed below the reveal threshold
+    #[derive(Copy, Clone)]
+    pub struct ItemAnalysisReport {
+        pub threshold_met: bool,
+        pub graded_attempts: u32,
+        pub answered: [u32; QUIZ_ITEMS],
+        pub correct: [u32; QUIZ_ITEMS],
+        pub difficulty: [u32; QUIZ_ITEMS],               // Share of attempts correct × 10^4 (classical p-value)
+        pub discrimination_plus_one: [u32; QUIZ_ITEMS],  // (point-biserial r + 1) × 10^4, so 0..=20000
+    }
+
+    // Every joined attempt lands in one 10% score bucket. Only the best K keep an
+    // exact score, tagged with the pseudonym the student chose (0 = empty slot).
+    // Pseudonyms on the board are unique: record_leaderboard_score turns away a clash.
+    #[derive(Copy, Clone)]
+    pub struct Leaderboard {
+        pub entries: u64,
+        pub buckets: [u64; LEADERBOARD_BUCKETS],
+        pub top_scores: [u32; LEADERBOARD_TOP_K],       // Best first; ties keep the earlier entry ahead
+        pub top_pseudonyms: [u32; LEADERBOARD_TOP_K],
+    }
+
+    // A student's own leaderboard score, kept so they can ask for their rank later
+    #[derive(Copy, Clone)]
+    pub struct LeaderboardEntry {
+        pub score: u32,
+        pub pseudonym: u32,
+    }
+
+    // Rank is bucketed: the student learns how many classmates scored in higher and
+    // lower 10% bands, never anyone's exact score outside the published top K
+    #[derive(Copy, Clone)]
+    pub struct RankReport {
+        pub threshold_met: bool,
+        pub entries: u32,
+        pub rank_best: u32,       // 1 + students in higher buckets
+        pub rank_worst: u32,      // Students in higher buckets + everyone in the student's bucket
+        pub percentile: u32,      // Share of students in lower buckets × 10^4
+        pub top_k_position: u32,  // 1..=K when the student is on the published board, else 0
+    }
+
+    // MXE-owned copy of the latest submissions, oldest first. The per-student answer
+    // accounts are encrypted to keys the program never stores, so this is the only
+    // form in which earlier answers can be compared. Empty slots hold NOT_ANSWERED.
+    #[derive(Copy, Clone)]
+    pub struct AnswerHistory {
+        pub answers: [[u32; QUIZ_ITEMS]; SIMILARITY_HISTORY],
+    }
+
+    // Answer keys for a whole question bank, one packed key per question
+    #[derive(Copy, Clone)]
+    pub struct PoolKeys {
+        pub passing_threshold: u32,
+        pub keys: [u128; MAX_POOL_QUESTIONS],
+    }
+
+    // Packed keys (same layout as PoolKeys) for every question in the adaptive grid
+    #[derive(Copy, Clone)]
+    pub struct AdaptiveKeys {
+        pub keys: [u128; ADAPTIVE_STEPS * ADAPTIVE_TIERS],
+    }
+
+    // Per-attempt progress; never leaves MPC until the attempt is finished
+    #[derive(Copy, Clone)]
+    pub struct AdaptiveState {
+        pub score: u32,
+        pub tier: u32,
+        pub correct_answers: u32,
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct AdaptiveResult {
+        pub score: u32,
+        pub final_tier: u32,      // Placement: tier the student ended on
+        pub correct_answers: u32,
+    }
+
+    // (points awarded, points deducted, fully correct) for one question.
+    // Every scheme is evaluated and the encrypted `scheme` selects one, so the
+    // circuit never branches on how a question is graded. Skipped questions
+    // earn and lose nothing.
+    fn grade_question(answer: u32, key: u32, points: u32, scheme: u32, tolerance: u32, penalty: u32) -> (u32, u32, u32) {
+        let answered = (answer != NOT_ANSWERED) as u32;
+
+        // Exact and numeric tolerance: all-or-nothing
+        let exact_hit = eq_u32(answer, key);
+        let tolerance_hit = (abs_diff_u32(answer, key) <= tolerance) as u32;
+
+        // Multi-select: answer and key are option bitmasks (bit i = option i)
+        let mut key_options = 0u32;
+        let mut hits = 0u32;
+        let mut wrong_picks = 0u32;
+        let mut bit = 1u32;
+        for _ in 0..MAX_SELECT_OPTIONS {
+            let in_key = (key / bit) % 2u32;
+            let picked = ((answer / bit) % 2u32) * answered;
+            key_options = key_options + in_key;
+            hits = hits + in_key * picked;
+            wrong_picks = wrong_picks + (1u32 - in_key) * picked;
+            bit = bit * 2u32;
+        }
+        // Points are split evenly across the correct options (rounded down)
+        let safe_key_options = key_options + (key_options == 0u32) as u32;
+        let partial_credit = (((points as u64) * (hits as u64)) / (safe_key_options as u64)) as u32;
+        let multi_full = eq_u32(hits, key_options) * eq_u32(wrong_picks, 0u32) * answered;
+
+        let is_exact = eq_u32(scheme, SCHEME_EXACT);
+        let is_multi = eq_u32(scheme, SCHEME_MULTI_SELECT);
+        let is_tolerance = eq_u32(scheme, SCHEME_NUMERIC_TOLERANCE);
+
+        let single_answered = (is_exact + is_tolerance) * answered;
+        let single_hit = (is_exact * exact_hit + is_tolerance * tolerance_hit) * answered;
+        let single_miss = single_answered - single_hit;
+
+        let awarded = single_hit * points + is_multi * partial_credit;
+        let deducted = sat_add_u32(single_miss * penalty, is_multi * sat_mul_u32(wrong_picks, penalty));
+        let fully_correct = single_hit + is_multi * multi_full;
+
+        (awarded, deducted, fully_correct)
+    }
+
+    // Map answers from the student's shuffled order back to the canonical order the
+    // answer key uses. `answer_shuffle` is public (derived from the attempt PDA):
+    // bit 0 swaps the questions, bits 1-2 mark MultiSelect (bitmask) answers, and each
+    // shown option i carries a nibble holding `i XOR canonical index`. 0 = no shuffle.
+    fn unshuffle_answers(answers: [u32; 2], answer_shuffle: u128) -> [u32; 2] {
+        let swapped = (answer_shuffle % 2u128) as u32;
+        // Answers in canonical question order, still using shown option indices
+        let by_question = [
+            select_u32(swapped, answers[1], answers[0]),
+            select_u32(swapped, answers[0], answers[1]),
+        ];
+
+        let mut canonical = [0u32; QUIZ_ITEMS];
+        let mut group = SHUFFLE_OPTIONS_BIT;
+        for q in 0..QUIZ_ITEMS {
+            let answer = by_question[q];
+            let bitmask_mode = ((answer_shuffle / (SHUFFLE_BITMASK_BIT << q)) % 2u128) as u32;
+
+            let mut in_range = 0u32;
+            let mut mapped_index = 0u32;
+            let mut mapped_mask = 0u32;
+            let mut bit = 1u32;
+            let mut nibble = group;
+            for i in 0..MAX_SELECT_OPTIONS {
+                let target = ((i as u128) ^ ((answer_shuffle / nibble) % SHUFFLE_NIBBLE)) as u32;
+                let is_option = eq_u32(answer, i as u32);
+                in_range = in_range + is_option;
+                mapped_index = mapped_index + is_option * target;
+                mapped_mask = mapped_mask + ((answer / bit) % 2u32) * (1u32 << target);
+                bit = bit * 2u32;
+                nibble = nibble * SHUFFLE_NIBBLE;
+            }
+            // Values that aren't option indices (NOT_ANSWERED, ratings, numbers) pass through
+            let index_answer = mapped_index + (1u32 - in_range) * answer;
+            let mask_answer = select_u32(eq_u32(answer, NOT_ANSWERED), answer, mapped_mask);
+            canonical[q] = select_u32(bitmask_mode, mask_answer, index_answer);
+            group = nibble;
+        }
+        canonical
+    }
+
+    // Key of bank question `index`, selected obliviously so the same circuit works
+    // whether the drawn index is public or encrypted
+    fn pick_pool_key(keys: [u128; MAX_POOL_QUESTIONS], index: u32) -> u128 {
+        let mut picked = 0u128;
+        for i in 0..MAX_POOL_QUESTIONS {
+            picked = picked + ((index == i as u32) as u128) * keys[i];
+        }
+        picked
+    }
+
+    // One-hot bucket of a leaderboard score; 100% falls into the top bucket
+    fn bucket_flags(score: u32) -> [u64; LEADERBOARD_BUCKETS] {
+        let mut flags = [0u64; LEADERBOARD_BUCKETS];
+        for b in 0..LEADERBOARD_BUCKETS {
+            let lower = (b as u32) * LEADERBOARD_BUCKET_WIDTH;
+            let upper = if b + 1 == LEADERBOARD_BUCKETS { u32::MAX } else { lower + LEADERBOARD_BUCKET_WIDTH };
+            flags[b] = ((score >= lower) as u64) * ((score < upper) as u64);
+        }
+        flags
+    }
+
+    // (earned points, total points, correct1, correct2) for one attempt. Shared by
+    // quiz_evaluation and the leaderboard so both rank exactly the same score.
+    fn score_attempt(answers: [u32; 2], qd: [u32; 14]) -> (u32, u32, u32, u32) {
+        let total_points = sat_add_u32(qd[2], qd[3]);
+
+        let (awarded1, deducted1, correct1) = grade_question(answers[0], qd[0], qd[2], qd[8], qd[10], qd[12]);
+        let (awarded2, deducted2, correct2) = grade_question(answers[1], qd[1], qd[3], qd[9], qd[11], qd[13]);
+
+        // Negative marking can cancel earned points but never drives the score below zero
+        let earned = sat_sub_u32(sat_add_u32(awarded1, awarded2), sat_add_u32(deducted1, deducted2));
+
+        (earned, total_points, correct1, correct2)
+    }
+
     // ✅ CONSOLIDATED: All MXE quiz data in ONE array to reduce argument count
     // This reduces args from 26 to 20 and prevents "out of memory" error
     #[instruction]
     pub fn quiz_evaluation(
         user_answers: Enc<Shared, [u32; 2]>,  // Student answers encrypted with same key/nonce [answer1, answer2]
-        quiz_data: Enc<Mxe, [u32; 8]>,        // ALL quiz data in one array: [correct1, correct2, points1, points2, threshold, stat1, stat2, stat3]
+        answer_shuffle: u128,                 // Public per-attempt shuffle (0 = canonical order)
+        quiz_data: Enc<Mxe, [u32; 14]>,       // ALL quiz data in one array: [correct1, correct2, points1, points2, threshold, stat1, stat2, stat3,
+                                              //   scheme1, scheme2, tolerance1, tolerance2, penalty1, penalty2]
+        item_stats: Enc<Mxe, ItemStats>,      // Item analysis totals across all graded attempts
+        report_decimals: u8,                  // Public precision for percentages (0-4 decimal places)
         student: Shared,                      // Re-encrypt result for student
         instructor: Shared,                   // Re-encrypt analytics for instructor
         quiz_creator: Shared                  // Re-encrypt full results for quiz creator
-    ) -> (Enc<Shared, QuizResult>, Enc<Shared, InstructorAnalytics>, Enc<Shared, StudentFeedback>) {
+    ) -> (Enc<Shared, QuizResult>, Enc<Shared, InstructorAnalytics>, Enc<Shared, StudentFeedback>, Enc<Mxe, ItemStats>) {
         // Decrypt inputs
-        let user_ans = user_answers.to_arcis();
+        let user_ans = unshuffle_answers(user_answers.to_arcis(), answer_shuffle);
         let ua1 = user_ans[0];
         let ua2 = user_ans[1];
         
         // Decrypt consolidated quiz data and extract individual values
         let qd = quiz_data.to_arcis();
-        let ca1 = qd[0];  // correct_answer1
-        let ca2 = qd[1];  // correct_answer2
         let p1 = qd[2];   // points1
         let p2 = qd[3];   // points2
         let threshold = qd[4];  // passing_threshold
@@ -56,26 +325,17 @@ mod circuits {
         let total_class_score = qd[6];  // class_stat2
         let completion_count = qd[7];   // class_stat3
         
-        // Calculate score
-        let total_points = p1 + p2;
-        let diff1 = ua1 - ca1;
-        let diff2 = ua2 - ca2;
-        
-        // Use constant-time comparison
-        let is_correct1 = (diff1 == 0u32) as u32;
-        let is_correct2 = (diff2 == 0u32) as u32;
-        
-        let points_earned1 = is_correct1 * p1;
-        let points_earned2 = is_correct2 * p2;
-        let earned_points = points_earned1 + points_earned2;
+        // Calculate score (correct answers, grading schemes and penalties live in qd[0..2] and qd[8..14])
+        let (earned_points, total_points, is_correct1, is_correct2) = score_attempt(user_ans, qd);
         
         // Calculate percentage using constant-time operations to prevent side-channel leaks
+        let scale = scale_for(report_decimals);
+        let final_percentage = clamp_to_u32(percentage(earned_points as u64, total_points as u64, scale));
+        
+        // Compare exactly rather than against the rounded percentage (69.996% must not pass a 70% bar)
         let is_zero = (total_points == 0u32) as u32;
         let safe_divisor = total_points + is_zero; // Ensures divisor is never 0
-        let percentage = (earned_points * 100u32) / safe_divisor;
-        let final_percentage = percentage * (1u32 - is_zero); // Zero out result if total_points was 0
-        
-        let passed = final_percentage >= threshold;
+        let passed = (earned_points as u64) * 100u64 >= (threshold as u64) * (safe_divisor as u64);
         
         // Quiz result for student
         let quiz_result = QuizResult {
@@ -86,40 +346,439 @@ mod circuits {
             feedback: (passed as u32), // 1 = "Great job!", 0 = "Keep studying!"
         };
         
-        // Calculate proper class analytics with aggregation using constant-time operations
-        let completion_is_zero = (completion_count == 0u32) as u32;
-        let students_is_zero = (total_students == 0u32) as u32;
-        
-        let safe_completion_count = completion_count + completion_is_zero;
-        let safe_student_count = total_students + students_is_zero;
-        
-        let class_average_raw = (total_class_score * 100u32) / (safe_completion_count * total_points);
-        let class_average = class_average_raw * (1u32 - completion_is_zero);
-        
-        let class_completion_rate_raw = (completion_count * 100u32) / safe_student_count;
-        let _class_completion_rate = class_completion_rate_raw * (1u32 - students_is_zero);
+        // Calculate proper class analytics with aggregation (zero-safe fixed-point division)
+        let class_average = clamp_to_u32(percentage(
+            total_class_score as u64,
+            (completion_count as u64) * (total_points as u64),
+            scale,
+        ));
+        let _class_completion_rate = clamp_to_u32(percentage(completion_count as u64, total_students as u64, scale));
+        let recommendation_bar = (RECOMMENDATION_THRESHOLD * scale) as u32;
         
         // Analytics for instructor
         let instructor_analytics = InstructorAnalytics {
             class_average, // Properly aggregated across all students
-            difficulty_rating: (p1 + p2) / 2u32,
+            difficulty_rating: clamp_to_u32(div_round((p1 as u64) + (p2 as u64), 2u64, scale)),
             question_analysis: is_correct1 + is_correct2,
-            improvement_suggestions: (class_average < 70u32) as u32,
+            improvement_suggestions: (class_average < recommendation_bar) as u32,
         };
         
         // Personal feedback for student
         let student_feedback = StudentFeedback {
             personal_score: final_percentage,
             correct_answers: is_correct1 + is_correct2,
-            study_recommendations: (final_percentage < 70u32) as u32,
+            study_recommendations: (final_percentage < recommendation_bar) as u32,
             next_quiz_suggestions: (passed as u32),
         };
         
+        // Fold this attempt into the item analysis totals
+        let mut stats = item_stats.to_arcis();
+        let score = earned_points as u64;
+        let answered = [(ua1 != NOT_ANSWERED) as u64, (ua2 != NOT_ANSWERED) as u64];
+        let correct = [is_correct1 as u64, is_correct2 as u64];
+        stats.graded_attempts = sat_add_u64(stats.graded_attempts, 1u64);
+        stats.score_sum = sat_add_u64(stats.score_sum, score);
+        stats.score_sq_sum = sat_add_u64(stats.score_sq_sum, sat_mul_u64(score, score));
+        for i in 0..QUIZ_ITEMS {
+            stats.answered[i] = sat_add_u64(stats.answered[i], answered[i]);
+            stats.correct[i] = sat_add_u64(stats.correct[i], correct[i]);
+            stats.correct_score_sum[i] = sat_add_u64(stats.correct_score_sum[i], score * correct[i]);
+        }
+        
         // Re-encrypt results for different stakeholders
         let student_result = student.from_arcis(quiz_result);
         let instructor_result = instructor.from_arcis(instructor_analytics);
         let creator_result = quiz_creator.from_arcis(student_feedback);
+        let stats_result = item_stats.owner.from_arcis(stats);
         
-        (student_result, instructor_result, creator_result)
+        (student_result, instructor_result, creator_result, stats_result)
+    }
+
+    // ✅ NEW: Start empty item analysis totals owned by the MXE
+    #[instruction]
+    pub fn init_item_stats(mxe: Mxe) -> Enc<Mxe, ItemStats> {
+        let stats = ItemStats {
+            graded_attempts: 0u64,
+            score_sum: 0u64,
+            score_sq_sum: 0u64,
+            answered: [0u64; QUIZ_ITEMS],
+            correct: [0u64; QUIZ_ITEMS],
+            correct_score_sum: [0u64; QUIZ_ITEMS],
+        };
+        mxe.from_arcis(stats)
+    }
+
+    // ✅ NEW: Reveal item difficulty and discrimination to the instructor
+    //
+    // Point-biserial r between "got item i right" and total score:
+    //   r = (N·S1 − C·T) / sqrt(C(N − C) · (N·ΣT² − T²))
+    // with N attempts, C correct, T = Σscore and S1 = Σscore over correct attempts.
+    // Only these per-item aggregates are revealed, never which attempt missed what.
+    #[instruction]
+    pub fn reveal_item_analysis(
+        item_stats: Enc<Mxe, ItemStats>,
+        min_responses: u32,
+        instructor: Shared,
+    ) -> Enc<Shared, ItemAnalysisReport> {
+        let stats = item_stats.to_arcis();
+
+        let n = stats.graded_attempts as u128;
+        let total = stats.score_sum as u128;
+        let threshold_met = stats.graded_attempts >= min_responses as u64;
+        let met = threshold_met as u32;
+
+        let (score_variance, _) = abs_diff(
+            sat_mul_u128(n, stats.score_sq_sum as u128),
+            sat_mul_u128(total, total),
+        );
+
+        let mut answered = [0u32; QUIZ_ITEMS];
+        let mut correct = [0u32; QUIZ_ITEMS];
+        let mut difficulty = [0u32; QUIZ_ITEMS];
+        let mut discrimination_plus_one = [0u32; QUIZ_ITEMS];
+        for i in 0..QUIZ_ITEMS {
+            let c = stats.correct[i] as u128;
+            let (covariance, positive) = abs_diff(
+                sat_mul_u128(n, stats.correct_score_sum[i] as u128),
+                sat_mul_u128(c, total),
+            );
+            // C ≤ N, so the subtraction can't underflow
+            let item_variance = sat_mul_u128(c, n - c);
+            let r = correlation(covariance, item_variance, score_variance, ITEM_STAT_SCALE);
+            let signed = (positive as u128) * (ITEM_STAT_SCALE + r) + (1u128 - positive as u128) * (ITEM_STAT_SCALE - r);
+
+            answered[i] = clamp_to_u32(stats.answered[i]) * met;
+            correct[i] = clamp_to_u32(stats.correct[i]) * met;
+            difficulty[i] = (div_round(stats.correct[i], stats.graded_attempts, ITEM_STAT_SCALE as u64) as u32) * met;
+            discrimination_plus_one[i] = (signed as u32) * met;
+        }
+
+        let report = ItemAnalysisReport {
+            threshold_met,
+            graded_attempts: clamp_to_u32(stats.graded_attempts) * met,
+            answered,
+            correct,
+            difficulty,
+            discrimination_plus_one,
+        };
+
+        instructor.from_arcis(report)
+    }
+
+    // ✅ NEW: Start an empty leaderboard owned by the MXE
+    #[instruction]
+    pub fn init_leaderboard(mxe: Mxe) -> Enc<Mxe, Leaderboard> {
+        let board = Leaderboard {
+            entries: 0u64,
+            buckets: [0u64; LEADERBOARD_BUCKETS],
+            top_scores: [0u32; LEADERBOARD_TOP_K],
+            top_pseudonyms: [0u32; LEADERBOARD_TOP_K],
+        };
+        mxe.from_arcis(board)
+    }
+
+    // ✅ NEW: Grade an attempt again and add it to the leaderboard
+    //
+    // Uses the same score_attempt as quiz_evaluation, so the board can't disagree
+    // with the grade. The entry is encrypted under a fresh MXE nonce (`entry`).
+    #[instruction]
+    pub fn record_leaderboard_score(
+        user_answers: Enc<Shared, [u32; 2]>,
+        answer_shuffle: u128,
+        quiz_data: Enc<Mxe, [u32; 14]>,
+        pseudonym: Enc<Shared, u32>,          // Chosen by the student, shown on the published board
+        leaderboard: Enc<Mxe, Leaderboard>,
+        entry: Mxe,
+    ) -> (bool, Enc<Mxe, Leaderboard>, Enc<Mxe, LeaderboardEntry>) {
+        let (earned_points, total_points, _, _) = score_attempt(
+            unshuffle_answers(user_answers.to_arcis(), answer_shuffle),
+            quiz_data.to_arcis(),
+        );
+        let score = clamp_to_u32(percentage(earned_points as u64, total_points as u64, LEADERBOARD_SCORE_SCALE));
+        let pseudonym = pseudonym.to_arcis();
+
+        let mut board = leaderboard.to_arcis();
+
+        // A pseudonym already on the board (or 0, the empty-slot marker) is turned away
+        // and the board left as it was; only the verdict is revealed, so the student can
+        // retry with another pseudonym
+        let mut clash = eq_u32(pseudonym, 0u32);
+        for i in 0..LEADERBOARD_TOP_K {
+            clash = clash + eq_u32(board.top_pseudonyms[i], pseudonym) * (1u32 - clash);
+        }
+        let accepted = 1u32 - clash;
+
+        board.entries = sat_add_u64(board.entries, accepted as u64);
+        let flags = bucket_flags(score);
+        for b in 0..LEADERBOARD_BUCKETS {
+            board.buckets[b] = sat_add_u64(board.buckets[b], flags[b] * accepted as u64);
+        }
+
+        // Oblivious insertion: carry the new entry down the board and swap it into
+        // every slot it beats, so the board stays sorted without revealing where it landed.
+        // A rejected entry carries score 0, which beats no slot.
+        let mut carry_score = score * accepted;
+        let mut carry_pseudonym = pseudonym;
+        for i in 0..LEADERBOARD_TOP_K {
+            let beats = (carry_score > board.top_scores[i]) as u32;
+            let slot_score = select_u32(beats, carry_score, board.top_scores[i]);
+            let slot_pseudonym = select_u32(beats, carry_pseudonym, board.top_pseudonyms[i]);
+            carry_score = select_u32(beats, board.top_scores[i], carry_score);
+            carry_pseudonym = select_u32(beats, board.top_pseudonyms[i], carry_pseudonym);
+            board.top_scores[i] = slot_score;
+            board.top_pseudonyms[i] = slot_pseudonym;
+        }
+
+        let mine = LeaderboardEntry { score, pseudonym };
+        ((accepted == 1u32).reveal(), leaderboard.owner.from_arcis(board), entry.from_arcis(mine))
+    }
+
+    // ✅ NEW: Re-encrypt a student's rank and percentile for that student only
+    #[instruction]
+    pub fn reveal_my_rank(
+        entry: Enc<Mxe, LeaderboardEntry>,
+        leaderboard: Enc<Mxe, Leaderboard>,
+        min_responses: u32,
+        student: Shared,
+    ) -> Enc<Shared, RankReport> {
+        let mine = entry.to_arcis();
+        let board = leaderboard.to_arcis();
+        let threshold_met = board.entries >= min_responses as u64;
+        let met = threshold_met as u32;
+
+        // `passed` turns 1 once the scan is above the student's bucket
+        let flags = bucket_flags(mine.score);
+        let mut above = 0u64;
+        let mut same = 0u64;
+        let mut below = 0u64;
+        let mut passed = 0u64;
+        for b in 0..LEADERBOARD_BUCKETS {
+            above = sat_add_u64(above, passed * board.buckets[b]);
+            same = sat_add_u64(same, flags[b] * board.buckets[b]);
+            below = sat_add_u64(below, (1u64 - passed - flags[b]) * board.buckets[b]);
+            passed = passed + flags[b];
+        }
+
+        let mut top_k_position = 0u32;
+        let mut found = 0u32;
+        for i in 0..LEADERBOARD_TOP_K {
+            let hit = eq_u32(board.top_scores[i], mine.score)
+                * eq_u32(board.top_pseudonyms[i], mine.pseudonym)
+                * (1u32 - found);
+            top_k_position = top_k_position + hit * ((i as u32) + 1u32);
+            found = found + hit;
+        }
+
+        let report = RankReport {
+            threshold_met,
+            entries: clamp_to_u32(board.entries) * met,
+            rank_best: sat_add_u32(clamp_to_u32(above), 1u32) * met,
+            rank_worst: clamp_to_u32(sat_add_u64(above, same)) * met,
+            percentile: (div_round(below, board.entries, ITEM_STAT_SCALE as u64) as u32) * met,
+            top_k_position: top_k_position * met,
+        };
+
+        student.from_arcis(report)
+    }
+
+    // ✅ NEW: Publish the pseudonymous top K in plaintext
+    //
+    // Only pseudonyms and scores of the best K leave MPC, and nothing at all until
+    // min_responses students have joined.
+    #[instruction]
+    pub fn reveal_leaderboard(
+        leaderboard: Enc<Mxe, Leaderboard>,
+        min_responses: u32,
+    ) -> (bool, [u32; LEADERBOARD_TOP_K], [u32; LEADERBOARD_TOP_K]) {
+        let board = leaderboard.to_arcis();
+        let threshold_met = board.entries >= min_responses as u64;
+        let met = threshold_met as u32;
+
+        let mut pseudonyms = [0u32; LEADERBOARD_TOP_K];
+        let mut scores = [0u32; LEADERBOARD_TOP_K];
+        for i in 0..LEADERBOARD_TOP_K {
+            pseudonyms[i] = (board.top_pseudonyms[i] * met).reveal();
+            scores[i] = (board.top_scores[i] * met).reveal();
+        }
+
+        (threshold_met.reveal(), pseudonyms, scores)
+    }
+
+    // ✅ NEW: Start an empty answer history owned by the MXE
+    #[instruction]
+    pub fn init_answer_history(mxe: Mxe) -> Enc<Mxe, AnswerHistory> {
+        let history = AnswerHistory {
+            answers: [[NOT_ANSWERED; QUIZ_ITEMS]; SIMILARITY_HISTORY],
+        };
+        mxe.from_arcis(history)
+    }
+
+    // ✅ NEW: Count earlier submissions that look like copies of this one
+    //
+    // A prior submission matches when it shares at least `similarity_threshold` of
+    // this submission's wrong answers. Correct answers never count: with two questions,
+    // any two strong students would agree on everything. Skipped questions never count
+    // either. The history holds the last SIMILARITY_HISTORY submissions, so copying is
+    // caught between attempts made close together. Only the number of matches leaves
+    // MPC, encrypted to the instructor.
+    #[instruction]
+    pub fn check_answer_similarity(
+        user_answers: Enc<Shared, [u32; 2]>,
+        answer_shuffle: u128,                 // Compared in canonical order, so shuffling can't hide copying
+        quiz_data: Enc<Mxe, [u32; 14]>,       // Same layout as quiz_evaluation, to tell wrong answers apart
+        history: Enc<Mxe, AnswerHistory>,
+        similarity_threshold: u8,             // Shared wrong answers needed to flag a pair (1..=QUIZ_ITEMS)
+        instructor: Shared,
+    ) -> (Enc<Mxe, AnswerHistory>, Enc<Shared, u32>) {
+        let answers = unshuffle_answers(user_answers.to_arcis(), answer_shuffle);
+        let (_, _, correct1, correct2) = score_attempt(answers, quiz_data.to_arcis());
+        let wrong = [1u32 - correct1, 1u32 - correct2];
+        let mut past = history.to_arcis();
+
+        let mut flagged = 0u32;
+        for s in 0..SIMILARITY_HISTORY {
+            let mut identical = 0u32;
+            for q in 0..QUIZ_ITEMS {
+                let answered = (answers[q] != NOT_ANSWERED) as u32;
+                identical = identical + eq_u32(answers[q], past.answers[s][q]) * answered * wrong[q];
+            }
+            flagged = flagged + (identical >= similarity_threshold as u32) as u32;
+        }
+
+        // Drop the oldest submission and remember this one
+        for s in 0..SIMILARITY_HISTORY - 1 {
+            past.answers[s] = past.answers[s + 1];
+        }
+        past.answers[SIMILARITY_HISTORY - 1] = answers;
+
+        (history.owner.from_arcis(past), instructor.from_arcis(flagged))
+    }
+
+    // ✅ NEW: Grade an attempt whose two questions were drawn from a question bank
+    //
+    // The drawn keys are unpacked into the same [u32; 14] layout as quiz_evaluation's
+    // quiz_data, so both score attempts identically. Class statistics and item
+    // analysis stay with fixed papers, where question positions mean the same thing.
+    #[instruction]
+    pub fn pool_quiz_evaluation(
+        user_answers: Enc<Shared, [u32; 2]>,  // Answers to the drawn questions, in draw order
+        pool_keys: Enc<Mxe, PoolKeys>,
+        drawn1: u8,                           // Bank indices recorded by start_quiz_attempt
+        drawn2: u8,
+        report_decimals: u8,
+        student: Shared,
+        quiz_creator: Shared,
+    ) -> (Enc<Shared, QuizResult>, Enc<Shared, StudentFeedback>) {
+        let bank = pool_keys.to_arcis();
+        let drawn = [drawn1 as u32, drawn2 as u32];
+
+        let mut qd = [0u32; 14];
+        qd[4] = bank.passing_threshold;
+        for q in 0..QUIZ_ITEMS {
+            let key = pick_pool_key(bank.keys, drawn[q]);
+            qd[q] = (key % POOL_KEY_WORD) as u32;                                // correct
+            qd[2 + q] = ((key / POOL_KEY_POINTS) % POOL_KEY_BYTE) as u32;       // points
+            qd[8 + q] = ((key / POOL_KEY_SCHEME) % POOL_KEY_BYTE) as u32;       // scheme
+            qd[10 + q] = ((key / POOL_KEY_TOLERANCE) % POOL_KEY_WORD) as u32;   // tolerance
+            qd[12 + q] = ((key / POOL_KEY_PENALTY) % POOL_KEY_BYTE) as u32;     // penalty
+        }
+
+        let (earned_points, total_points, is_correct1, is_correct2) = score_attempt(user_answers.to_arcis(), qd);
+
+        let scale = scale_for(report_decimals);
+        let final_percentage = clamp_to_u32(percentage(earned_points as u64, total_points as u64, scale));
+        let safe_divisor = total_points + (total_points == 0u32) as u32;
+        let passed = (earned_points as u64) * 100u64 >= (bank.passing_threshold as u64) * (safe_divisor as u64);
+        let recommendation_bar = (RECOMMENDATION_THRESHOLD * scale) as u32;
+
+        let quiz_result = QuizResult {
+            score: earned_points,
+            total_points,
+            percentage: final_percentage,
+            passed,
+            feedback: passed as u32,
+        };
+        let student_feedback = StudentFeedback {
+            personal_score: final_percentage,
+            correct_answers: is_correct1 + is_correct2,
+            study_recommendations: (final_percentage < recommendation_bar) as u32,
+            next_quiz_suggestions: passed as u32,
+        };
+
+        (student.from_arcis(quiz_result), quiz_creator.from_arcis(student_feedback))
+    }
+
+    // ✅ NEW: Open an adaptive attempt at the middle tier
+    #[instruction]
+    pub fn init_adaptive_attempt(mxe: Mxe, student: Shared) -> (Enc<Mxe, AdaptiveState>, Enc<Shared, u32>) {
+        let state = AdaptiveState {
+            score: 0u32,
+            tier: ADAPTIVE_START_TIER,
+            correct_answers: 0u32,
+        };
+        (mxe.from_arcis(state), student.from_arcis(ADAPTIVE_START_TIER))
+    }
+
+    // ✅ NEW: Grade one adaptive answer and choose the next question
+    //
+    // The answered question is the one the encrypted tier pointed at, so its key is
+    // picked obliviously. A full-credit answer moves one tier up, anything else one
+    // tier down. Only the next question index leaves MPC, encrypted to the student.
+    #[instruction]
+    pub fn adaptive_step(
+        answer: Enc<Shared, u32>,
+        state: Enc<Mxe, AdaptiveState>,
+        adaptive_keys: Enc<Mxe, AdaptiveKeys>,
+        step: u8,                             // Answers recorded so far (public)
+        student: Shared,
+    ) -> (Enc<Mxe, AdaptiveState>, Enc<Shared, u32>) {
+        let answer = answer.to_arcis();
+        let mut progress = state.to_arcis();
+        let grid = adaptive_keys.to_arcis();
+
+        let mut key = 0u128;
+        for i in 0..ADAPTIVE_STEPS * ADAPTIVE_TIERS {
+            let here = (i as u32) == (step as u32) * (ADAPTIVE_TIERS as u32) + progress.tier;
+            key = key + (here as u128) * grid.keys[i];
+        }
+        let (awarded, deducted, fully_correct) = grade_question(
+            answer,
+            (key % POOL_KEY_WORD) as u32,
+            ((key / POOL_KEY_POINTS) % POOL_KEY_BYTE) as u32,
+            ((key / POOL_KEY_SCHEME) % POOL_KEY_BYTE) as u32,
+            ((key / POOL_KEY_TOLERANCE) % POOL_KEY_WORD) as u32,
+            ((key / POOL_KEY_PENALTY) % POOL_KEY_BYTE) as u32,
+        );
+
+        progress.score = sat_sub_u32(sat_add_u32(progress.score, awarded), deducted);
+        progress.correct_answers = progress.correct_answers + fully_correct;
+
+        let up = fully_correct * (progress.tier < (ADAPTIVE_TIERS as u32) - 1u32) as u32;
+        let down = (1u32 - fully_correct) * (progress.tier > 0u32) as u32;
+        progress.tier = (progress.tier + up) - down;
+
+        let next_question = if (step as usize) + 1 < ADAPTIVE_STEPS {
+            ((step as u32) + 1u32) * (ADAPTIVE_TIERS as u32) + progress.tier
+        } else {
+            NO_NEXT_QUESTION
+        };
+
+        (state.owner.from_arcis(progress), student.from_arcis(next_question))
+    }
+
+    // ✅ NEW: Reveal the placement once every step is answered
+    #[instruction]
+    pub fn reveal_adaptive_result(
+        state: Enc<Mxe, AdaptiveState>,
+        student: Shared,
+        quiz_creator: Shared,
+    ) -> (Enc<Shared, AdaptiveResult>, Enc<Shared, AdaptiveResult>) {
+        let progress = state.to_arcis();
+        let result = AdaptiveResult {
+            score: progress.score,
+            final_tier: progress.tier,
+            correct_answers: progress.correct_answers,
+        };
+        (student.from_arcis(result), quiz_creator.from_arcis(result))
     }
 }
@@ -3,6 +3,7 @@ use arcis_imports::*;
 #[encrypted]
 mod circuits {
     use arcis_imports::*;
+    use crate::safe_math::{sat_add_u32, sat_mul_u32};
 
     // Data structures for quiz threshold verification
     #[derive(Copy, Clone)]
@@ -71,10 +72,10 @@ mod circuits {
         
         // Audit record for compliance using constant-time operations
         let audit_record = AuditRecord {
-            verification_id: score + thresh,
+            verification_id: sat_add_u32(score, thresh),
             verification_status: meets_threshold_u32, // 0 or 1
             compliance_metrics: meets_requirement_u32 * 100u32, // 0 * 100 = 0, 1 * 100 = 100
-            audit_trail: score * thresh,
+            audit_trail: sat_mul_u32(score, thresh),
         };
         
         // Re-encrypt results for different stakeholders
@@ -0,0 +1,81 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+    use crate::safe_math::{sat_add_u32, sat_sub_u32};
+
+    // Must match MAX_RANKING_OPTIONS in the se_qure program
+    const MAX_RANKING_OPTIONS: usize = 6;
+
+    // Running Borda scores for one ranking question
+    #[derive(Copy, Clone)]
+    pub struct RankingTally {
+        pub borda_scores: [u32; MAX_RANKING_OPTIONS],       // Sum of (option_count - 1 - rank)
+        pub first_place_counts: [u32; MAX_RANKING_OPTIONS], // Ballots ranking the option first
+        pub valid_ballots: u32,
+    }
+
+    // ✅ NEW: Start an empty tally owned by the MXE
+    #[instruction]
+    pub fn init_ranking_tally(mxe: Mxe) -> Enc<Mxe, RankingTally> {
+        let tally = RankingTally {
+            borda_scores: [0u32; MAX_RANKING_OPTIONS],
+            first_place_counts: [0u32; MAX_RANKING_OPTIONS],
+            valid_ballots: 0u32,
+        };
+        mxe.from_arcis(tally)
+    }
+
+    // ✅ NEW: Fold one respondent's ordering into the tally
+    //
+    // `ranks[option]` is the position the respondent gave that option (0 = most preferred).
+    // A ballot only counts if the first `option_count` ranks are a permutation of
+    // 0..option_count; anything else contributes zero everywhere, so a malformed
+    // ballot can't inflate an option and nothing about the ordering leaks.
+    #[instruction]
+    pub fn accumulate_ranking_response(
+        response: Enc<Shared, [u8; MAX_RANKING_OPTIONS]>,
+        option_count: u8, // Public question shape from the survey account
+        tally: Enc<Mxe, RankingTally>,
+    ) -> Enc<Mxe, RankingTally> {
+        let ranks = response.to_arcis();
+        let mut state = tally.to_arcis();
+
+        // Every used rank must be in range and distinct
+        let mut valid = 1u32;
+        for i in 0..MAX_RANKING_OPTIONS {
+            let used_i = ((i as u8) < option_count) as u32;
+            let in_range = (ranks[i] < option_count) as u32;
+            valid = valid * (1u32 - used_i * (1u32 - in_range));
+            for j in (i + 1)..MAX_RANKING_OPTIONS {
+                let used_j = ((j as u8) < option_count) as u32;
+                let collision = (ranks[i] == ranks[j]) as u32;
+                valid = valid * (1u32 - used_i * used_j * collision);
+            }
+        }
+
+        let top_points = sat_sub_u32(option_count as u32, 1u32);
+        for i in 0..MAX_RANKING_OPTIONS {
+            let used = ((i as u8) < option_count) as u32;
+            let counted = valid * used;
+            // Out-of-range ranks only occur on invalid ballots, but the subtraction still clamps
+            let points = sat_sub_u32(top_points, ranks[i] as u32);
+            state.borda_scores[i] = sat_add_u32(state.borda_scores[i], points * counted);
+            state.first_place_counts[i] = sat_add_u32(state.first_place_counts[i], ((ranks[i] == 0u8) as u32) * counted);
+        }
+        state.valid_ballots = sat_add_u32(state.valid_ballots, valid);
+
+        tally.owner.from_arcis(state)
+    }
+
+    // ✅ NEW: Re-encrypt the aggregate scores for the survey creator
+    #[instruction]
+    pub fn reveal_ranking_tally(
+        tally: Enc<Mxe, RankingTally>,
+        creator: Shared,
+    ) -> Enc<Shared, RankingTally> {
+        let state = tally.to_arcis();
+        creator.from_arcis(state)
+    }
+}
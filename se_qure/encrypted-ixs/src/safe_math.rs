@@ -0,0 +1,131 @@
+use arcis_imports::*;
+
+// Overflow-safe integer helpers for the circuits.
+//
+// MPC arithmetic can't branch or trap, so an overflow silently wraps and turns a
+// score or tally into garbage. These helpers either widen to the next integer size
+// or clamp at the type's maximum, and never evaluate an operation that would
+// overflow. Equality is a direct comparison, never `a - b == 0`.
+#[encrypted]
+pub mod safe_math {
+    use arcis_imports::*;
+
+    // 1 if a == b, else 0 (no subtraction, so no underflow when a < b)
+    pub fn eq_u32(a: u32, b: u32) -> u32 {
+        (a == b) as u32
+    }
+
+    // `if_true` when cond is 1, `if_false` when cond is 0
+    pub fn select_u32(cond: u32, if_true: u32, if_false: u32) -> u32 {
+        if_true * cond + if_false * (1u32 - cond)
+    }
+
+    // Narrow a u64 to u32, clamping at u32::MAX instead of truncating
+    pub fn clamp_to_u32(value: u64) -> u32 {
+        let over = (value > u32::MAX as u64) as u64;
+        (value * (1u64 - over) + (u32::MAX as u64) * over) as u32
+    }
+
+    // a + b, clamped at u32::MAX
+    pub fn sat_add_u32(a: u32, b: u32) -> u32 {
+        clamp_to_u32((a as u64) + (b as u64))
+    }
+
+    // a - b, clamped at 0
+    pub fn sat_sub_u32(a: u32, b: u32) -> u32 {
+        // Clamp the subtrahend first so the subtraction itself can't underflow
+        let fits = (b <= a) as u32;
+        a - select_u32(fits, b, a)
+    }
+
+    // a * b, clamped at u32::MAX
+    pub fn sat_mul_u32(a: u32, b: u32) -> u32 {
+        clamp_to_u32(widening_mul_u32(a, b))
+    }
+
+    // Exact product of two u32s (always fits in u64)
+    pub fn widening_mul_u32(a: u32, b: u32) -> u64 {
+        (a as u64) * (b as u64)
+    }
+
+    // |a - b| without underflow
+    pub fn abs_diff_u32(a: u32, b: u32) -> u32 {
+        let a_ge_b = (a >= b) as u32;
+        select_u32(a_ge_b, a, b) - select_u32(a_ge_b, b, a)
+    }
+
+    // a + b, clamped at u64::MAX
+    pub fn sat_add_u64(a: u64, b: u64) -> u64 {
+        let wide = (a as u128) + (b as u128);
+        let over = (wide > u64::MAX as u128) as u128;
+        (wide * (1u128 - over) + (u64::MAX as u128) * over) as u64
+    }
+
+    // a * b, clamped at u64::MAX
+    pub fn sat_mul_u64(a: u64, b: u64) -> u64 {
+        let wide = (a as u128) * (b as u128);
+        let over = (wide > u64::MAX as u128) as u128;
+        (wide * (1u128 - over) + (u64::MAX as u128) * over) as u64
+    }
+
+    // a * b, clamped at u128::MAX. There is no wider type, so the overflow is
+    // detected by division and the factor zeroed before multiplying.
+    pub fn sat_mul_u128(a: u128, b: u128) -> u128 {
+        let a_zero = (a == 0u128) as u128;
+        let over = (b > u128::MAX / (a + a_zero)) as u128;
+        a * (b * (1u128 - over)) + u128::MAX * over
+    }
+}
+
+pub use safe_math::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const U32_EDGES: [u32; 9] = [0, 1, 2, 255, 65_535, 65_536, u32::MAX / 2, u32::MAX - 1, u32::MAX];
+    const U64_EDGES: [u64; 8] = [0, 1, 2, u32::MAX as u64, u32::MAX as u64 + 1, u64::MAX / 2, u64::MAX - 1, u64::MAX];
+
+    #[test]
+    fn test_u32_helpers_match_std() {
+        for &a in &U32_EDGES {
+            for &b in &U32_EDGES {
+                assert_eq!(eq_u32(a, b), (a == b) as u32);
+                assert_eq!(sat_add_u32(a, b), a.saturating_add(b));
+                assert_eq!(sat_sub_u32(a, b), a.saturating_sub(b));
+                assert_eq!(sat_mul_u32(a, b), a.saturating_mul(b));
+                assert_eq!(widening_mul_u32(a, b), a as u64 * b as u64);
+                assert_eq!(abs_diff_u32(a, b), a.abs_diff(b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_u32() {
+        for &value in &U32_EDGES {
+            assert_eq!(select_u32(1, value, 7), value);
+            assert_eq!(select_u32(0, 7, value), value);
+        }
+    }
+
+    #[test]
+    fn test_u64_helpers_match_std() {
+        for &a in &U64_EDGES {
+            assert_eq!(clamp_to_u32(a), u32::try_from(a).unwrap_or(u32::MAX));
+            for &b in &U64_EDGES {
+                assert_eq!(sat_add_u64(a, b), a.saturating_add(b));
+                assert_eq!(sat_mul_u64(a, b), a.saturating_mul(b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sat_mul_u128_matches_std() {
+        let edges = [0, 1, 2, u64::MAX as u128, u64::MAX as u128 + 1, u128::MAX / 3, u128::MAX - 1, u128::MAX];
+        for &a in &edges {
+            for &b in &edges {
+                assert_eq!(sat_mul_u128(a, b), a.saturating_mul(b));
+            }
+        }
+    }
+}
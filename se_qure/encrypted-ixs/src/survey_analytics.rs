@@ -3,14 +3,20 @@ use arcis_imports::*;
 #[encrypted]
 mod circuits {
     use arcis_imports::*;
+    use crate::fixed_point::{div_round, scale_for};
+    use crate::safe_math::{clamp_to_u32, sat_add_u32};
+
+    // Sentinel the client encrypts for questions hidden by skip logic (see NOT_ANSWERED in the program)
+    const NOT_ANSWERED: u32 = u32::MAX;
 
     // Data structures for survey analytics
     #[derive(Copy, Clone)]
     pub struct SurveyAnalytics {
         pub total_responses: u32,
-        pub average_rating: u32,
+        pub average_rating: u32,      // Scaled by 10^report_decimals
         pub completion_rate: u32,
         pub response_pattern: u32,
+        pub answered_questions: u32,  // Questions actually shown and answered (skips excluded)
     }
 
     #[derive(Copy, Clone)]
@@ -22,7 +28,7 @@ mod circuits {
     #[derive(Copy, Clone)]
     pub struct RespondentFeedback {
         pub response_id: u32,
-        pub feedback_score: u32,
+        pub feedback_score: u32,      // Same scale as average_rating
         pub completion_time: u32,
     }
 
@@ -32,6 +38,7 @@ mod circuits {
     pub fn survey_analytics(
         user_data: Enc<Shared, [u32; 6]>,  // All 6 values encrypted with same key/nonce
                                             // [answer1, answer2, question_type1, question_type2, total_responses, completion_rate]
+        report_decimals: u8,                // Public precision for averages (0-4 decimal places)
         survey_creator: Shared,             // Re-encrypt full analytics for survey creator
         public_viewer: Shared,              // Re-encrypt limited summary for public viewers
         respondent: Shared                  // Re-encrypt feedback for the respondent
@@ -45,9 +52,18 @@ mod circuits {
         let total_resp = data[4];  // total_responses
         let completion = data[5];  // completion_rate
         
-        // Calculate comprehensive analytics
-        let average_rating = (a1 + a2) / 2u32;
-        let response_pattern = a1 + a2 + qt1 + qt2;
+        // Skipped questions carry the NOT_ANSWERED sentinel; mask them out instead of counting as 0
+        let answered1 = (a1 != NOT_ANSWERED) as u32;
+        let answered2 = (a2 != NOT_ANSWERED) as u32;
+        let answered_count = answered1 + answered2;
+        let v1 = a1 * answered1;
+        let v2 = a2 * answered2;
+        
+        // Calculate comprehensive analytics over answered questions only (rounded, not truncated)
+        let scale = scale_for(report_decimals);
+        let answer_sum = sat_add_u32(v1, v2);
+        let average_rating = clamp_to_u32(div_round((v1 as u64) + (v2 as u64), answered_count as u64, scale));
+        let response_pattern = sat_add_u32(answer_sum, sat_add_u32(qt1, qt2));
         
         // Full analytics for survey creator
         let full_analytics = SurveyAnalytics {
@@ -55,6 +71,7 @@ mod circuits {
             average_rating,
             completion_rate: completion,
             response_pattern,
+            answered_questions: answered_count,
         };
         
         // Limited public summary
@@ -65,9 +82,9 @@ mod circuits {
         
         // Personal feedback for respondent
         let respondent_feedback = RespondentFeedback {
-            response_id: a1 + a2, // Unique response identifier
+            response_id: answer_sum, // Unique response identifier
             feedback_score: average_rating,
-            completion_time: qt1 + qt2, // Time-based metric
+            completion_time: sat_add_u32(qt1, qt2), // Time-based metric
         };
         
         // Re-encrypt results for different stakeholders
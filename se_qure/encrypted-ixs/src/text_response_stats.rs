@@ -0,0 +1,187 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+    use crate::fixed_point::div_round;
+    use crate::safe_math::{clamp_to_u32, sat_add_u32, sat_add_u64};
+
+    // Encrypted answer value for questions hidden by skip logic (must match the program)
+    const NOT_ANSWERED: u32 = u32::MAX;
+
+    // Keyword counters (must match the program and client/text_responses.ts). The
+    // client maps an answer onto the question's fixed vocabulary and sends up to
+    // MAX_ANSWER_KEYWORDS token ids, padded with NO_KEYWORD.
+    const KEYWORD_VOCABULARY: usize = 32;
+    const MAX_ANSWER_KEYWORDS: usize = 8;
+    const TOP_KEYWORDS: usize = 5;
+    const NO_KEYWORD: u32 = u32::MAX;
+
+    // The text itself is encrypted to the creator; MPC only sees its trimmed UTF-8 length
+    #[derive(Copy, Clone)]
+    pub struct TextTally {
+        pub responses: u32,     // Submitted, blank ones included
+        pub non_empty: u32,
+        pub total_length: u64,  // Bytes over non-empty answers
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct TextReport {
+        pub threshold_met: bool,
+        pub responses: u32,
+        pub non_empty: u32,
+        pub average_length: u32,  // Rounded bytes per non-empty answer
+    }
+
+    // Answers mentioning each vocabulary keyword
+    #[derive(Copy, Clone)]
+    pub struct KeywordTally {
+        pub answers: u32,
+        pub counts: [u32; KEYWORD_VOCABULARY],
+    }
+
+    // Most mentioned keywords, best first; empty slots are NO_KEYWORD with count 0
+    #[derive(Copy, Clone)]
+    pub struct KeywordTable {
+        pub threshold_met: bool,
+        pub answers: u32,
+        pub keywords: [u32; TOP_KEYWORDS],
+        pub counts: [u32; TOP_KEYWORDS],
+    }
+
+    // ✅ NEW: Start an empty text tally owned by the MXE
+    #[instruction]
+    pub fn init_text_tally(mxe: Mxe) -> Enc<Mxe, TextTally> {
+        let tally = TextTally {
+            responses: 0u32,
+            non_empty: 0u32,
+            total_length: 0u64,
+        };
+        mxe.from_arcis(tally)
+    }
+
+    // ✅ NEW: Count one text answer
+    //
+    // Clients submit every answer padded to the same block size, so the chain can't
+    // tell blank from filled in; only this count can.
+    #[instruction]
+    pub fn accumulate_text_response(
+        length: Enc<Shared, u32>,
+        tally: Enc<Mxe, TextTally>,
+    ) -> Enc<Mxe, TextTally> {
+        let length = length.to_arcis();
+        let mut state = tally.to_arcis();
+
+        let answered = length != NOT_ANSWERED;
+        let non_empty = answered && length > 0u32;
+
+        state.responses = sat_add_u32(state.responses, answered as u32);
+        state.non_empty = sat_add_u32(state.non_empty, non_empty as u32);
+        state.total_length = sat_add_u64(state.total_length, if non_empty { length as u64 } else { 0u64 });
+
+        tally.owner.from_arcis(state)
+    }
+
+    // ✅ NEW: Reveal text answer counts to the creator once enough have come in
+    #[instruction]
+    pub fn reveal_text_stats(
+        tally: Enc<Mxe, TextTally>,
+        min_responses: u32,
+        creator: Shared,
+    ) -> Enc<Shared, TextReport> {
+        let state = tally.to_arcis();
+
+        let threshold_met = state.responses >= min_responses;
+        let met = threshold_met as u32;
+        let average_length = clamp_to_u32(div_round(state.total_length, state.non_empty as u64, 1u64));
+
+        let report = TextReport {
+            threshold_met,
+            responses: state.responses * met,
+            non_empty: state.non_empty * met,
+            average_length: average_length * met,
+        };
+
+        creator.from_arcis(report)
+    }
+
+    // ✅ NEW: Start an empty keyword tally owned by the MXE
+    #[instruction]
+    pub fn init_keyword_tally(mxe: Mxe) -> Enc<Mxe, KeywordTally> {
+        let tally = KeywordTally {
+            answers: 0u32,
+            counts: [0u32; KEYWORD_VOCABULARY],
+        };
+        mxe.from_arcis(tally)
+    }
+
+    // ✅ NEW: Count the keywords of one answer
+    //
+    // A keyword counts once per answer however often it appears; ids outside the
+    // question's vocabulary (NO_KEYWORD padding included) are ignored.
+    #[instruction]
+    pub fn accumulate_keywords(
+        tokens: Enc<Shared, [u32; MAX_ANSWER_KEYWORDS]>,
+        vocabulary_size: u8,
+        tally: Enc<Mxe, KeywordTally>,
+    ) -> Enc<Mxe, KeywordTally> {
+        let tokens = tokens.to_arcis();
+        let mut state = tally.to_arcis();
+
+        for keyword in 0..KEYWORD_VOCABULARY {
+            let mut mentioned = false;
+            for i in 0..MAX_ANSWER_KEYWORDS {
+                mentioned = mentioned || tokens[i] == keyword as u32;
+            }
+            let counted = mentioned && (keyword as u8) < vocabulary_size;
+            state.counts[keyword] = sat_add_u32(state.counts[keyword], counted as u32);
+        }
+        state.answers = sat_add_u32(state.answers, 1u32);
+
+        tally.owner.from_arcis(state)
+    }
+
+    // ✅ NEW: Reveal the top keywords to the creator once enough answers have come in
+    //
+    // Ties go to the lower keyword id. Keywords nobody mentioned never make the table.
+    #[instruction]
+    pub fn reveal_keyword_table(
+        tally: Enc<Mxe, KeywordTally>,
+        vocabulary_size: u8,
+        min_responses: u32,
+        creator: Shared,
+    ) -> Enc<Shared, KeywordTable> {
+        let state = tally.to_arcis();
+
+        let threshold_met = state.answers >= min_responses;
+        let mut taken = [false; KEYWORD_VOCABULARY];
+        let mut keywords = [NO_KEYWORD; TOP_KEYWORDS];
+        let mut counts = [0u32; TOP_KEYWORDS];
+
+        for slot in 0..TOP_KEYWORDS {
+            let mut best = 0u32;
+            let mut best_keyword = NO_KEYWORD;
+            for keyword in 0..KEYWORD_VOCABULARY {
+                let higher = !taken[keyword]
+                    && (keyword as u8) < vocabulary_size
+                    && state.counts[keyword] > best;
+                best = if higher { state.counts[keyword] } else { best };
+                best_keyword = if higher { keyword as u32 } else { best_keyword };
+            }
+            for keyword in 0..KEYWORD_VOCABULARY {
+                taken[keyword] = taken[keyword] || best_keyword == keyword as u32;
+            }
+            keywords[slot] = if threshold_met { best_keyword } else { NO_KEYWORD };
+            counts[slot] = best * threshold_met as u32;
+        }
+
+        let table = KeywordTable {
+            threshold_met,
+            answers: state.answers * threshold_met as u32,
+            keywords,
+            counts,
+        };
+
+        creator.from_arcis(table)
+    }
+}
@@ -2,6 +2,7 @@
 use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::{CallbackAccount, CircuitSource, OffChainCircuitSource};
+use anchor_lang::solana_program::hash::hashv;
 // MXEAccount is available from arcium_anchor::prelude::*
 
 // Legacy functions moved to separate file for reference
@@ -9,11 +10,83 @@ mod legacy_functions;
 
 // Computation definition offsets for our survey/quiz DApp
 // ✅ V3 FIX: New offsets to create fresh comp def accounts (v1 and v2 are immutable/cached)
-const COMP_DEF_OFFSET_SURVEY_ANALYTICS: u32 = comp_def_offset("survey_analytics_v3");
-const COMP_DEF_OFFSET_QUIZ_EVALUATION: u32 = comp_def_offset("quiz_evaluation_v3");
-const COMP_DEF_OFFSET_ANALYTICS: u32 = comp_def_offset("analytics_computation_v3");
+// ✅ V4: survey_analytics / quiz_evaluation take a public report_decimals precision (new signature)
+const COMP_DEF_OFFSET_SURVEY_ANALYTICS: u32 = comp_def_offset("survey_analytics_v4");
+// ✅ V7: quiz_evaluation takes the public per-attempt answer_shuffle code (new signature)
+const COMP_DEF_OFFSET_QUIZ_EVALUATION: u32 = comp_def_offset("quiz_evaluation_v7");
+// ✅ V4: analytics_computation now threads encrypted correlation sums (new signature)
+const COMP_DEF_OFFSET_ANALYTICS: u32 = comp_def_offset("analytics_computation_v4");
 const COMP_DEF_OFFSET_QUIZ_THRESHOLD: u32 = comp_def_offset("quiz_threshold_check_v3");
 
+// Matrix / ranking question aggregation
+const COMP_DEF_OFFSET_INIT_MATRIX_TALLY: u32 = comp_def_offset("init_matrix_tally");
+const COMP_DEF_OFFSET_ACCUMULATE_MATRIX_RESPONSE: u32 = comp_def_offset("accumulate_matrix_response");
+const COMP_DEF_OFFSET_REVEAL_MATRIX_TALLY: u32 = comp_def_offset("reveal_matrix_tally");
+const COMP_DEF_OFFSET_INIT_RANKING_TALLY: u32 = comp_def_offset("init_ranking_tally");
+const COMP_DEF_OFFSET_ACCUMULATE_RANKING_RESPONSE: u32 = comp_def_offset("accumulate_ranking_response");
+const COMP_DEF_OFFSET_REVEAL_RANKING_TALLY: u32 = comp_def_offset("reveal_ranking_tally");
+
+// Net Promoter Score
+const COMP_DEF_OFFSET_INIT_NPS_TALLY: u32 = comp_def_offset("init_nps_tally");
+const COMP_DEF_OFFSET_ACCUMULATE_NPS_RESPONSE: u32 = comp_def_offset("accumulate_nps_response");
+const COMP_DEF_OFFSET_REVEAL_NPS: u32 = comp_def_offset("reveal_nps");
+
+// Median / percentiles
+const COMP_DEF_OFFSET_INIT_HISTOGRAM: u32 = comp_def_offset("init_histogram");
+const COMP_DEF_OFFSET_ACCUMULATE_HISTOGRAM_RESPONSE: u32 = comp_def_offset("accumulate_histogram_response");
+const COMP_DEF_OFFSET_REVEAL_PERCENTILES: u32 = comp_def_offset("reveal_percentiles");
+
+// Cross-tabulation
+const COMP_DEF_OFFSET_INIT_CROSSTAB: u32 = comp_def_offset("init_crosstab");
+const COMP_DEF_OFFSET_ACCUMULATE_CROSSTAB_RESPONSE: u32 = comp_def_offset("accumulate_crosstab_response");
+const COMP_DEF_OFFSET_REVEAL_CROSSTAB: u32 = comp_def_offset("reveal_crosstab");
+
+// Research correlation state
+const COMP_DEF_OFFSET_INIT_CORRELATION_SUMS: u32 = comp_def_offset("init_correlation_sums");
+
+// Quiz item analysis
+const COMP_DEF_OFFSET_INIT_ITEM_STATS: u32 = comp_def_offset("init_item_stats");
+const COMP_DEF_OFFSET_REVEAL_ITEM_ANALYSIS: u32 = comp_def_offset("reveal_item_analysis");
+
+// Quiz leaderboard
+const COMP_DEF_OFFSET_INIT_LEADERBOARD: u32 = comp_def_offset("init_leaderboard");
+const COMP_DEF_OFFSET_RECORD_LEADERBOARD_SCORE: u32 = comp_def_offset("record_leaderboard_score");
+const COMP_DEF_OFFSET_REVEAL_MY_RANK: u32 = comp_def_offset("reveal_my_rank");
+const COMP_DEF_OFFSET_REVEAL_LEADERBOARD: u32 = comp_def_offset("reveal_leaderboard");
+
+// Answer similarity checks
+const COMP_DEF_OFFSET_INIT_ANSWER_HISTORY: u32 = comp_def_offset("init_answer_history");
+const COMP_DEF_OFFSET_CHECK_ANSWER_SIMILARITY: u32 = comp_def_offset("check_answer_similarity");
+
+// Question pools
+const COMP_DEF_OFFSET_POOL_QUIZ_EVALUATION: u32 = comp_def_offset("pool_quiz_evaluation");
+
+// Adaptive quizzes
+const COMP_DEF_OFFSET_INIT_ADAPTIVE_ATTEMPT: u32 = comp_def_offset("init_adaptive_attempt");
+const COMP_DEF_OFFSET_ADAPTIVE_STEP: u32 = comp_def_offset("adaptive_step");
+const COMP_DEF_OFFSET_REVEAL_ADAPTIVE_RESULT: u32 = comp_def_offset("reveal_adaptive_result");
+
+// Polls
+const COMP_DEF_OFFSET_INIT_POLL_TALLY: u32 = comp_def_offset("init_poll_tally");
+const COMP_DEF_OFFSET_CAST_POLL_VOTE: u32 = comp_def_offset("cast_poll_vote");
+const COMP_DEF_OFFSET_REVEAL_POLL_TALLY: u32 = comp_def_offset("reveal_poll_tally");
+const COMP_DEF_OFFSET_INIT_RANKED_TALLY: u32 = comp_def_offset("init_ranked_tally");
+const COMP_DEF_OFFSET_CAST_RANKED_BALLOT: u32 = comp_def_offset("cast_ranked_ballot");
+const COMP_DEF_OFFSET_RUN_INSTANT_RUNOFF: u32 = comp_def_offset("run_instant_runoff");
+
+// Free-text answers
+const COMP_DEF_OFFSET_INIT_TEXT_TALLY: u32 = comp_def_offset("init_text_tally");
+const COMP_DEF_OFFSET_ACCUMULATE_TEXT_RESPONSE: u32 = comp_def_offset("accumulate_text_response");
+const COMP_DEF_OFFSET_REVEAL_TEXT_STATS: u32 = comp_def_offset("reveal_text_stats");
+const COMP_DEF_OFFSET_INIT_KEYWORD_TALLY: u32 = comp_def_offset("init_keyword_tally");
+const COMP_DEF_OFFSET_ACCUMULATE_KEYWORDS: u32 = comp_def_offset("accumulate_keywords");
+const COMP_DEF_OFFSET_REVEAL_KEYWORD_TABLE: u32 = comp_def_offset("reveal_keyword_table");
+
+// Batched survey responses
+const COMP_DEF_OFFSET_INIT_BATCH_TOTALS: u32 = comp_def_offset("init_batch_totals");
+const COMP_DEF_OFFSET_PROCESS_RESPONSE_BATCH: u32 = comp_def_offset("process_response_batch");
+const COMP_DEF_OFFSET_REVEAL_BATCH_ANALYTICS: u32 = comp_def_offset("reveal_batch_analytics");
+
 // ✅ FIXED: Using SIGN_PDA_SEED from arcium_anchor::prelude::* instead of manual definition
 
 // Arcium program ID for owner constraints
@@ -59,6 +132,10 @@ pub struct QuizEvaluationCallback<'info> {
     /// CHECK: instructions_sysvar, checked by the account constraint
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub item_stats: Account<'info, ItemStatsStorage>,
+    #[account(mut)]
+    pub answers_storage: Account<'info, QuizAnswersStorage>,
 }
 
 #[callback_accounts("analytics_computation")]
@@ -70,6 +147,10 @@ pub struct AnalyticsComputationCallback<'info> {
     /// CHECK: instructions_sysvar, checked by the account constraint
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub correlation_sums: Account<'info, CorrelationSumsStorage>,
+    #[account(mut)]
+    pub receipt: Account<'info, TallyReceipt>,
 }
 
 #[callback_accounts("quiz_threshold_check")]
@@ -85,1401 +166,11411 @@ pub struct QuizThresholdCheckCallback<'info> {
     pub completion_proof: Account<'info, QuizCompletionProof>,
 }
 
-// ✅ RESTORED: Manual computation definition initialization functions (from backup)
-#[init_computation_definition_accounts("survey_analytics", payer)]
+#[callback_accounts("init_matrix_tally")]
 #[derive(Accounts)]
-pub struct InitSurveyAnalyticsCompDef<'info> {
+pub struct InitMatrixTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MATRIX_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    pub tally_storage: Account<'info, MatrixTallyStorage>,
+}
+
+#[callback_accounts("accumulate_matrix_response")]
+#[derive(Accounts)]
+pub struct AccumulateMatrixResponseCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_MATRIX_RESPONSE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    /// CHECK: comp_def_account, checked by the arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
+    pub tally_storage: Account<'info, MatrixTallyStorage>,
+    #[account(mut)]
+    pub receipt: Account<'info, TallyReceipt>,
+}
+
+#[callback_accounts("reveal_matrix_tally")]
+#[derive(Accounts)]
+pub struct RevealMatrixTallyCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_MATRIX_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub tally_storage: Account<'info, MatrixTallyStorage>,
 }
 
-#[init_computation_definition_accounts("quiz_evaluation", payer)]
+#[callback_accounts("init_ranking_tally")]
 #[derive(Accounts)]
-pub struct InitQuizEvaluationCompDef<'info> {
+pub struct InitRankingTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RANKING_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    pub tally_storage: Account<'info, RankingTallyStorage>,
+}
+
+#[callback_accounts("accumulate_ranking_response")]
+#[derive(Accounts)]
+pub struct AccumulateRankingResponseCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_RANKING_RESPONSE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    /// CHECK: comp_def_account, checked by the arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
+    pub tally_storage: Account<'info, RankingTallyStorage>,
+    #[account(mut)]
+    pub receipt: Account<'info, TallyReceipt>,
+}
+
+#[callback_accounts("reveal_ranking_tally")]
+#[derive(Accounts)]
+pub struct RevealRankingTallyCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RANKING_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub tally_storage: Account<'info, RankingTallyStorage>,
 }
 
-#[init_computation_definition_accounts("analytics_computation", payer)]
+#[callback_accounts("init_nps_tally")]
 #[derive(Accounts)]
-pub struct InitAnalyticsComputationCompDef<'info> {
+pub struct InitNpsTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_NPS_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    pub tally_storage: Account<'info, NpsTallyStorage>,
+}
+
+#[callback_accounts("accumulate_nps_response")]
+#[derive(Accounts)]
+pub struct AccumulateNpsResponseCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_NPS_RESPONSE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    /// CHECK: comp_def_account, checked by the arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
+    pub tally_storage: Account<'info, NpsTallyStorage>,
+    #[account(mut)]
+    pub receipt: Account<'info, TallyReceipt>,
+}
+
+#[callback_accounts("reveal_nps")]
+#[derive(Accounts)]
+pub struct RevealNpsCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_NPS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub tally_storage: Account<'info, NpsTallyStorage>,
 }
 
-#[init_computation_definition_accounts("quiz_threshold_check", payer)]
+#[callback_accounts("init_histogram")]
 #[derive(Accounts)]
-pub struct InitQuizThresholdCheckCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
+pub struct InitHistogramCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_HISTOGRAM))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    /// CHECK: comp_def_account, checked by the arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
+    pub histogram_storage: Account<'info, HistogramStorage>,
+}
+
+#[callback_accounts("accumulate_histogram_response")]
+#[derive(Accounts)]
+pub struct AccumulateHistogramResponseCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_HISTOGRAM_RESPONSE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub histogram_storage: Account<'info, HistogramStorage>,
+    #[account(mut)]
+    pub receipt: Account<'info, TallyReceipt>,
 }
 
-// Helper function to calculate expiration timestamp
-fn calculate_expiration_timestamp(
-    current_timestamp: i64,
-    value: u32,
-    unit: &ExpirationUnit,
-) -> Result<i64> {
-    let seconds_to_add = match unit {
-        ExpirationUnit::Minutes => {
-            let minutes = value as i64;
-            minutes.checked_mul(60).ok_or::<anchor_lang::error::Error>(ErrorCode::IntegerOverflow.into())?
-        },
-        ExpirationUnit::Hours => {
-            let hours = value as i64;
-            hours.checked_mul(60 * 60).ok_or::<anchor_lang::error::Error>(ErrorCode::IntegerOverflow.into())?
-        },
-        ExpirationUnit::Days => {
-            let days = value as i64;
-            days.checked_mul(24 * 60 * 60).ok_or::<anchor_lang::error::Error>(ErrorCode::IntegerOverflow.into())?
-        },
-    };
-    
-    current_timestamp.checked_add(seconds_to_add).ok_or::<anchor_lang::error::Error>(ErrorCode::IntegerOverflow.into())
+#[callback_accounts("reveal_percentiles")]
+#[derive(Accounts)]
+pub struct RevealPercentilesCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_PERCENTILES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub histogram_storage: Account<'info, HistogramStorage>,
 }
 
-// ✅ REMOVED: Fake hash function - using direct user references instead
+#[callback_accounts("init_crosstab")]
+#[derive(Accounts)]
+pub struct InitCrosstabCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_CROSSTAB))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub crosstab_storage: Account<'info, CrosstabStorage>,
+}
 
-// Helper function to validate expiration configuration
-fn validate_expiration_config(
-    value: u32,
-    unit: &ExpirationUnit,
-) -> Result<()> {
-    match unit {
-        ExpirationUnit::Minutes => {
-            require!(value > 0 && value <= 525600, ErrorCode::InvalidExpirationValue); // Max 1 year in minutes
-        },
-        ExpirationUnit::Hours => {
-            require!(value > 0 && value <= 8760, ErrorCode::InvalidExpirationValue); // Max 1 year in hours
-        },
-        ExpirationUnit::Days => {
-            require!(value > 0 && value <= 365, ErrorCode::InvalidExpirationValue); // Max 1 year in days
-        },
-    }
-    Ok(())
+#[callback_accounts("accumulate_crosstab_response")]
+#[derive(Accounts)]
+pub struct AccumulateCrosstabResponseCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_CROSSTAB_RESPONSE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub crosstab_storage: Account<'info, CrosstabStorage>,
+    #[account(mut)]
+    pub receipt: Account<'info, TallyReceipt>,
 }
 
-#[arcium_program]
-pub mod se_qure {
-    use super::*;
-    
-    // ✅ FIXED: Removed manual callback struct imports
-    // Arcium automatically generates these types when using #[callback_accounts] macro
+#[callback_accounts("reveal_crosstab")]
+#[derive(Accounts)]
+pub struct RevealCrosstabCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_CROSSTAB))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub crosstab_storage: Account<'info, CrosstabStorage>,
+}
 
-    // ✅ REMOVED: Duplicate callback functions - Arcium macros auto-generate these
+#[callback_accounts("init_correlation_sums")]
+#[derive(Accounts)]
+pub struct InitCorrelationSumsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_CORRELATION_SUMS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub correlation_sums: Account<'info, CorrelationSumsStorage>,
+}
 
+#[callback_accounts("init_item_stats")]
+#[derive(Accounts)]
+pub struct InitItemStatsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ITEM_STATS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub item_stats: Account<'info, ItemStatsStorage>,
+}
 
+#[callback_accounts("reveal_item_analysis")]
+#[derive(Accounts)]
+pub struct RevealItemAnalysisCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_ITEM_ANALYSIS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub item_stats: Account<'info, ItemStatsStorage>,
+}
 
-    // ✅ REVERTED: Use standard Arcium v0.3.0 patterns without problematic macros
-    // Note: Computation definitions will be initialized via standard Anchor patterns
+#[callback_accounts("init_leaderboard")]
+#[derive(Accounts)]
+pub struct InitLeaderboardCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_LEADERBOARD))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub leaderboard: Account<'info, LeaderboardStorage>,
+}
 
-    // ✅ FIXED: Initialize Sign PDA Account for cross-program invocations
-    pub fn init_sign_pda(ctx: Context<InitSignPda>) -> Result<()> {
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
-        Ok(())
-    }
+#[callback_accounts("record_leaderboard_score")]
+#[derive(Accounts)]
+pub struct RecordLeaderboardScoreCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RECORD_LEADERBOARD_SCORE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub leaderboard: Account<'info, LeaderboardStorage>,
+    #[account(mut)]
+    pub leaderboard_entry: Account<'info, LeaderboardEntryStorage>,
+}
 
-    // ✅ UPDATED: Computation definition initialization with Supabase circuit URLs
-    pub fn init_survey_analytics_comp_def(ctx: Context<InitSurveyAnalyticsCompDef>) -> Result<()> {
-        init_comp_def(
-            ctx.accounts,
-            true,
-            0,
-            Some(CircuitSource::OffChain(OffChainCircuitSource {
-                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/survey_analytics.arcis".to_string(),
-                hash: [0; 32], // Hash verification not enforced yet
-            })),
-            None,
-        )?;
-        Ok(())
-    }
-
-    pub fn init_quiz_evaluation_comp_def(ctx: Context<InitQuizEvaluationCompDef>) -> Result<()> {
-        init_comp_def(
-            ctx.accounts,
-            true,
-            0,
-            Some(CircuitSource::OffChain(OffChainCircuitSource {
-                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/quiz_evaluation.arcis".to_string(),
-                hash: [0; 32], // Hash verification not enforced yet
-            })),
-            None,
-        )?;
-        Ok(())
-    }
+#[callback_accounts("reveal_my_rank")]
+#[derive(Accounts)]
+pub struct RevealMyRankCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_MY_RANK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub leaderboard_entry: Account<'info, LeaderboardEntryStorage>,
+}
 
-    pub fn init_analytics_computation_comp_def(ctx: Context<InitAnalyticsComputationCompDef>) -> Result<()> {
-        init_comp_def(
-            ctx.accounts,
-            true,
-            0,
-            Some(CircuitSource::OffChain(OffChainCircuitSource {
-                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/analytics_computation.arcis".to_string(),
-                hash: [0; 32], // Hash verification not enforced yet
-            })),
-            None,
-        )?;
-        Ok(())
-    }
+#[callback_accounts("reveal_leaderboard")]
+#[derive(Accounts)]
+pub struct RevealLeaderboardCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_LEADERBOARD))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub leaderboard: Account<'info, LeaderboardStorage>,
+}
 
-    pub fn init_quiz_threshold_check_comp_def(ctx: Context<InitQuizThresholdCheckCompDef>) -> Result<()> {
-        init_comp_def(
-            ctx.accounts,
-            true,
-            0,
-            Some(CircuitSource::OffChain(OffChainCircuitSource {
-                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/quiz_threshold_check.arcis".to_string(),
-                hash: [0; 32], // Hash verification not enforced yet
-            })),
-            None,
-        )?;
-        Ok(())
-    }
+#[callback_accounts("init_answer_history")]
+#[derive(Accounts)]
+pub struct InitAnswerHistoryCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ANSWER_HISTORY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub answer_history: Account<'info, AnswerHistoryStorage>,
+}
 
+#[callback_accounts("check_answer_similarity")]
+#[derive(Accounts)]
+pub struct CheckAnswerSimilarityCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_ANSWER_SIMILARITY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub answer_history: Account<'info, AnswerHistoryStorage>,
+    #[account(mut)]
+    pub answers_storage: Account<'info, QuizAnswersStorage>,
+}
 
-    // ✅ FIXED: Store application preferences for survey/quiz computations
-    // Note: This is application-level metadata, not actual Arcium MXE configuration
-    // MXE configuration happens through Arcium's infrastructure, not custom program accounts
-    pub fn set_survey_computation_preferences(
-        ctx: Context<SetSurveyComputationPreferences>,
-        preferred_encryption_scheme: EncryptionScheme,
-        preferred_data_provisioning: DataProvisioningMethod,
-        preferred_mpc_protocol: MPCProtocol,
-    ) -> Result<()> {
-        // Store application preferences for survey/quiz computations
-        // These are used to guide computation setup, not to configure Arcium's MXE
-        
-        // Validate preferences are supported by our application
-        require!(
-            matches!(preferred_encryption_scheme, EncryptionScheme::Rescue | EncryptionScheme::AES),
-            ErrorCode::UnsupportedEncryptionScheme
-        );
-        
-        // Validate data provisioning preference
-        require!(
-            matches!(preferred_data_provisioning, DataProvisioningMethod::Direct | DataProvisioningMethod::Batch),
-            ErrorCode::UnsupportedDataProvisioning
-        );
-        
-        // Validate MPC protocol preference
-        require!(
-            matches!(preferred_mpc_protocol, MPCProtocol::Cerberus | MPCProtocol::Manticore),
-            ErrorCode::UnsupportedMPCProtocol
-        );
-        
-        // Store application preferences (not Arcium MXE configuration)
-        let app_preferences = &mut ctx.accounts.app_preferences;
-        app_preferences.admin = ctx.accounts.admin.key();
-        app_preferences.preferred_encryption_scheme = preferred_encryption_scheme.clone();
-        app_preferences.preferred_data_provisioning = preferred_data_provisioning.clone();
-        app_preferences.preferred_mpc_protocol = preferred_mpc_protocol.clone();
-        app_preferences.configured_at = Clock::get()?.unix_timestamp;
-        app_preferences.is_active = true;
-        
-        emit!(SurveyComputationPreferencesSet {
-            preferred_encryption_scheme: format!("{:?}", preferred_encryption_scheme),
-            preferred_data_provisioning: format!("{:?}", preferred_data_provisioning),
-            preferred_mpc_protocol: format!("{:?}", preferred_mpc_protocol),
-            configured_at: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
-    }
+#[callback_accounts("pool_quiz_evaluation")]
+#[derive(Accounts)]
+pub struct PoolQuizEvaluationCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_POOL_QUIZ_EVALUATION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub answers_storage: Account<'info, QuizAnswersStorage>,
+}
 
+#[callback_accounts("init_adaptive_attempt")]
+#[derive(Accounts)]
+pub struct InitAdaptiveAttemptCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ADAPTIVE_ATTEMPT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub adaptive_attempt: Account<'info, AdaptiveAttempt>,
+}
 
+#[callback_accounts("adaptive_step")]
+#[derive(Accounts)]
+pub struct AdaptiveStepCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADAPTIVE_STEP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub adaptive_attempt: Account<'info, AdaptiveAttempt>,
+}
 
-    // Create a new survey with timestamp-based PDA (allows multiple surveys per creator)
-    // Only stores verification data on blockchain - sensitive data encrypted in Supabase
-    pub fn create_survey_with_timestamp(
-        ctx: Context<CreateSurveyWithTimestamp>,
-        _timestamp: i64,
-        slug: String,
-        title: String,
-        description: String,
-        survey_type: SurveyType,
-        max_responses: u32,
-        instructor_arcium_pubkey: [u8; 32],  // Add this parameter
-        creator_arcium_pubkey: [u8; 32],     // Add this parameter
-    ) -> Result<()> {
-        let survey = &mut ctx.accounts.survey;
-        let clock = Clock::get()?;
-        
-        // Input validation with security checks
-        validate_slug(&slug)?;
-        validate_string_length(&title, MAX_TITLE_LENGTH, "title")?;
-        validate_string_length(&description, MAX_DESCRIPTION_LENGTH, "description")?;
-        validate_max_responses(max_responses)?;
-        
-        // Security validations
-        validate_no_xss(&title)?;
-        validate_no_xss(&description)?;
-        validate_no_sql_injection(&title)?;
-        validate_no_sql_injection(&description)?;
-        
-        // ✅ FIXED: Validate space requirements before account creation
-        let required_space = Survey::calculate_space(&slug, &title);
-        require!(
-            required_space <= 15000, // Increased limit to accommodate questions storage
-            ErrorCode::SurveyTooLarge
-        );
-        
-        // Validate quiz expiration configuration if it's a quiz
-        if let SurveyType::Quiz { completion_proof_expiration_value, completion_proof_expiration_unit, .. } = &survey_type {
-            validate_expiration_config(*completion_proof_expiration_value, completion_proof_expiration_unit)?;
-        }
-        
-        survey.creator = ctx.accounts.creator.key();
-        survey.slug = slug;
-        survey.title = title;
-        survey.description = description;
-        survey.survey_type = survey_type;
-        survey.questions = Vec::new();  // ✅ FIXED: Initialize questions as empty vector
-        survey.max_responses = max_responses;
-        survey.current_responses = 0;  // Initialize response counter
-        survey.is_active = true;
-        survey.is_publicly_browsable = true;  // Regular surveys are publicly browsable
-        survey.modification_count = 0;  // Initialize modification counter
-        survey.created_at = clock.unix_timestamp;
-        survey.mpc_computation_id = 0;  // ✅ FIXED: Initialize MPC computation reference
-        
-        // ✅ NICO FIX: Store Arcium encryption keys
-        survey.instructor_arcium_pubkey = instructor_arcium_pubkey;
-        survey.creator_arcium_pubkey = creator_arcium_pubkey;
+#[callback_accounts("reveal_adaptive_result")]
+#[derive(Accounts)]
+pub struct RevealAdaptiveResultCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_ADAPTIVE_RESULT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub adaptive_attempt: Account<'info, AdaptiveAttempt>,
+}
 
-        emit!(SurveyCreated {
-            survey: survey.key(),
-            survey_type: match survey.survey_type {
-                SurveyType::Basic => "basic".to_string(),
-                SurveyType::Quiz { .. } => "quiz".to_string(),
-                SurveyType::Special => "special".to_string(),
-            },
-            created_at: clock.unix_timestamp,
-        });
+#[callback_accounts("init_poll_tally")]
+#[derive(Accounts)]
+pub struct InitPollTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POLL_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub poll_tally: Account<'info, PollTallyStorage>,
+}
 
-        Ok(())
-    }
+#[callback_accounts("cast_poll_vote")]
+#[derive(Accounts)]
+pub struct CastPollVoteCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_POLL_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub poll_tally: Account<'info, PollTallyStorage>,
+    #[account(mut)]
+    pub ballot: Account<'info, PollBallot>,
+}
 
-    // Add questions to a survey
-    pub fn add_questions(
-        ctx: Context<AddQuestions>,
-        questions: Vec<QuestionData>,
-    ) -> Result<()> {
-        // Input validation
-        validate_questions(&questions)?;
-        
-        let survey = &mut ctx.accounts.survey;
-        
-        require!(
-            survey.creator == ctx.accounts.creator.key(),
-            ErrorCode::Unauthorized
-        );
+#[callback_accounts("reveal_poll_tally")]
+#[derive(Accounts)]
+pub struct RevealPollTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_POLL_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub poll_tally: Account<'info, PollTallyStorage>,
+}
 
-        let question_count = questions.len() as u32;
-        
-        for question_data in questions {
-            survey.questions.push(question_data);
-        }
+#[callback_accounts("init_ranked_tally")]
+#[derive(Accounts)]
+pub struct InitRankedTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RANKED_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub ranked_tally: Account<'info, RankedTallyStorage>,
+}
 
-        emit!(QuestionsAdded {
-            survey: survey.key(),
-            question_count,
-        });
+#[callback_accounts("cast_ranked_ballot")]
+#[derive(Accounts)]
+pub struct CastRankedBallotCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_RANKED_BALLOT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub ranked_tally: Account<'info, RankedTallyStorage>,
+    #[account(mut)]
+    pub ballot: Account<'info, PollBallot>,
+}
 
-        Ok(())
-    }
+#[callback_accounts("run_instant_runoff")]
+#[derive(Accounts)]
+pub struct RunInstantRunoffCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RUN_INSTANT_RUNOFF))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub ranked_tally: Account<'info, RankedTallyStorage>,
+}
 
-    // ✅ NEW: Set quiz grading data (MXE-encrypted correct answers, points, threshold, stats)
-    /// ✅ NICO FIX: Create separate account for quiz grading data (enables Argument::Account)
-    pub fn set_quiz_grading_data(
-        ctx: Context<SetQuizGradingData>,
-        encrypted_quiz_data: [u8; 256],           // Enc<Mxe, [u32; 8]> - ALL quiz data in one array
-        mxe_encryption_pubkey: [u8; 32],          // Client public key used for ECDH with MXE
-        quiz_data_nonce: u128,                    // Single nonce for all 8 values
-    ) -> Result<()> {
-        // Verify caller is the quiz creator
-        require!(
-            ctx.accounts.quiz.creator == ctx.accounts.creator.key(),
-            ErrorCode::Unauthorized
-        );
-        
-        // Verify this is actually a quiz (not a basic survey)
-        require!(
-            matches!(ctx.accounts.quiz.survey_type, SurveyType::Quiz { .. }),
-            ErrorCode::InvalidSurveyType
-        );
-        
-        // Store grading data in the dedicated account
-        let grading_data = &mut ctx.accounts.quiz_grading_data;
-        grading_data.quiz = ctx.accounts.quiz.key();
-        grading_data.encrypted_quiz_data = encrypted_quiz_data;
-        grading_data.quiz_data_nonce = quiz_data_nonce;
-        grading_data.mxe_encryption_pubkey = mxe_encryption_pubkey;
-        grading_data.bump = ctx.bumps.quiz_grading_data;
+#[callback_accounts("init_text_tally")]
+#[derive(Accounts)]
+pub struct InitTextTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_TEXT_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub tally_storage: Account<'info, TextTallyStorage>,
+}
 
-        Ok(())
-    }
+#[callback_accounts("accumulate_text_response")]
+#[derive(Accounts)]
+pub struct AccumulateTextResponseCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_TEXT_RESPONSE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub tally_storage: Account<'info, TextTallyStorage>,
+    #[account(mut)]
+    pub receipt: Account<'info, TallyReceipt>,
+}
 
-    // Separate instruction for analytics computation
-    pub fn submit_survey_analytics(
-        ctx: Context<SubmitSurveyAnalytics>,
-        analytics_computation_offset: u64,
-        // ✅ UPDATED: Simplified args following Nico's advice (shared encryption = same key/nonce)
-        // user_data: Enc<Shared, [u32; 6]> - all 6 values encrypted with same key/nonce
-        ciphertext_answer1: [u8; 32],
-        ciphertext_answer2: [u8; 32],
-        ciphertext_question_type1: [u8; 32],
-        ciphertext_question_type2: [u8; 32],
-        ciphertext_total_responses: [u8; 32],
-        ciphertext_completion_rate: [u8; 32],
-        user_pub_key: [u8; 32],              // Shared encryption key (used for all 6 values)
-        user_nonce: u128,                    // Shared nonce (used for all 6 values)
-        // Recipients for re-encryption
-        survey_creator_pub_key: [u8; 32],
-        survey_creator_nonce: u128,
-        public_viewer_pub_key: [u8; 32],
-        public_viewer_nonce: u128,
-        respondent_pub_key: [u8; 32],
-        respondent_nonce: u128,
-    ) -> Result<()> {
-        // Set the sign PDA account bump
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
-        
-        // Validate survey is active
-        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
-        
-        // Check if survey has reached max responses
-        require!(
-            ctx.accounts.survey.current_responses < ctx.accounts.survey.max_responses,
-            ErrorCode::SurveyFull
-        );
-        
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+#[callback_accounts("reveal_text_stats")]
+#[derive(Accounts)]
+pub struct RevealTextStatsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_TEXT_STATS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub tally_storage: Account<'info, TextTallyStorage>,
+}
 
-        // ✅ UPDATED: Following Nico's advice for shared encryption
-        // Circuit signature: user_data: Enc<Shared, [u32; 6]>, survey_creator: Shared, public_viewer: Shared, respondent: Shared
-        // Total: 14 arguments (much more efficient!)
-        let creator_args = vec![
-            // user_data: Enc<Shared, [u32; 6]> - pubkey, nonce, then 6 ciphertexts
-            Argument::ArcisPubkey(user_pub_key),
-            Argument::PlaintextU128(user_nonce),
-            Argument::EncryptedU32(ciphertext_answer1),
-            Argument::EncryptedU32(ciphertext_answer2),
-            Argument::EncryptedU32(ciphertext_question_type1),
-            Argument::EncryptedU32(ciphertext_question_type2),
-            Argument::EncryptedU32(ciphertext_total_responses),
-            Argument::EncryptedU32(ciphertext_completion_rate),
-            // survey_creator: Shared - 2 args
-            Argument::ArcisPubkey(survey_creator_pub_key),
-            Argument::PlaintextU128(survey_creator_nonce),
-            // public_viewer: Shared - 2 args
-            Argument::ArcisPubkey(public_viewer_pub_key),
-            Argument::PlaintextU128(public_viewer_nonce),
-            // respondent: Shared - 2 args
-            Argument::ArcisPubkey(respondent_pub_key),
-            Argument::PlaintextU128(respondent_nonce),
-        ];
+#[callback_accounts("init_keyword_tally")]
+#[derive(Accounts)]
+pub struct InitKeywordTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_KEYWORD_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub keyword_tally: Account<'info, KeywordTallyStorage>,
+}
 
-        // Single computation for analytics
-        queue_computation(
-            ctx.accounts,
-            analytics_computation_offset,
-            creator_args,
-            None,
-            vec![SurveyAnalyticsCallback::callback_ix(&[
-                CallbackAccount {
-                    pubkey: ctx.accounts.analytics_storage.key(),
-                    is_writable: true,
-                },
-                CallbackAccount {
-                    pubkey: ctx.accounts.survey.key(),
-                    is_writable: true,
-                },
-            ])],
-        )?;
+#[callback_accounts("accumulate_keywords")]
+#[derive(Accounts)]
+pub struct AccumulateKeywordsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_KEYWORDS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub keyword_tally: Account<'info, KeywordTallyStorage>,
+    #[account(mut)]
+    pub receipt: Account<'info, TallyReceipt>,
+    #[account(mut)]
+    pub text_response: Account<'info, TextResponse>,
+}
 
-        // Increment response counter
-        ctx.accounts.survey.current_responses += 1;
+#[callback_accounts("reveal_keyword_table")]
+#[derive(Accounts)]
+pub struct RevealKeywordTableCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_KEYWORD_TABLE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub keyword_tally: Account<'info, KeywordTallyStorage>,
+}
 
-        // Emit event for tracking - avoid wallet exposure
-        emit!(ResponseSubmitted {
-            survey: ctx.accounts.survey.key(),
-        });
+#[callback_accounts("init_batch_totals")]
+#[derive(Accounts)]
+pub struct InitBatchTotalsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH_TOTALS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub response_batch: Account<'info, ResponseBatchBuffer>,
+}
 
-        Ok(())
-    }
+#[callback_accounts("process_response_batch")]
+#[derive(Accounts)]
+pub struct ProcessResponseBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_RESPONSE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub response_batch: Account<'info, ResponseBatchBuffer>,
+}
 
-    // Separate instruction for feedback computation
-    pub fn submit_survey_feedback(
-        ctx: Context<SubmitSurveyFeedback>,
-        feedback_computation_offset: u64,
-        // user_data: Enc<Shared, [u32; 6]> - All 6 values encrypted with same key/nonce
-        user_pub_key: [u8; 32],              // Shared encryption key for all 6 values
-        user_nonce: u128,                    // Shared nonce for all 6 values
-        ciphertext_answer1: [u8; 32],
-        ciphertext_answer2: [u8; 32],
-        ciphertext_question_type1: [u8; 32],
-        ciphertext_question_type2: [u8; 32],
-        ciphertext_total_responses: [u8; 32],
-        ciphertext_completion_rate: [u8; 32],
-        // survey_creator: Shared
-        survey_creator_pub_key: [u8; 32],
-        survey_creator_nonce: u128,
-        // public_viewer: Shared
-        public_viewer_pub_key: [u8; 32],
-        public_viewer_nonce: u128,
-        // respondent: Shared
-        respondent_pub_key: [u8; 32],
-        respondent_nonce: u128,
-    ) -> Result<()> {
-        // Set sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+#[callback_accounts("reveal_batch_analytics")]
+#[derive(Accounts)]
+pub struct RevealBatchAnalyticsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_BATCH_ANALYTICS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub response_batch: Account<'info, ResponseBatchBuffer>,
+}
 
-        // Build arguments for survey_analytics circuit (14 arguments total)
-        // Circuit signature: survey_analytics(user_data: Enc<Shared, [u32; 6]>, survey_creator: Shared, public_viewer: Shared, respondent: Shared)
-        let respondent_args = vec![
-            // user_data: Enc<Shared, [u32; 6]> - pubkey, nonce, then 6 ciphertexts
-            Argument::ArcisPubkey(user_pub_key),
-            Argument::PlaintextU128(user_nonce),
-            Argument::EncryptedU32(ciphertext_answer1),
-            Argument::EncryptedU32(ciphertext_answer2),
-            Argument::EncryptedU32(ciphertext_question_type1),
-            Argument::EncryptedU32(ciphertext_question_type2),
-            Argument::EncryptedU32(ciphertext_total_responses),
-            Argument::EncryptedU32(ciphertext_completion_rate),
-            // survey_creator: Shared
-            Argument::ArcisPubkey(survey_creator_pub_key),
-            Argument::PlaintextU128(survey_creator_nonce),
-            // public_viewer: Shared
-            Argument::ArcisPubkey(public_viewer_pub_key),
-            Argument::PlaintextU128(public_viewer_nonce),
-            // respondent: Shared
-            Argument::ArcisPubkey(respondent_pub_key),
-            Argument::PlaintextU128(respondent_nonce),
-        ];
+// ✅ RESTORED: Manual computation definition initialization functions (from backup)
+#[init_computation_definition_accounts("survey_analytics", payer)]
+#[derive(Accounts)]
+pub struct InitSurveyAnalyticsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Single computation for feedback
-        queue_computation(
-            ctx.accounts,
-            feedback_computation_offset,
-            respondent_args,
-            None,
-            vec![SurveyAnalyticsCallback::callback_ix(&[])],
-        )?;
+#[init_computation_definition_accounts("quiz_evaluation", payer)]
+#[derive(Accounts)]
+pub struct InitQuizEvaluationCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        Ok(())
-    }
+#[init_computation_definition_accounts("analytics_computation", payer)]
+#[derive(Accounts)]
+pub struct InitAnalyticsComputationCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // Separate instruction for student quiz computation
-    pub fn submit_quiz_student(
-        ctx: Context<SubmitQuizStudent>,
-        student_computation_offset: u64,     // For student results
-        ciphertext_answer1: [u8; 32],
-        ciphertext_answer2: [u8; 32],
-        ciphertext_correct_answer1: [u8; 32],
-        ciphertext_correct_answer2: [u8; 32],
-        ciphertext_points1: [u8; 32],
-        ciphertext_points2: [u8; 32],
-        ciphertext_threshold: [u8; 32],  // Encrypted passing threshold
-        ciphertext_class_total_students: [u8; 32],  // Encrypted class total students
-        ciphertext_class_total_score: [u8; 32],     // Encrypted class total score
-        ciphertext_class_completion_count: [u8; 32], // Encrypted class completion count
-        ciphertext_passing_requirement: [u8; 32],   // Encrypted passing requirement
-        student_pub_key: [u8; 32],       // Student's public key
-        student_nonce: u128,             // Student's nonce
-        instructor_pub_key: [u8; 32],    // Instructor's public key
-        instructor_nonce: u128,          // Instructor's nonce
-        creator_pub_key: [u8; 32],       // Creator's public key
-        creator_nonce: u128,             // Creator's nonce
-    ) -> Result<()> {
-        // Validate quiz is active
-        require!(ctx.accounts.quiz.is_active, ErrorCode::SurveyInactive);
-        
-        // Check if quiz has reached max responses
-        require!(
-            ctx.accounts.quiz.current_responses < ctx.accounts.quiz.max_responses,
-            ErrorCode::SurveyFull
-        );
-        
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+#[init_computation_definition_accounts("quiz_threshold_check", payer)]
+#[derive(Accounts)]
+pub struct InitQuizThresholdCheckCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Single computation for student results
-        let student_args = vec![
-            // user_answer1: Enc<Shared, u32> - 3 args
-            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer1
-            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer1
-            Argument::EncryptedU32(ciphertext_answer1),       // Encrypted user_answer1
-            // user_answer2: Enc<Shared, u32> - 3 args
-            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer2
-            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer2
-            Argument::EncryptedU32(ciphertext_answer2),       // Encrypted user_answer2
-            // correct_answer1: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_correct_answer1), // Encrypted correct_answer1
-            // correct_answer2: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_correct_answer2), // Encrypted correct_answer2
-            // points1: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_points1),       // Encrypted points1
-            // points2: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_points2),       // Encrypted points2
-            // passing_threshold: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_threshold),     // Encrypted passing_threshold
-            // class_total_students: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_total_students), // Encrypted class_total_students
-            // class_total_score: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_total_score), // Encrypted class_total_score
-            // class_completion_count: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_completion_count), // Encrypted class_completion_count
-            // student: Shared - 2 args
-            Argument::ArcisPubkey(student_pub_key),           // student public key
-            Argument::PlaintextU128(student_nonce),           // student nonce
-            // instructor: Shared - 2 args
-            Argument::ArcisPubkey(instructor_pub_key),        // instructor public key
-            Argument::PlaintextU128(instructor_nonce),        // instructor nonce
-            // quiz_creator: Shared - 2 args
-            Argument::ArcisPubkey(creator_pub_key),           // quiz_creator public key
-            Argument::PlaintextU128(creator_nonce),           // quiz_creator nonce
-        ];
+#[init_computation_definition_accounts("init_matrix_tally", payer)]
+#[derive(Accounts)]
+pub struct InitMatrixTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        queue_computation(
-            ctx.accounts,
-            student_computation_offset,
-            student_args,
-            None,
-            vec![QuizEvaluationCallback::callback_ix(&[])],
-        )?;
+#[init_computation_definition_accounts("accumulate_matrix_response", payer)]
+#[derive(Accounts)]
+pub struct InitAccumulateMatrixResponseCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Increment response counter
-        ctx.accounts.quiz.current_responses += 1;
+#[init_computation_definition_accounts("reveal_matrix_tally", payer)]
+#[derive(Accounts)]
+pub struct InitRevealMatrixTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Emit event for tracking - avoid wallet exposure
-        emit!(QuizResponseSubmitted {
-            quiz: ctx.accounts.quiz.key(),
-        });
+#[init_computation_definition_accounts("init_ranking_tally", payer)]
+#[derive(Accounts)]
+pub struct InitRankingTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        Ok(())
-    }
+#[init_computation_definition_accounts("accumulate_ranking_response", payer)]
+#[derive(Accounts)]
+pub struct InitAccumulateRankingResponseCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // Separate instruction for instructor quiz computation
-    pub fn submit_quiz_instructor(
-        ctx: Context<SubmitQuizInstructor>,
-        instructor_computation_offset: u64,  // For instructor analytics
-        ciphertext_answer1: [u8; 32],
-        ciphertext_answer2: [u8; 32],
-        ciphertext_correct_answer1: [u8; 32],
-        ciphertext_correct_answer2: [u8; 32],
-        ciphertext_points1: [u8; 32],
-        ciphertext_points2: [u8; 32],
-        ciphertext_threshold: [u8; 32],  // Encrypted passing threshold
-        ciphertext_class_total_students: [u8; 32],  // Encrypted class total students
-        ciphertext_class_total_score: [u8; 32],     // Encrypted class total score
-        ciphertext_class_completion_count: [u8; 32], // Encrypted class completion count
-        ciphertext_passing_requirement: [u8; 32],   // Encrypted passing requirement
-        student_pub_key: [u8; 32],       // Student's public key
-        student_nonce: u128,             // Student's nonce
-        instructor_pub_key: [u8; 32],    // Instructor's public key
-        instructor_nonce: u128,          // Instructor's nonce
-        creator_pub_key: [u8; 32],       // Creator's public key
-        creator_nonce: u128,             // Creator's nonce
-    ) -> Result<()> {
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
-
-        // Single computation for instructor analytics
-        let instructor_args = vec![
-            // user_answer1: Enc<Shared, u32> - 3 args
-            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer1
-            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer1
-            Argument::EncryptedU32(ciphertext_answer1),       // Encrypted user_answer1
-            // user_answer2: Enc<Shared, u32> - 3 args
-            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer2
-            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer2
-            Argument::EncryptedU32(ciphertext_answer2),       // Encrypted user_answer2
-            // correct_answer1: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_correct_answer1), // Encrypted correct_answer1
-            // correct_answer2: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_correct_answer2), // Encrypted correct_answer2
-            // points1: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_points1),       // Encrypted points1
-            // points2: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_points2),       // Encrypted points2
-            // passing_threshold: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_threshold),     // Encrypted passing_threshold
-            // class_total_students: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_total_students), // Encrypted class_total_students
-            // class_total_score: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_total_score), // Encrypted class_total_score
-            // class_completion_count: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_completion_count), // Encrypted class_completion_count
-            // student: Shared - 2 args
-            Argument::ArcisPubkey(student_pub_key),           // student public key
-            Argument::PlaintextU128(student_nonce),           // student nonce
-            // instructor: Shared - 2 args
-            Argument::ArcisPubkey(instructor_pub_key),        // instructor public key
-            Argument::PlaintextU128(instructor_nonce),        // instructor nonce
-            // quiz_creator: Shared - 2 args
-            Argument::ArcisPubkey(creator_pub_key),           // quiz_creator public key
-            Argument::PlaintextU128(creator_nonce),           // quiz_creator nonce
-        ];
-
-        queue_computation(
-            ctx.accounts,
-            instructor_computation_offset,
-            instructor_args,
-            None,
-            vec![QuizEvaluationCallback::callback_ix(&[])],
-        )?;
+#[init_computation_definition_accounts("reveal_ranking_tally", payer)]
+#[derive(Accounts)]
+pub struct InitRevealRankingTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        Ok(())
-    }
+#[init_computation_definition_accounts("init_nps_tally", payer)]
+#[derive(Accounts)]
+pub struct InitNpsTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // Separate instruction for creator quiz computation
-    pub fn submit_quiz_creator(
-        ctx: Context<SubmitQuizCreator>,
-        creator_computation_offset: u64,     // For quiz creator analytics
-        ciphertext_answer1: [u8; 32],
-        ciphertext_answer2: [u8; 32],
-        ciphertext_correct_answer1: [u8; 32],
-        ciphertext_correct_answer2: [u8; 32],
-        ciphertext_points1: [u8; 32],
-        ciphertext_points2: [u8; 32],
-        ciphertext_threshold: [u8; 32],  // Encrypted passing threshold
-        ciphertext_class_total_students: [u8; 32],  // Encrypted class total students
-        ciphertext_class_total_score: [u8; 32],     // Encrypted class total score
-        ciphertext_class_completion_count: [u8; 32], // Encrypted class completion count
-        ciphertext_passing_requirement: [u8; 32],   // Encrypted passing requirement
-        student_pub_key: [u8; 32],       // Student's public key
-        student_nonce: u128,             // Student's nonce
-        instructor_pub_key: [u8; 32],    // Instructor's public key
-        instructor_nonce: u128,          // Instructor's nonce
-        creator_pub_key: [u8; 32],       // Creator's public key
-        creator_nonce: u128,             // Creator's nonce
-    ) -> Result<()> {
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+#[init_computation_definition_accounts("accumulate_nps_response", payer)]
+#[derive(Accounts)]
+pub struct InitAccumulateNpsResponseCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Single computation for creator analytics
-        let creator_args = vec![
-            // user_answer1: Enc<Shared, u32> - 3 args
-            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer1
-            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer1
-            Argument::EncryptedU32(ciphertext_answer1),       // Encrypted user_answer1
-            // user_answer2: Enc<Shared, u32> - 3 args
-            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer2
-            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer2
-            Argument::EncryptedU32(ciphertext_answer2),       // Encrypted user_answer2
-            // correct_answer1: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_correct_answer1), // Encrypted correct_answer1
-            // correct_answer2: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_correct_answer2), // Encrypted correct_answer2
-            // points1: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_points1),       // Encrypted points1
-            // points2: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_points2),       // Encrypted points2
-            // passing_threshold: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_threshold),     // Encrypted passing_threshold
-            // class_total_students: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_total_students), // Encrypted class_total_students
-            // class_total_score: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_total_score), // Encrypted class_total_score
-            // class_completion_count: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(ciphertext_class_completion_count), // Encrypted class_completion_count
-            // student: Shared - 2 args
-            Argument::ArcisPubkey(student_pub_key),           // student public key
-            Argument::PlaintextU128(student_nonce),           // student nonce
-            // instructor: Shared - 2 args
-            Argument::ArcisPubkey(instructor_pub_key),        // instructor public key
-            Argument::PlaintextU128(instructor_nonce),        // instructor nonce
-            // quiz_creator: Shared - 2 args
-            Argument::ArcisPubkey(creator_pub_key),           // quiz_creator public key
-            Argument::PlaintextU128(creator_nonce),           // quiz_creator nonce
-        ];
+#[init_computation_definition_accounts("reveal_nps", payer)]
+#[derive(Accounts)]
+pub struct InitRevealNpsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        queue_computation(
-            ctx.accounts,
-            creator_computation_offset,
-            creator_args,
-            None,
-            vec![QuizEvaluationCallback::callback_ix(&[])],
-        )?;
+#[init_computation_definition_accounts("init_histogram", payer)]
+#[derive(Accounts)]
+pub struct InitHistogramCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        Ok(())
-    }
+#[init_computation_definition_accounts("accumulate_histogram_response", payer)]
+#[derive(Accounts)]
+pub struct InitAccumulateHistogramResponseCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // Create special survey for high scorers
-    pub fn create_special_survey(
-        ctx: Context<CreateSpecialSurvey>,
-        title: String,
-        description: String,
-        max_responses: u32,
-    ) -> Result<()> {
-        // Input validation
-        validate_string_length(&title, MAX_TITLE_LENGTH, "title")?;
-        validate_string_length(&description, MAX_DESCRIPTION_LENGTH, "description")?;
-        validate_max_responses(max_responses)?;
-        
-        let special_survey = &mut ctx.accounts.special_survey;
-        let quiz = &mut ctx.accounts.quiz;
-        let clock = Clock::get()?;
+#[init_computation_definition_accounts("reveal_percentiles", payer)]
+#[derive(Accounts)]
+pub struct InitRevealPercentilesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        require!(
-            quiz.creator == ctx.accounts.creator.key(),
-            ErrorCode::Unauthorized
-        );
+#[init_computation_definition_accounts("init_crosstab", payer)]
+#[derive(Accounts)]
+pub struct InitCrosstabCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Only allow special survey if enabled on the quiz
-        require!(
-            matches!(quiz.survey_type, SurveyType::Quiz { special_survey_enabled: true, .. }),
-            ErrorCode::InvalidSurveyType
-        );
+#[init_computation_definition_accounts("accumulate_crosstab_response", payer)]
+#[derive(Accounts)]
+pub struct InitAccumulateCrosstabResponseCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        special_survey.creator = ctx.accounts.creator.key();
-        special_survey.title = title;
-        special_survey.description = description;
-        special_survey.survey_type = SurveyType::Special;
-        special_survey.max_responses = max_responses;
-        special_survey.current_responses = 0;  // Initialize response counter
-        special_survey.is_active = true;
-        special_survey.is_publicly_browsable = false;  // Special surveys are NOT publicly browsable
-        special_survey.created_at = clock.unix_timestamp;
+#[init_computation_definition_accounts("reveal_crosstab", payer)]
+#[derive(Accounts)]
+pub struct InitRevealCrosstabCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        emit!(SpecialSurveyCreated {
-            quiz: quiz.key(),
-            special_survey: special_survey.key(),
-        });
+#[init_computation_definition_accounts("init_correlation_sums", payer)]
+#[derive(Accounts)]
+pub struct InitCorrelationSumsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        Ok(())
-    }
+#[init_computation_definition_accounts("init_item_stats", payer)]
+#[derive(Accounts)]
+pub struct InitItemStatsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// ✅ NEW: Submit quiz answers (lightweight - fixes transaction size issue)
-    /// This is STEP 1 of quiz submission - just stores encrypted answers
-    /// Auto-grading happens separately in STEP 2
-    pub fn submit_quiz_answers(
-        ctx: Context<SubmitQuizAnswers>,
-        encrypted_answers: [u8; 64],  // All answers encrypted as a single blob
-        answers_hash: [u8; 32],        // Hash for integrity verification
-    ) -> Result<()> {
-        let quiz = &mut ctx.accounts.quiz;
-        let answers_storage = &mut ctx.accounts.answers_storage;
-        let clock = Clock::get()?;
+#[init_computation_definition_accounts("reveal_item_analysis", payer)]
+#[derive(Accounts)]
+pub struct InitRevealItemAnalysisCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Validate quiz is active
-        require!(quiz.is_active, ErrorCode::SurveyInactive);
-        
-        // Check if quiz has reached max responses
-        require!(
-            quiz.current_responses < quiz.max_responses,
-            ErrorCode::SurveyFull
-        );
+#[init_computation_definition_accounts("init_leaderboard", payer)]
+#[derive(Accounts)]
+pub struct InitLeaderboardCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Initialize the answers storage
-        answers_storage.quiz = quiz.key();
-        answers_storage.student = ctx.accounts.payer.key();
-        answers_storage.encrypted_answers = encrypted_answers;
-        answers_storage.answers_hash = answers_hash;
-        answers_storage.submission_timestamp = clock.unix_timestamp;
-        answers_storage.grading_status = GradingStatus::Pending;
-        answers_storage.bump = ctx.bumps.answers_storage;
+#[init_computation_definition_accounts("record_leaderboard_score", payer)]
+#[derive(Accounts)]
+pub struct InitRecordLeaderboardScoreCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Increment response count
-        quiz.current_responses = quiz.current_responses.checked_add(1)
-            .ok_or(ErrorCode::IntegerOverflow)?;
+#[init_computation_definition_accounts("reveal_my_rank", payer)]
+#[derive(Accounts)]
+pub struct InitRevealMyRankCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        msg!("Quiz answers submitted successfully. Quiz: {}, Student: {}", 
-            quiz.key(), ctx.accounts.payer.key());
+#[init_computation_definition_accounts("reveal_leaderboard", payer)]
+#[derive(Accounts)]
+pub struct InitRevealLeaderboardCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        Ok(())
-    }
+#[init_computation_definition_accounts("init_answer_history", payer)]
+#[derive(Accounts)]
+pub struct InitAnswerHistoryCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// ✅ NEW: Compute quiz grade (triggered separately by backend/creator)
-    /// This is STEP 2 of quiz submission - heavy MPC computation
-    pub fn compute_quiz_grade(
-        ctx: Context<ComputeQuizGrade>,
-        computation_offset: u64,
-        // Minimal args - references the stored answers
-        student_pub_key: [u8; 32],
-        student_nonce: u128,
-        threshold: u8,  // Plaintext threshold (public parameter)
-    ) -> Result<()> {
-        let answers_storage = &mut ctx.accounts.answers_storage;
-        
-        // Validate that answers haven't been graded yet
-        require!(
-            answers_storage.grading_status == GradingStatus::Pending,
-            ErrorCode::AlreadyGraded
-        );
+#[init_computation_definition_accounts("check_answer_similarity", payer)]
+#[derive(Accounts)]
+pub struct InitCheckAnswerSimilarityCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Update status to computing
-        answers_storage.grading_status = GradingStatus::Computing;
+#[init_computation_definition_accounts("pool_quiz_evaluation", payer)]
+#[derive(Accounts)]
+pub struct InitPoolQuizEvaluationCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+#[init_computation_definition_accounts("init_adaptive_attempt", payer)]
+#[derive(Accounts)]
+pub struct InitAdaptiveAttemptCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // ✅ NICO FIXES: Reduced args from 26 → 11 using Argument::Account
-        // Circuit signature: user_answers: Enc<Shared, [u32; 2]>, quiz_data: Enc<Mxe, [u32; 8]>,
-        //                    student: Shared, instructor: Shared, quiz_creator: Shared
-        
-        let grading_args = vec![
-            // user_answers: Enc<Shared, [u32; 2]> - pubkey, nonce, Account = 3 args
-            // ✅ NICO FIX: Use Argument::Account instead of individual EncryptedU32
-            Argument::ArcisPubkey(student_pub_key),
-            Argument::PlaintextU128(student_nonce),
-            Argument::Account(
-                ctx.accounts.answers_storage.key(),
-                8 + 32 + 32,  // Discriminator + quiz + student = 72 bytes
-                64            // 2 answers * 32 bytes each
-            ),
-            
-            // quiz_data: Enc<Mxe, [u32; 8]> - nonce, Account = 2 args
-            // [correct1, correct2, points1, points2, threshold, stat1, stat2, stat3]
-            // ✅ NICO FIX: MXE knows its own pubkey + use Argument::Account!
-            Argument::PlaintextU128(ctx.accounts.quiz_grading_data.quiz_data_nonce),
-            Argument::Account(
-                ctx.accounts.quiz_grading_data.key(),
-                8 + 32,  // Discriminator + quiz = 40 bytes
-                256      // 8 values * 32 bytes each
-            ),
-            
-            // student: Shared - 2 args
-            Argument::ArcisPubkey(student_pub_key),
-            Argument::PlaintextU128(student_nonce),
-            
-            // instructor: Shared - 2 args
-            Argument::ArcisPubkey(ctx.accounts.quiz.instructor_arcium_pubkey),
-            Argument::PlaintextU128(Clock::get()?.unix_timestamp as u128),
-            
-            // quiz_creator: Shared - 2 args
-            Argument::ArcisPubkey(ctx.accounts.quiz.creator_arcium_pubkey),
-            Argument::PlaintextU128(Clock::get()?.unix_timestamp as u128 + 1),
-        ];
-        // Total: 3 + 2 + 2 + 2 + 2 = 11 args (down from 26!!!)
-        // ✅ NICO FIXES: Removed MXE pubkey + used Argument::Account for both arrays!
-
-        // Queue the MPC computation for quiz grading using Arcium SDK
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            grading_args,
-            None,
-            vec![QuizEvaluationCallback::callback_ix(&[])],
-        )?;
-
-        msg!("Quiz grading computation queued. Offset: {}", computation_offset);
-
-        Ok(())
-    }
+#[init_computation_definition_accounts("adaptive_step", payer)]
+#[derive(Accounts)]
+pub struct InitAdaptiveStepCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// ✅ NEW: Verify quiz threshold to grant special survey access
-    /// 
-    /// This instruction queues the `quiz_threshold_check` computation with correct argument count (11 args).
-    /// The arguments passed here MUST match the circuit signature:
-    /// 
-    /// Circuit parameters → Instruction arguments mapping:
-    /// - `encrypted_score: Enc<Shared, u32>` → 3 args: ArcisPubkey + PlaintextU128 + EncryptedU32
-    /// - `threshold: Enc<Mxe, u8>` → 1 arg: EncryptedU8
-    /// - `passing_requirement: Enc<Mxe, u32>` → 1 arg: EncryptedU32
-    /// - `student: Shared` → 2 args: ArcisPubkey + PlaintextU128
-    /// - `access_controller: Shared` → 2 args: ArcisPubkey + PlaintextU128
-    /// - `auditor: Shared` → 2 args: ArcisPubkey + PlaintextU128
-    /// 
-    /// # Flow
-    /// 1. Student completes quiz and gets encrypted score
-    /// 2. This instruction verifies the score meets the threshold
-    /// 3. If verification passes, student gains access to special surveys
-    /// 4. Results are re-encrypted for student, access controller, and auditor
-    // Separate instruction for student quiz verification
-    pub fn verify_quiz_student(
-        ctx: Context<VerifyQuizStudent>,
-        quiz: Pubkey,
-        student_computation_offset: u64,     // For student verification result
-        encrypted_score: [u8; 32], // This will be processed by MPC
-        encrypted_threshold: [u8; 32], // Encrypted threshold
-        encrypted_requirement: [u8; 32], // Encrypted additional requirement
-        student_pub_key: [u8; 32], // Student's public key
-        student_nonce: u128,       // Student's nonce
-        access_controller_pub_key: [u8; 32], // Access controller's public key
-        access_controller_nonce: u128,       // Access controller's nonce
-        auditor_pub_key: [u8; 32], // Auditor's public key
-        auditor_nonce: u128,       // Auditor's nonce
-    ) -> Result<()> {
-        // Set the sign PDA account bump
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
-        
-        let clock = Clock::get()?;
-        
-        // Verify the quiz exists and is active
-        require!(ctx.accounts.quiz.is_active, ErrorCode::SurveyInactive);
-        
-        // Verify the quiz has special survey enabled
-        require!(
-            matches!(ctx.accounts.quiz.survey_type, SurveyType::Quiz { special_survey_enabled: true, .. }),
-            ErrorCode::InvalidSurveyType
-        );
-        
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+#[init_computation_definition_accounts("reveal_adaptive_result", payer)]
+#[derive(Accounts)]
+pub struct InitRevealAdaptiveResultCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Computation 1: Verification result for student
-        // Circuit signature: encrypted_score: Enc<Shared, u32>, threshold: Enc<Mxe, u8>, passing_requirement: Enc<Mxe, u32>, student: Shared, access_controller: Shared, auditor: Shared
-        let student_args = vec![
-            // encrypted_score: Enc<Shared, u32> - 3 args
-            Argument::ArcisPubkey(student_pub_key),           // Encryption key for encrypted_score
-            Argument::PlaintextU128(student_nonce),           // Nonce for encrypted_score
-            Argument::EncryptedU32(encrypted_score),          // Encrypted score
-            // threshold: Enc<Mxe, u8> - 1 arg
-            Argument::EncryptedU8(encrypted_threshold),       // Encrypted threshold
-            // passing_requirement: Enc<Mxe, u32> - 1 arg
-            Argument::EncryptedU32(encrypted_requirement),    // Encrypted passing requirement
-            // student: Shared - 2 args
-            Argument::ArcisPubkey(student_pub_key),           // student public key
-            Argument::PlaintextU128(student_nonce),           // student nonce
-            // access_controller: Shared - 2 args
-            Argument::ArcisPubkey(access_controller_pub_key), // access_controller public key
-            Argument::PlaintextU128(access_controller_nonce), // access_controller nonce
-            // auditor: Shared - 2 args
-            Argument::ArcisPubkey(auditor_pub_key),           // auditor public key
-            Argument::PlaintextU128(auditor_nonce),           // auditor nonce
-        ];
+#[init_computation_definition_accounts("init_poll_tally", payer)]
+#[derive(Accounts)]
+pub struct InitPollTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        queue_computation(
-            ctx.accounts,
-            student_computation_offset,
-            student_args,
-            None,
-            vec![QuizThresholdCheckCallback::callback_ix(&[
-                CallbackAccount {
-                    pubkey: ctx.accounts.completion_proof.key(),
-                    is_writable: true,
-                }
-            ])],
-        )?;
-        
-        // ✅ FIXED: Store completion proof with proper data handling
-        let completion_proof = &mut ctx.accounts.completion_proof;
-        
-        // Check if this is a new completion proof or updating an existing one
-        if completion_proof.verified {
-            // If already verified, check if it's expired
-            let clock = Clock::get()?;
-            if completion_proof.expires_at > 0 && clock.unix_timestamp > completion_proof.expires_at {
-                // Expired - allow re-verification
-                completion_proof.verified = false;
-                completion_proof.verified_at = 0;
-            } else {
-                // Still valid - prevent re-verification
-                return Err(ErrorCode::AlreadyVerified.into());
-            }
-        }
-        
-        // Initialize or update completion proof data
-        completion_proof.quiz = quiz;
-        completion_proof.user = ctx.accounts.payer.key();
-        completion_proof.encrypted_score = encrypted_score;  // Store as bytes
-        completion_proof.threshold = 0;  // Threshold is now encrypted, not stored as plaintext
-        completion_proof.verified = false; // Will be set to true by MPC callback
-        completion_proof.verified_at = 0; // Will be set by MPC callback
-        completion_proof.mpc_computation_id = student_computation_offset; // Use first computation ID
-        
-        // Set expiration timestamp based on quiz configuration
-        let expiration_timestamp = match &ctx.accounts.quiz.survey_type {
-            SurveyType::Quiz { completion_proof_expiration_value, completion_proof_expiration_unit, .. } => {
-                calculate_expiration_timestamp(
-                    clock.unix_timestamp,
-                    *completion_proof_expiration_value,  // Borrow, so dereference
-                    completion_proof_expiration_unit    // Already a reference
-                )?
-            },
-            _ => clock.unix_timestamp + (7 * 24 * 60 * 60), // Default 7 days fallback
-        };
-        
-        completion_proof.expires_at = expiration_timestamp;
-        
-        // ✅ FIXED: Don't emit event here - wait for MPC callback to complete verification
-        // The QuizCompletionVerified event will be emitted by the callback with the actual result
-        
-        Ok(())
-    }
+#[init_computation_definition_accounts("cast_poll_vote", payer)]
+#[derive(Accounts)]
+pub struct InitCastPollVoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
+#[init_computation_definition_accounts("reveal_poll_tally", payer)]
+#[derive(Accounts)]
+pub struct InitRevealPollTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
+#[init_computation_definition_accounts("init_ranked_tally", payer)]
+#[derive(Accounts)]
+pub struct InitRankedTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // Submit special survey response with access control
-    pub fn submit_special_survey_response(
-        ctx: Context<SubmitSpecialSurveyResponse>,
-        computation_offset: u64,
-        // User data (all 6 values encrypted with same key/nonce)
-        ciphertext_answer1: [u8; 32],
-        ciphertext_answer2: [u8; 32],
-        ciphertext_question_type1: [u8; 32],
-        ciphertext_question_type2: [u8; 32],
-        ciphertext_total_responses: [u8; 32],
-        ciphertext_completion_rate: [u8; 32],
-        user_pub_key: [u8; 32],
-        user_nonce: u128,
-        // Recipients for re-encryption
-        creator_pub_key: [u8; 32],
-        creator_nonce: u128,
-        viewer_pub_key: [u8; 32],
-        viewer_nonce: u128,
-        respondent_pub_key: [u8; 32],
-        respondent_nonce: u128,
-    ) -> Result<()> {
-        // Set the sign PDA account bump
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
-        
-        // Access control: Verify user has valid quiz completion proof
-        require!(ctx.accounts.completion_proof.verified, ErrorCode::Unauthorized);
-        require!(ctx.accounts.completion_proof.user == ctx.accounts.payer.key(), ErrorCode::Unauthorized);
-        require!(ctx.accounts.completion_proof.quiz == ctx.accounts.quiz.key(), ErrorCode::Unauthorized);
-        
-        // Check if completion proof has expired
-        let clock = Clock::get()?;
-        require!(
-            clock.unix_timestamp <= ctx.accounts.completion_proof.expires_at,
-            ErrorCode::ProofExpired
-        );
-        
-        // Verify special survey is active
-        require!(ctx.accounts.special_survey.is_active, ErrorCode::SurveyInactive);
-        
-        // Check if special survey has reached max responses
-        require!(
-            ctx.accounts.special_survey.current_responses < ctx.accounts.special_survey.max_responses,
-            ErrorCode::SurveyFull
-        );
-        
-        // ✅ FIXED: Queue computation with array approach (Following Nico's advice)
-        // Circuit signature: user_data: Enc<Shared, [u32; 6]>, survey_creator: Shared, public_viewer: Shared, respondent: Shared
-        let args = vec![
-            // user_data: Enc<Shared, [u32; 6]> - pubkey, nonce, then 6 ciphertexts
-            Argument::ArcisPubkey(user_pub_key),
-            Argument::PlaintextU128(user_nonce),
-            Argument::EncryptedU32(ciphertext_answer1),
-            Argument::EncryptedU32(ciphertext_answer2),
-            Argument::EncryptedU32(ciphertext_question_type1),
-            Argument::EncryptedU32(ciphertext_question_type2),
-            Argument::EncryptedU32(ciphertext_total_responses),
-            Argument::EncryptedU32(ciphertext_completion_rate),
-            // survey_creator: Shared - 2 args
-            Argument::ArcisPubkey(creator_pub_key),
-            Argument::PlaintextU128(creator_nonce),
-            // public_viewer: Shared - 2 args
-            Argument::ArcisPubkey(viewer_pub_key),
-            Argument::PlaintextU128(viewer_nonce),
-            // respondent: Shared - 2 args
-            Argument::ArcisPubkey(respondent_pub_key),
-            Argument::PlaintextU128(respondent_nonce),
-        ];
+#[init_computation_definition_accounts("cast_ranked_ballot", payer)]
+#[derive(Accounts)]
+pub struct InitCastRankedBallotCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![SurveyAnalyticsCallback::callback_ix(&[])],
-        )?;
+#[init_computation_definition_accounts("run_instant_runoff", payer)]
+#[derive(Accounts)]
+pub struct InitRunInstantRunoffCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Increment response counter
-        ctx.accounts.special_survey.current_responses += 1;
+#[init_computation_definition_accounts("init_text_tally", payer)]
+#[derive(Accounts)]
+pub struct InitTextTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        emit!(SpecialSurveyResponseSubmitted {
-            special_survey: ctx.accounts.special_survey.key(),
-            quiz: ctx.accounts.quiz.key(),
-        });
+#[init_computation_definition_accounts("accumulate_text_response", payer)]
+#[derive(Accounts)]
+pub struct InitAccumulateTextResponseCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        Ok(())
-    }
+#[init_computation_definition_accounts("reveal_text_stats", payer)]
+#[derive(Accounts)]
+pub struct InitRevealTextStatsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // Delete survey/quiz
-    pub fn delete_survey(ctx: Context<DeleteSurvey>) -> Result<()> {
-        let survey = &mut ctx.accounts.survey;
-        let clock = Clock::get()?;
-        
-        require!(
-            survey.creator == ctx.accounts.creator.key(),
-            ErrorCode::Unauthorized
-        );
+#[init_computation_definition_accounts("init_keyword_tally", payer)]
+#[derive(Accounts)]
+pub struct InitKeywordTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        survey.is_active = false;
+#[init_computation_definition_accounts("accumulate_keywords", payer)]
+#[derive(Accounts)]
+pub struct InitAccumulateKeywordsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        emit!(SurveyDeleted {
-            survey: survey.key(),
-            creator: survey.creator,
-            deleted_at: clock.unix_timestamp,
-        });
+#[init_computation_definition_accounts("reveal_keyword_table", payer)]
+#[derive(Accounts)]
+pub struct InitRevealKeywordTableCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-        Ok(())
-    }
+#[init_computation_definition_accounts("init_batch_totals", payer)]
+#[derive(Accounts)]
+pub struct InitBatchTotalsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // ✅ REVERTED: Use standard Arcium v0.3.0 callback pattern
-    // Note: Callbacks will be handled via standard Anchor event patterns
+#[init_computation_definition_accounts("process_response_batch", payer)]
+#[derive(Accounts)]
+pub struct InitProcessResponseBatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // ✅ REVERTED: Use standard Arcium v0.3.0 callback pattern
+#[init_computation_definition_accounts("reveal_batch_analytics", payer)]
+#[derive(Accounts)]
+pub struct InitRevealBatchAnalyticsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by the arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    // ✅ FIXED: Implement callbacks using standard Arcium v0.3.0 patterns
-    #[arcium_callback(encrypted_ix = "survey_analytics")]
-    pub fn survey_analytics_callback(
-        ctx: Context<SurveyAnalyticsCallback>,
-        output: ComputationOutputs<SurveyAnalyticsOutput>,
-    ) -> Result<()> {
-        let result = match output {
-            ComputationOutputs::Success(data) => data,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
-
-        // ✅ FIX: Store analytics data in the analytics storage account
-        let analytics_storage = &mut ctx.accounts.analytics_storage;
-        let clock = Clock::get()?;
-        
-        // Store the encrypted analytics data
-        // The MPC output structure has field_0, field_1, field_2 - we need to access the correct fields
-        analytics_storage.survey = ctx.accounts.survey.key();
-        analytics_storage.creator_analytics = result.field_0.field_0.ciphertexts[0];  // Extract ciphertext from first field
-        analytics_storage.public_summary = result.field_0.field_1.ciphertexts[0];     // Extract ciphertext from second field
-        analytics_storage.respondent_feedback = result.field_0.field_2.ciphertexts[0]; // Extract ciphertext from third field
-        analytics_storage.nonce = 0; // TODO: Extract nonce from MPC output if available
-        analytics_storage.created_at = clock.unix_timestamp;
-        analytics_storage.updated_at = clock.unix_timestamp;
+// Helper function to calculate expiration timestamp
+fn calculate_expiration_timestamp(
+    current_timestamp: i64,
+    value: u32,
+    unit: &ExpirationUnit,
+) -> Result<i64> {
+    let seconds_to_add = match unit {
+        ExpirationUnit::Minutes => {
+            let minutes = value as i64;
+            minutes.checked_mul(60).ok_or::<anchor_lang::error::Error>(ErrorCode::IntegerOverflow.into())?
+        },
+        ExpirationUnit::Hours => {
+            let hours = value as i64;
+            hours.checked_mul(60 * 60).ok_or::<anchor_lang::error::Error>(ErrorCode::IntegerOverflow.into())?
+        },
+        ExpirationUnit::Days => {
+            let days = value as i64;
+            days.checked_mul(24 * 60 * 60).ok_or::<anchor_lang::error::Error>(ErrorCode::IntegerOverflow.into())?
+        },
+    };
+    
+    current_timestamp.checked_add(seconds_to_add).ok_or::<anchor_lang::error::Error>(ErrorCode::IntegerOverflow.into())
+}
 
-        // ✅ TODO: Re-enable event after fixing output struct types
-        // emit!(SurveyAnalyticsComplete {
-        //     analytics_data_ct: result.field_0,
-        // });
+// ✅ REMOVED: Fake hash function - using direct user references instead
 
-        Ok(())
+// Helper function to validate expiration configuration
+fn validate_expiration_config(
+    value: u32,
+    unit: &ExpirationUnit,
+) -> Result<()> {
+    match unit {
+        ExpirationUnit::Minutes => {
+            require!(value > 0 && value <= 525600, ErrorCode::InvalidExpirationValue); // Max 1 year in minutes
+        },
+        ExpirationUnit::Hours => {
+            require!(value > 0 && value <= 8760, ErrorCode::InvalidExpirationValue); // Max 1 year in hours
+        },
+        ExpirationUnit::Days => {
+            require!(value > 0 && value <= 365, ErrorCode::InvalidExpirationValue); // Max 1 year in days
+        },
     }
+    Ok(())
+}
 
-    #[arcium_callback(encrypted_ix = "quiz_evaluation")]
-    pub fn quiz_evaluation_callback(
-        ctx: Context<QuizEvaluationCallback>,
-        output: ComputationOutputs<QuizEvaluationOutput>,
-    ) -> Result<()> {
-        let result = match output {
-            ComputationOutputs::Success(data) => data,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+#[arcium_program]
+pub mod se_qure {
+    use super::*;
+    
+    // ✅ FIXED: Removed manual callback struct imports
+    // Arcium automatically generates these types when using #[callback_accounts] macro
 
-        // ✅ TODO: Re-enable event after fixing output struct types
-        // emit!(QuizEvaluationComplete {
-        //     evaluation_data_ct: result.field_0,
-        // });
+    // ✅ REMOVED: Duplicate callback functions - Arcium macros auto-generate these
 
+
+
+    // ✅ REVERTED: Use standard Arcium v0.3.0 patterns without problematic macros
+    // Note: Computation definitions will be initialized via standard Anchor patterns
+
+    // ✅ FIXED: Initialize Sign PDA Account for cross-program invocations
+    pub fn init_sign_pda(ctx: Context<InitSignPda>) -> Result<()> {
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "analytics_computation")]
-    pub fn analytics_computation_callback(
-        ctx: Context<AnalyticsComputationCallback>,
-        output: ComputationOutputs<AnalyticsComputationOutput>,
-    ) -> Result<()> {
-        let result = match output {
-            ComputationOutputs::Success(data) => data,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    // ✅ UPDATED: Computation definition initialization with Supabase circuit URLs
+    pub fn init_survey_analytics_comp_def(ctx: Context<InitSurveyAnalyticsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/survey_analytics.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
 
-        // ✅ TODO: Re-enable event after fixing output struct types
-        // emit!(AnalyticsComputed {
-        //     computation_data_ct: result.field_0,
-        // });
+    pub fn init_quiz_evaluation_comp_def(ctx: Context<InitQuizEvaluationCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/quiz_evaluation.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
 
+    pub fn init_analytics_computation_comp_def(ctx: Context<InitAnalyticsComputationCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/analytics_computation.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "quiz_threshold_check")]
-    pub fn quiz_threshold_check_callback(
-        ctx: Context<QuizThresholdCheckCallback>,
-        output: ComputationOutputs<QuizThresholdCheckOutput>,
-    ) -> Result<()> {
-        let result = match output {
-            ComputationOutputs::Success(data) => data,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    pub fn init_quiz_threshold_check_comp_def(ctx: Context<InitQuizThresholdCheckCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/quiz_threshold_check.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
 
-        let clock = Clock::get()?;
-        
-        // Update completion proof account with MPC result
-        let completion_proof = &mut ctx.accounts.completion_proof;
-        completion_proof.verified = true; // MPC has verified the threshold check
-        completion_proof.verified_at = clock.unix_timestamp;
-        // Extract the actual encrypted verification result from MPC output
-        // result.field_0 = ThresholdVerification (for student)
-        // result.field_1 = AccessControl (for access controller)
-        // result.field_2 = AuditRecord (for auditor)
-        completion_proof.encrypted_verification_result = result.field_0.field_0.ciphertexts[0];
-        
-        // TODO: Update quiz aggregation account here
-        // This would require adding the quiz_aggregation account to the callback struct
-        // and implementing the aggregation logic
-        
-        // ✅ TODO: Re-enable event after fixing output struct types
-        // emit!(QuizCompletionVerified {
-        //     verified_at: clock.unix_timestamp,
-        //     verification_data_ct: result.field_0,
-        // });
 
+    pub fn init_matrix_tally_comp_def(ctx: Context<InitMatrixTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_matrix_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
         Ok(())
     }
 
+    pub fn init_accumulate_matrix_response_comp_def(ctx: Context<InitAccumulateMatrixResponseCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/accumulate_matrix_response.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
 
-
-    // ✅ DASHBOARD: Create user account for personalized dashboard
-    pub fn create_user_account(
-        ctx: Context<CreateUserAccount>,
-        username: String,
-        email: String,
-    ) -> Result<()> {
-        let user_account = &mut ctx.accounts.user_account;
-        let clock = Clock::get()?;
-        
-        // Input validation
-        require!(username.len() <= 50, ErrorCode::UsernameTooLong);
-        require!(email.len() <= 100, ErrorCode::EmailTooLong);
-        require!(username.len() >= 3, ErrorCode::UsernameTooShort);
-        
-        // Security validations
-        validate_no_xss(&username)?;
-        validate_no_sql_injection(&username)?;
-        validate_no_xss(&email)?;
-        validate_no_sql_injection(&email)?;
-        
-        user_account.creator = ctx.accounts.creator.key();
-        user_account.username = username;
-        user_account.email = email;
-        user_account.created_at = clock.unix_timestamp;
-        user_account.is_active = true;
-        user_account.last_login = clock.unix_timestamp;
-        
-        emit!(UserAccountCreated {
-            user: ctx.accounts.creator.key(),
-            username: user_account.username.clone(),
-            created_at: clock.unix_timestamp,
-        });
-        
+    pub fn init_reveal_matrix_tally_comp_def(ctx: Context<InitRevealMatrixTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_matrix_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
         Ok(())
     }
 
-    // ✅ DASHBOARD: Get user's personalized dashboard data
-    pub fn get_user_dashboard(
-        ctx: Context<GetUserDashboard>,
-    ) -> Result<UserDashboard> {
-        let user = ctx.accounts.user.key();
-        let clock = Clock::get()?;
-        
-        // Update last login
-        let user_account = &mut ctx.accounts.user_account;
-        user_account.last_login = clock.unix_timestamp;
-        
-        // Build dashboard data (this would typically query from events/logs)
-        let dashboard = UserDashboard {
-            surveys_created: vec![], // Would be populated from survey creation events
-            responses_submitted: vec![], // Would be populated from response events
-            quiz_results: vec![], // Would be populated from quiz completion events
-            special_survey_access: vec![], // Would be populated from special survey events
-        };
-        
-        emit!(UserDashboardAccessed {
-            user,
-            accessed_at: clock.unix_timestamp,
-        });
-        
-        Ok(dashboard)
+    pub fn init_ranking_tally_comp_def(ctx: Context<InitRankingTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_ranking_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_accumulate_ranking_response_comp_def(ctx: Context<InitAccumulateRankingResponseCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/accumulate_ranking_response.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_ranking_tally_comp_def(ctx: Context<InitRevealRankingTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_ranking_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_nps_tally_comp_def(ctx: Context<InitNpsTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_nps_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_accumulate_nps_response_comp_def(ctx: Context<InitAccumulateNpsResponseCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/accumulate_nps_response.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_nps_comp_def(ctx: Context<InitRevealNpsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_nps.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_histogram_comp_def(ctx: Context<InitHistogramCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_histogram.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_accumulate_histogram_response_comp_def(ctx: Context<InitAccumulateHistogramResponseCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/accumulate_histogram_response.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_percentiles_comp_def(ctx: Context<InitRevealPercentilesCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_percentiles.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_crosstab_comp_def(ctx: Context<InitCrosstabCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_crosstab.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_accumulate_crosstab_response_comp_def(ctx: Context<InitAccumulateCrosstabResponseCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/accumulate_crosstab_response.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_crosstab_comp_def(ctx: Context<InitRevealCrosstabCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_crosstab.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_correlation_sums_comp_def(ctx: Context<InitCorrelationSumsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_correlation_sums.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_item_stats_comp_def(ctx: Context<InitItemStatsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_item_stats.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_item_analysis_comp_def(ctx: Context<InitRevealItemAnalysisCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_item_analysis.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_leaderboard_comp_def(ctx: Context<InitLeaderboardCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_leaderboard.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_record_leaderboard_score_comp_def(ctx: Context<InitRecordLeaderboardScoreCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/record_leaderboard_score.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_my_rank_comp_def(ctx: Context<InitRevealMyRankCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_my_rank.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
     }
 
+    pub fn init_reveal_leaderboard_comp_def(ctx: Context<InitRevealLeaderboardCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_leaderboard.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
 
+    pub fn init_answer_history_comp_def(ctx: Context<InitAnswerHistoryCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_answer_history.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_check_answer_similarity_comp_def(ctx: Context<InitCheckAnswerSimilarityCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/check_answer_similarity.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_pool_quiz_evaluation_comp_def(ctx: Context<InitPoolQuizEvaluationCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/pool_quiz_evaluation.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_adaptive_attempt_comp_def(ctx: Context<InitAdaptiveAttemptCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_adaptive_attempt.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_adaptive_step_comp_def(ctx: Context<InitAdaptiveStepCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/adaptive_step.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_adaptive_result_comp_def(ctx: Context<InitRevealAdaptiveResultCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_adaptive_result.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_poll_tally_comp_def(ctx: Context<InitPollTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_poll_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_cast_poll_vote_comp_def(ctx: Context<InitCastPollVoteCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/cast_poll_vote.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_poll_tally_comp_def(ctx: Context<InitRevealPollTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_poll_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_ranked_tally_comp_def(ctx: Context<InitRankedTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_ranked_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_cast_ranked_ballot_comp_def(ctx: Context<InitCastRankedBallotCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/cast_ranked_ballot.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_run_instant_runoff_comp_def(ctx: Context<InitRunInstantRunoffCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/run_instant_runoff.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_text_tally_comp_def(ctx: Context<InitTextTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_text_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_accumulate_text_response_comp_def(ctx: Context<InitAccumulateTextResponseCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/accumulate_text_response.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_text_stats_comp_def(ctx: Context<InitRevealTextStatsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_text_stats.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_keyword_tally_comp_def(ctx: Context<InitKeywordTallyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_keyword_tally.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_accumulate_keywords_comp_def(ctx: Context<InitAccumulateKeywordsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/accumulate_keywords.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_keyword_table_comp_def(ctx: Context<InitRevealKeywordTableCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_keyword_table.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_batch_totals_comp_def(ctx: Context<InitBatchTotalsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/init_batch_totals.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_process_response_batch_comp_def(ctx: Context<InitProcessResponseBatchCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/process_response_batch.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reveal_batch_analytics_comp_def(ctx: Context<InitRevealBatchAnalyticsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            true,
+            0,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://eswjamjanympzqopbqyt.supabase.co/storage/v1/object/public/arcium%20circuits/reveal_batch_analytics.arcis".to_string(),
+                hash: [0; 32], // Hash verification not enforced yet
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    // ✅ FIXED: Store application preferences for survey/quiz computations
+    // Note: This is application-level metadata, not Arcium MXE configuration
+    // MXE configuration happens through Arcium's infrastructure, not custom program accounts
+    // Only the program's upgrade authority can create them; it becomes the admin
+    pub fn set_survey_computation_preferences(
+        ctx: Context<SetSurveyComputationPreferences>,
+        preferred_encryption_scheme: EncryptionScheme,
+        preferred_data_provisioning: DataProvisioningMethod,
+        preferred_mpc_protocol: MPCProtocol,
+    ) -> Result<()> {
+        let app_preferences = &mut ctx.accounts.app_preferences;
+        app_preferences.admin = ctx.accounts.admin.key();
+        app_preferences.bump = ctx.bumps.app_preferences;
+        app_preferences.configure(
+            preferred_encryption_scheme,
+            preferred_data_provisioning,
+            preferred_mpc_protocol,
+        )
+    }
+
+    // ✅ NEW: Change the global computation preferences (admin only)
+    /// Surveys without overrides follow them; a survey's data provisioning is pinned
+    /// by its first response, so only surveys nobody has answered yet switch.
+    pub fn update_survey_computation_preferences(
+        ctx: Context<ManageSurveyComputationPreferences>,
+        preferred_encryption_scheme: EncryptionScheme,
+        preferred_data_provisioning: DataProvisioningMethod,
+        preferred_mpc_protocol: MPCProtocol,
+    ) -> Result<()> {
+        ctx.accounts.app_preferences.configure(
+            preferred_encryption_scheme,
+            preferred_data_provisioning,
+            preferred_mpc_protocol,
+        )
+    }
+
+    // ✅ NEW: Hand the computation preferences admin role to another wallet
+    pub fn transfer_admin(
+        ctx: Context<ManageSurveyComputationPreferences>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        let app_preferences = &mut ctx.accounts.app_preferences;
+        let previous_admin = app_preferences.admin;
+        app_preferences.admin = new_admin;
+
+        emit!(PreferencesAdminTransferred {
+            previous_admin,
+            new_admin,
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Repair preferences created before the admin role existed (upgrade authority only)
+    /// The original set_survey_computation_preferences let anyone create the account and
+    /// stored no bump. Such accounts still load (INIT_SPACE counts the discriminator twice,
+    /// so the zeroed padding reads as `bump`); this stores the real bump, tops the account
+    /// up to the current size and makes the upgrade authority the admin.
+    pub fn migrate_survey_computation_preferences(
+        ctx: Context<MigrateSurveyComputationPreferences>,
+    ) -> Result<()> {
+        let app_preferences = &mut ctx.accounts.app_preferences;
+        let previous_admin = app_preferences.admin;
+        app_preferences.admin = ctx.accounts.authority.key();
+        app_preferences.bump = ctx.bumps.app_preferences;
+
+        emit!(PreferencesAdminTransferred {
+            previous_admin,
+            new_admin: app_preferences.admin,
+        });
+
+        Ok(())
+    }
+
+
+
+    // Create a new survey with timestamp-based PDA (allows multiple surveys per creator)
+    // Only stores verification data on blockchain - sensitive data encrypted in Supabase
+    pub fn create_survey_with_timestamp(
+        ctx: Context<CreateSurveyWithTimestamp>,
+        _timestamp: i64,
+        slug: String,
+        title: String,
+        description: String,
+        survey_type: SurveyType,
+        max_responses: u32,
+        instructor_arcium_pubkey: [u8; 32],  // Add this parameter
+        creator_arcium_pubkey: [u8; 32],     // Add this parameter
+        default_language: String,            // BCP 47 tag of question_text / option labels
+    ) -> Result<()> {
+        let survey = &mut ctx.accounts.survey;
+        let clock = Clock::get()?;
+        
+        // Input validation with security checks
+        validate_slug(&slug)?;
+        validate_string_length(&title, MAX_TITLE_LENGTH, "title")?;
+        validate_string_length(&description, MAX_DESCRIPTION_LENGTH, "description")?;
+        validate_max_responses(max_responses)?;
+        validate_language_tag(&default_language)?;
+        
+        // Security validations
+        validate_no_xss(&title)?;
+        validate_no_xss(&description)?;
+        validate_no_sql_injection(&title)?;
+        validate_no_sql_injection(&description)?;
+        
+        // ✅ FIXED: Validate space requirements before account creation
+        let required_space = Survey::calculate_space(&slug, &title);
+        require!(
+            required_space <= 15000, // Increased limit to accommodate questions storage
+            ErrorCode::SurveyTooLarge
+        );
+        
+        // Validate quiz expiration configuration if it's a quiz
+        if let SurveyType::Quiz { completion_proof_expiration_value, completion_proof_expiration_unit, .. } = &survey_type {
+            validate_expiration_config(*completion_proof_expiration_value, completion_proof_expiration_unit)?;
+        }
+        if let SurveyType::Poll { option_count, closes_at, ranked_choice, .. } = &survey_type {
+            let max_options = if *ranked_choice { MAX_IRV_CANDIDATES } else { MAX_POLL_OPTIONS };
+            require!(
+                (MIN_POLL_OPTIONS..=max_options).contains(&(*option_count as usize))
+                    && *closes_at > clock.unix_timestamp,
+                ErrorCode::InvalidPollConfig
+            );
+        }
+        
+        survey.creator = ctx.accounts.creator.key();
+        survey.slug = slug;
+        survey.title = title;
+        survey.description = description;
+        survey.survey_type = survey_type;
+        survey.questions = Vec::new();  // ✅ FIXED: Initialize questions as empty vector
+        survey.question_content = None;  // Set later via set_question_content_commitment
+        survey.default_language = default_language;
+        survey.report_decimals = 0;  // Whole numbers until the creator opts into more precision
+        survey.shuffle_answers = false;  // Canonical order until the creator opts in
+        survey.data_provisioning = None;  // Follow SurveyComputationPreferences until overridden
+        survey.mpc_protocol = None;
+        survey.max_responses = max_responses;
+        survey.current_responses = 0;  // Initialize response counter
+        survey.is_active = true;
+        survey.is_publicly_browsable = true;  // Regular surveys are publicly browsable
+        survey.modification_count = 0;  // Initialize modification counter
+        survey.created_at = clock.unix_timestamp;
+        survey.mpc_computation_id = 0;  // ✅ FIXED: Initialize MPC computation reference
+        
+        // ✅ NICO FIX: Store Arcium encryption keys
+        survey.instructor_arcium_pubkey = instructor_arcium_pubkey;
+        survey.creator_arcium_pubkey = creator_arcium_pubkey;
+
+        emit!(SurveyCreated {
+            survey: survey.key(),
+            survey_type: match survey.survey_type {
+                SurveyType::Basic => "basic".to_string(),
+                SurveyType::Quiz { .. } => "quiz".to_string(),
+                SurveyType::Special => "special".to_string(),
+                SurveyType::Poll { .. } => "poll".to_string(),
+            },
+            created_at: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Add questions to a survey
+    pub fn add_questions(
+        ctx: Context<AddQuestions>,
+        questions: Vec<QuestionData>,
+        translations: Vec<QuestionTranslations>,
+        display_conditions: Vec<QuestionDisplayConditions>,
+        grading: Vec<QuestionGrading>,
+    ) -> Result<()> {
+        // Input validation
+        validate_questions(
+            &questions,
+            &translations,
+            &display_conditions,
+            &grading,
+            &ctx.accounts.survey.questions,
+            &ctx.accounts.survey.default_language,
+        )?;
+        
+        let survey = &mut ctx.accounts.survey;
+
+        require!(
+            survey.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Surveys with committed off-chain content never store question text on-chain
+        require!(
+            survey.question_content.is_none(),
+            ErrorCode::QuestionStorageModeConflict
+        );
+
+        let question_count = questions.len() as u32;
+        
+        for question_data in questions {
+            survey.questions.push(question_data);
+        }
+        survey.question_translations.extend(translations);
+        survey.question_display_conditions.extend(display_conditions);
+        survey.question_grading.extend(grading);
+
+        emit!(QuestionsAdded {
+            survey: survey.key(),
+            question_count,
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Commit to off-chain question content instead of storing questions on-chain
+    /// The survey keeps only a hash (or Merkle root) of the question document plus its URI,
+    /// so rent stays flat regardless of survey length. Clients verify downloaded content
+    /// with client/question_content.ts. Question ids are document indices: per-question
+    /// tallies take the question with its Merkle proof (see Survey::resolve_question).
+    pub fn set_question_content_commitment(
+        ctx: Context<SetQuestionContentCommitment>,
+        scheme: ContentCommitmentScheme,
+        content_hash: [u8; 32],
+        content_uri: String,
+        question_count: u32,
+    ) -> Result<()> {
+        let survey = &mut ctx.accounts.survey;
+
+        require!(
+            survey.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // On-chain questions and committed content are mutually exclusive
+        require!(
+            survey.questions.is_empty(),
+            ErrorCode::QuestionStorageModeConflict
+        );
+
+        // Responses reference questions by index, so content is frozen once answers exist
+        require!(
+            survey.current_responses == 0,
+            ErrorCode::QuestionContentFrozen
+        );
+
+        validate_content_commitment(&content_hash, &content_uri, question_count)?;
+
+        survey.question_content = Some(QuestionContentCommitment {
+            scheme: scheme.clone(),
+            content_hash,
+            content_uri,
+            question_count,
+        });
+
+        emit!(QuestionContentCommitted {
+            survey: survey.key(),
+            scheme: format!("{:?}", scheme),
+            content_hash,
+            question_count,
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Choose how many decimal places MPC reports use for averages and percentages
+    /// Values come back as integers scaled by 10^report_decimals (e.g. 4.25 at 2 decimals
+    /// is 425). Only affects computations queued after the change.
+    pub fn set_report_precision(
+        ctx: Context<SetReportPrecision>,
+        report_decimals: u8,
+    ) -> Result<()> {
+        let survey = &mut ctx.accounts.survey;
+
+        require!(
+            survey.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            report_decimals <= MAX_REPORT_DECIMALS,
+            ErrorCode::InvalidReportPrecision
+        );
+
+        survey.report_decimals = report_decimals;
+
+        emit!(ReportPrecisionUpdated {
+            survey: survey.key(),
+            report_decimals,
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Turn per-attempt question/option shuffling on or off for a quiz
+    /// Locked once anyone has responded, since graded attempts were shuffled with the old setting.
+    pub fn set_answer_shuffling(
+        ctx: Context<SetAnswerShuffling>,
+        enabled: bool,
+    ) -> Result<()> {
+        let quiz = &mut ctx.accounts.quiz;
+
+        require!(
+            quiz.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            matches!(quiz.survey_type, SurveyType::Quiz { .. }),
+            ErrorCode::InvalidSurveyType
+        );
+        require!(
+            quiz.current_responses == 0 && quiz.question_content.is_none(),
+            ErrorCode::ShuffleUnavailable
+        );
+        require_single_order_mode(enabled, !ctx.accounts.question_pool.data_is_empty())?;
+
+        quiz.shuffle_answers = enabled;
+
+        emit!(AnswerShufflingUpdated {
+            quiz: quiz.key(),
+            enabled,
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Override the global computation preferences for one survey
+    /// `None` follows SurveyComputationPreferences. `CreatorOnly` skips MPC entirely:
+    /// respondents encrypt answers straight to `creator_arcium_pubkey` and
+    /// `submit_creator_encrypted_response` just stores them. `Batch` buffers responses and
+    /// runs one computation per RESPONSE_BATCH_SIZE of them (see `init_response_batch`).
+    /// The data provisioning is pinned by the first response; the MPC protocol can change.
+    pub fn set_computation_overrides(
+        ctx: Context<SetComputationOverrides>,
+        data_provisioning: Option<DataProvisioningMethod>,
+        mpc_protocol: Option<MPCProtocol>,
+    ) -> Result<()> {
+        let survey = &mut ctx.accounts.survey;
+
+        require!(
+            survey.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            survey.current_responses == 0 || data_provisioning == survey.data_provisioning,
+            ErrorCode::DataProvisioningFrozen
+        );
+        // Grading and tallies need a computation per response
+        require!(
+            matches!(data_provisioning, None | Some(DataProvisioningMethod::Direct))
+                || matches!(survey.survey_type, SurveyType::Basic),
+            ErrorCode::InvalidSurveyType
+        );
+
+        survey.data_provisioning = data_provisioning;
+        survey.mpc_protocol = mpc_protocol;
+
+        emit!(ComputationOverridesUpdated {
+            survey: survey.key(),
+            data_provisioning: format!("{:?}", survey.data_provisioning),
+            mpc_protocol: format!("{:?}", survey.mpc_protocol),
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Store one respondent's answers to a CreatorOnly survey (no MPC round trip)
+    /// The response lives at a PDA of the fresh `respondent_pub_key`, so it can't be
+    /// looked up from a wallet; a separate receipt keeps one response per wallet.
+    pub fn submit_creator_encrypted_response(
+        ctx: Context<SubmitCreatorEncryptedResponse>,
+        respondent_pub_key: [u8; 32],
+        nonce: u128,
+        ciphertexts: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let survey = &mut ctx.accounts.survey;
+
+        require!(survey.is_active, ErrorCode::SurveyInactive);
+        let route = survey.computation_route(&ctx.accounts.app_preferences)?;
+        route.require_provisioning(DataProvisioningMethod::CreatorOnly)?;
+        survey.pin_data_provisioning(&route);
+        require!(
+            survey.current_responses < survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        require!(
+            !ciphertexts.is_empty() && ciphertexts.len() <= MAX_CREATOR_RESPONSE_CIPHERTEXTS,
+            ErrorCode::InvalidCreatorResponse
+        );
+
+        survey.current_responses = survey.current_responses.checked_add(1)
+            .ok_or(ErrorCode::IntegerOverflow)?;
+
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.tally = survey.key();
+        receipt.respondent = ctx.accounts.payer.key();
+        receipt.status = BallotStatus::Recorded;
+        receipt.submitted_at = Clock::get()?.unix_timestamp;
+        receipt.bump = ctx.bumps.receipt;
+
+        let response = &mut ctx.accounts.response;
+        response.survey = survey.key();
+        response.respondent_pub_key = respondent_pub_key;
+        response.nonce = nonce;
+        response.submitted_at = receipt.submitted_at;
+        response.bump = ctx.bumps.response;
+        response.ciphertexts = ciphertexts;
+
+        // Same event as MPC responses - avoid wallet exposure
+        emit!(ResponseSubmitted {
+            survey: survey.key(),
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Turn a quiz into a question bank students draw QUIZ_ITEMS questions from
+    /// The bank is the first `pool_size` on-chain questions; their answer keys are
+    /// MXE-encrypted and packed one per question (see POOL_KEYS_VALUES).
+    pub fn set_question_pool(
+        ctx: Context<SetQuestionPool>,
+        pool_size: u8,
+        encrypted_pool_keys: [[u8; 32]; POOL_KEYS_VALUES],  // Enc<Mxe, PoolKeys>
+        mxe_encryption_pubkey: [u8; 32],
+        pool_keys_nonce: u128,
+    ) -> Result<()> {
+        let quiz = &ctx.accounts.quiz;
+
+        require!(
+            quiz.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            matches!(quiz.survey_type, SurveyType::Quiz { .. }),
+            ErrorCode::InvalidSurveyType
+        );
+        require!(
+            (QUIZ_ITEMS..=MAX_POOL_QUESTIONS).contains(&(pool_size as usize))
+                && pool_size as usize <= quiz.questions.len()
+                && quiz.current_responses == 0,
+            ErrorCode::InvalidQuestionPool
+        );
+        require_single_order_mode(quiz.shuffle_answers, true)?;
+
+        let question_pool = &mut ctx.accounts.question_pool;
+        question_pool.quiz = quiz.key();
+        question_pool.encrypted_pool_keys = encrypted_pool_keys;
+        question_pool.pool_keys_nonce = pool_keys_nonce;
+        question_pool.mxe_encryption_pubkey = mxe_encryption_pubkey;
+        question_pool.pool_size = pool_size;
+        question_pool.bump = ctx.bumps.question_pool;
+
+        Ok(())
+    }
+
+    // ✅ NEW: Deal this student their questions from the pool
+    /// Drawn from the most recent slot hash, the quiz and the student, then recorded
+    /// so grading uses the same questions. A student gets one attempt per quiz.
+    pub fn start_quiz_attempt(ctx: Context<StartQuizAttempt>) -> Result<()> {
+        require!(ctx.accounts.quiz.is_active, ErrorCode::SurveyInactive);
+
+        let seed = attempt_seed(
+            &ctx.accounts.recent_slothashes,
+            &ctx.accounts.quiz.key(),
+            &ctx.accounts.student.key(),
+        )?;
+
+        // Partial Fisher-Yates over the bank
+        let pool_size = ctx.accounts.question_pool.pool_size as usize;
+        let mut bank: Vec<u8> = (0..pool_size as u8).collect();
+        let mut drawn_questions = [0u8; QUIZ_ITEMS];
+        for (k, drawn) in drawn_questions.iter_mut().enumerate() {
+            let word = u64::from_le_bytes(seed[8 * k..8 * k + 8].try_into().unwrap());
+            let j = k + (word % (pool_size - k) as u64) as usize;
+            bank.swap(k, j);
+            *drawn = bank[k];
+        }
+
+        let quiz_attempt = &mut ctx.accounts.quiz_attempt;
+        quiz_attempt.quiz = ctx.accounts.quiz.key();
+        quiz_attempt.student = ctx.accounts.student.key();
+        quiz_attempt.drawn_questions = drawn_questions;
+        quiz_attempt.started_at = Clock::get()?.unix_timestamp;
+        quiz_attempt.bump = ctx.bumps.quiz_attempt;
+        quiz_attempt.shuffle_seed = hashv(&[SHUFFLE_SEED_DOMAIN, &seed]).to_bytes();
+
+        emit!(QuizAttemptStarted {
+            quiz: quiz_attempt.quiz,
+            student: quiz_attempt.student,
+            drawn_questions,
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Deal this student their question/option order on a shuffled quiz
+    /// Seeded from the most recent slot hash, so nobody can work out an attempt's
+    /// order before it starts. submit_quiz_answers copies the seed onto the answers.
+    /// Pooled quizzes start with `start_quiz_attempt` instead.
+    pub fn start_shuffled_attempt(ctx: Context<StartShuffledAttempt>) -> Result<()> {
+        require!(ctx.accounts.quiz.is_active, ErrorCode::SurveyInactive);
+        require!(ctx.accounts.quiz.shuffle_answers, ErrorCode::ShuffleUnavailable);
+
+        let seed = attempt_seed(
+            &ctx.accounts.recent_slothashes,
+            &ctx.accounts.quiz.key(),
+            &ctx.accounts.student.key(),
+        )?;
+
+        let quiz_attempt = &mut ctx.accounts.quiz_attempt;
+        quiz_attempt.quiz = ctx.accounts.quiz.key();
+        quiz_attempt.student = ctx.accounts.student.key();
+        quiz_attempt.drawn_questions = core::array::from_fn(|q| q as u8);
+        quiz_attempt.started_at = Clock::get()?.unix_timestamp;
+        quiz_attempt.bump = ctx.bumps.quiz_attempt;
+        quiz_attempt.shuffle_seed = hashv(&[SHUFFLE_SEED_DOMAIN, &seed]).to_bytes();
+
+        emit!(QuizAttemptStarted {
+            quiz: quiz_attempt.quiz,
+            student: quiz_attempt.student,
+            drawn_questions: quiz_attempt.drawn_questions,
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Set quiz grading data (MXE-encrypted correct answers, points, threshold, stats)
+    /// ✅ NICO FIX: Create separate account for quiz grading data (enables Argument::Account)
+    pub fn set_quiz_grading_data(
+        ctx: Context<SetQuizGradingData>,
+        encrypted_quiz_data: [u8; 32 * QUIZ_GRADING_VALUES], // Enc<Mxe, [u32; 14]> - ALL quiz data in one array
+        mxe_encryption_pubkey: [u8; 32],          // Client public key used for ECDH with MXE
+        quiz_data_nonce: u128,                    // Single nonce for all 14 values
+    ) -> Result<()> {
+        // Verify caller is the quiz creator
+        require!(
+            ctx.accounts.quiz.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+        
+        // Verify this is actually a quiz (not a basic survey)
+        require!(
+            matches!(ctx.accounts.quiz.survey_type, SurveyType::Quiz { .. }),
+            ErrorCode::InvalidSurveyType
+        );
+        
+        // Store grading data in the dedicated account
+        let grading_data = &mut ctx.accounts.quiz_grading_data;
+        grading_data.quiz = ctx.accounts.quiz.key();
+        grading_data.encrypted_quiz_data = encrypted_quiz_data;
+        grading_data.quiz_data_nonce = quiz_data_nonce;
+        grading_data.mxe_encryption_pubkey = mxe_encryption_pubkey;
+        grading_data.bump = ctx.bumps.quiz_grading_data;
+
+        Ok(())
+    }
+
+    // Separate instruction for analytics computation
+    pub fn submit_survey_analytics(
+        ctx: Context<SubmitSurveyAnalytics>,
+        analytics_computation_offset: u64,
+        // ✅ UPDATED: Simplified args following Nico's advice (shared encryption = same key/nonce)
+        // user_data: Enc<Shared, [u32; 6]> - all 6 values encrypted with same key/nonce
+        ciphertext_answer1: [u8; 32],
+        ciphertext_answer2: [u8; 32],
+        ciphertext_question_type1: [u8; 32],
+        ciphertext_question_type2: [u8; 32],
+        ciphertext_total_responses: [u8; 32],
+        ciphertext_completion_rate: [u8; 32],
+        user_pub_key: [u8; 32],              // Shared encryption key (used for all 6 values)
+        user_nonce: u128,                    // Shared nonce (used for all 6 values)
+        // Recipients for re-encryption
+        survey_creator_pub_key: [u8; 32],
+        survey_creator_nonce: u128,
+        public_viewer_pub_key: [u8; 32],
+        public_viewer_nonce: u128,
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+    ) -> Result<()> {
+        // Set the sign PDA account bump
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        
+        // Validate survey is active
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+        
+        // Check if survey has reached max responses
+        require!(
+            ctx.accounts.survey.current_responses < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // ✅ UPDATED: Following Nico's advice for shared encryption
+        // Circuit signature: user_data: Enc<Shared, [u32; 6]>, survey_creator: Shared, public_viewer: Shared, respondent: Shared
+        // Total: 14 arguments (much more efficient!)
+        let creator_args = vec![
+            // user_data: Enc<Shared, [u32; 6]> - pubkey, nonce, then 6 ciphertexts
+            Argument::ArcisPubkey(user_pub_key),
+            Argument::PlaintextU128(user_nonce),
+            Argument::EncryptedU32(ciphertext_answer1),
+            Argument::EncryptedU32(ciphertext_answer2),
+            Argument::EncryptedU32(ciphertext_question_type1),
+            Argument::EncryptedU32(ciphertext_question_type2),
+            Argument::EncryptedU32(ciphertext_total_responses),
+            Argument::EncryptedU32(ciphertext_completion_rate),
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.survey.report_decimals),
+            // survey_creator: Shared - 2 args
+            Argument::ArcisPubkey(survey_creator_pub_key),
+            Argument::PlaintextU128(survey_creator_nonce),
+            // public_viewer: Shared - 2 args
+            Argument::ArcisPubkey(public_viewer_pub_key),
+            Argument::PlaintextU128(public_viewer_nonce),
+            // respondent: Shared - 2 args
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+        ];
+
+        // Single computation for analytics
+        queue_computation(
+            ctx.accounts,
+            analytics_computation_offset,
+            creator_args,
+            None,
+            vec![SurveyAnalyticsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.analytics_storage.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.survey.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        // Increment response counter
+        ctx.accounts.survey.current_responses += 1;
+
+        // Emit event for tracking - avoid wallet exposure
+        emit!(ResponseSubmitted {
+            survey: ctx.accounts.survey.key(),
+        });
+
+        Ok(())
+    }
+
+    // Separate instruction for feedback computation
+    pub fn submit_survey_feedback(
+        ctx: Context<SubmitSurveyFeedback>,
+        feedback_computation_offset: u64,
+        // user_data: Enc<Shared, [u32; 6]> - All 6 values encrypted with same key/nonce
+        user_pub_key: [u8; 32],              // Shared encryption key for all 6 values
+        user_nonce: u128,                    // Shared nonce for all 6 values
+        ciphertext_answer1: [u8; 32],
+        ciphertext_answer2: [u8; 32],
+        ciphertext_question_type1: [u8; 32],
+        ciphertext_question_type2: [u8; 32],
+        ciphertext_total_responses: [u8; 32],
+        ciphertext_completion_rate: [u8; 32],
+        // survey_creator: Shared
+        survey_creator_pub_key: [u8; 32],
+        survey_creator_nonce: u128,
+        // public_viewer: Shared
+        public_viewer_pub_key: [u8; 32],
+        public_viewer_nonce: u128,
+        // respondent: Shared
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+    ) -> Result<()> {
+        // Set sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Build arguments for survey_analytics circuit (14 arguments total)
+        // Circuit signature: survey_analytics(user_data: Enc<Shared, [u32; 6]>, survey_creator: Shared, public_viewer: Shared, respondent: Shared)
+        let respondent_args = vec![
+            // user_data: Enc<Shared, [u32; 6]> - pubkey, nonce, then 6 ciphertexts
+            Argument::ArcisPubkey(user_pub_key),
+            Argument::PlaintextU128(user_nonce),
+            Argument::EncryptedU32(ciphertext_answer1),
+            Argument::EncryptedU32(ciphertext_answer2),
+            Argument::EncryptedU32(ciphertext_question_type1),
+            Argument::EncryptedU32(ciphertext_question_type2),
+            Argument::EncryptedU32(ciphertext_total_responses),
+            Argument::EncryptedU32(ciphertext_completion_rate),
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.survey.report_decimals),
+            // survey_creator: Shared
+            Argument::ArcisPubkey(survey_creator_pub_key),
+            Argument::PlaintextU128(survey_creator_nonce),
+            // public_viewer: Shared
+            Argument::ArcisPubkey(public_viewer_pub_key),
+            Argument::PlaintextU128(public_viewer_nonce),
+            // respondent: Shared
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+        ];
+
+        // Single computation for feedback
+        queue_computation(
+            ctx.accounts,
+            feedback_computation_offset,
+            respondent_args,
+            None,
+            vec![SurveyAnalyticsCallback::callback_ix(&[])],
+        )?;
+
+        Ok(())
+    }
+
+    // Separate instruction for student quiz computation
+    pub fn submit_quiz_student(
+        ctx: Context<SubmitQuizStudent>,
+        student_computation_offset: u64,     // For student results
+        ciphertext_answer1: [u8; 32],
+        ciphertext_answer2: [u8; 32],
+        ciphertext_correct_answer1: [u8; 32],
+        ciphertext_correct_answer2: [u8; 32],
+        ciphertext_points1: [u8; 32],
+        ciphertext_points2: [u8; 32],
+        ciphertext_threshold: [u8; 32],  // Encrypted passing threshold
+        ciphertext_class_total_students: [u8; 32],  // Encrypted class total students
+        ciphertext_class_total_score: [u8; 32],     // Encrypted class total score
+        ciphertext_class_completion_count: [u8; 32], // Encrypted class completion count
+        ciphertext_passing_requirement: [u8; 32],   // Encrypted passing requirement
+        student_pub_key: [u8; 32],       // Student's public key
+        student_nonce: u128,             // Student's nonce
+        instructor_pub_key: [u8; 32],    // Instructor's public key
+        instructor_nonce: u128,          // Instructor's nonce
+        creator_pub_key: [u8; 32],       // Creator's public key
+        creator_nonce: u128,             // Creator's nonce
+    ) -> Result<()> {
+        // Validate quiz is active
+        require!(ctx.accounts.quiz.is_active, ErrorCode::SurveyInactive);
+        
+        // Check if quiz has reached max responses
+        require!(
+            ctx.accounts.quiz.current_responses < ctx.accounts.quiz.max_responses,
+            ErrorCode::SurveyFull
+        );
+
+        ctx.accounts.quiz.route_direct(&ctx.accounts.app_preferences)?;
+        
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Single computation for student results
+        let student_args = vec![
+            // user_answer1: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer1
+            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer1
+            Argument::EncryptedU32(ciphertext_answer1),       // Encrypted user_answer1
+            // user_answer2: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer2
+            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer2
+            Argument::EncryptedU32(ciphertext_answer2),       // Encrypted user_answer2
+            // correct_answer1: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_correct_answer1), // Encrypted correct_answer1
+            // correct_answer2: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_correct_answer2), // Encrypted correct_answer2
+            // points1: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_points1),       // Encrypted points1
+            // points2: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_points2),       // Encrypted points2
+            // passing_threshold: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_threshold),     // Encrypted passing_threshold
+            // class_total_students: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_total_students), // Encrypted class_total_students
+            // class_total_score: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_total_score), // Encrypted class_total_score
+            // class_completion_count: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_completion_count), // Encrypted class_completion_count
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.quiz.report_decimals),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),           // student public key
+            Argument::PlaintextU128(student_nonce),           // student nonce
+            // instructor: Shared - 2 args
+            Argument::ArcisPubkey(instructor_pub_key),        // instructor public key
+            Argument::PlaintextU128(instructor_nonce),        // instructor nonce
+            // quiz_creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),           // quiz_creator public key
+            Argument::PlaintextU128(creator_nonce),           // quiz_creator nonce
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            student_computation_offset,
+            student_args,
+            None,
+            vec![QuizEvaluationCallback::callback_ix(&[])],
+        )?;
+
+        // Increment response counter
+        ctx.accounts.quiz.current_responses += 1;
+
+        // Emit event for tracking - avoid wallet exposure
+        emit!(QuizResponseSubmitted {
+            quiz: ctx.accounts.quiz.key(),
+        });
+
+        Ok(())
+    }
+
+    // Separate instruction for instructor quiz computation
+    pub fn submit_quiz_instructor(
+        ctx: Context<SubmitQuizInstructor>,
+        instructor_computation_offset: u64,  // For instructor analytics
+        ciphertext_answer1: [u8; 32],
+        ciphertext_answer2: [u8; 32],
+        ciphertext_correct_answer1: [u8; 32],
+        ciphertext_correct_answer2: [u8; 32],
+        ciphertext_points1: [u8; 32],
+        ciphertext_points2: [u8; 32],
+        ciphertext_threshold: [u8; 32],  // Encrypted passing threshold
+        ciphertext_class_total_students: [u8; 32],  // Encrypted class total students
+        ciphertext_class_total_score: [u8; 32],     // Encrypted class total score
+        ciphertext_class_completion_count: [u8; 32], // Encrypted class completion count
+        ciphertext_passing_requirement: [u8; 32],   // Encrypted passing requirement
+        student_pub_key: [u8; 32],       // Student's public key
+        student_nonce: u128,             // Student's nonce
+        instructor_pub_key: [u8; 32],    // Instructor's public key
+        instructor_nonce: u128,          // Instructor's nonce
+        creator_pub_key: [u8; 32],       // Creator's public key
+        creator_nonce: u128,             // Creator's nonce
+    ) -> Result<()> {
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Single computation for instructor analytics
+        let instructor_args = vec![
+            // user_answer1: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer1
+            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer1
+            Argument::EncryptedU32(ciphertext_answer1),       // Encrypted user_answer1
+            // user_answer2: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer2
+            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer2
+            Argument::EncryptedU32(ciphertext_answer2),       // Encrypted user_answer2
+            // correct_answer1: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_correct_answer1), // Encrypted correct_answer1
+            // correct_answer2: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_correct_answer2), // Encrypted correct_answer2
+            // points1: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_points1),       // Encrypted points1
+            // points2: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_points2),       // Encrypted points2
+            // passing_threshold: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_threshold),     // Encrypted passing_threshold
+            // class_total_students: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_total_students), // Encrypted class_total_students
+            // class_total_score: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_total_score), // Encrypted class_total_score
+            // class_completion_count: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_completion_count), // Encrypted class_completion_count
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.quiz.report_decimals),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),           // student public key
+            Argument::PlaintextU128(student_nonce),           // student nonce
+            // instructor: Shared - 2 args
+            Argument::ArcisPubkey(instructor_pub_key),        // instructor public key
+            Argument::PlaintextU128(instructor_nonce),        // instructor nonce
+            // quiz_creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),           // quiz_creator public key
+            Argument::PlaintextU128(creator_nonce),           // quiz_creator nonce
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            instructor_computation_offset,
+            instructor_args,
+            None,
+            vec![QuizEvaluationCallback::callback_ix(&[])],
+        )?;
+
+        Ok(())
+    }
+
+    // Separate instruction for creator quiz computation
+    pub fn submit_quiz_creator(
+        ctx: Context<SubmitQuizCreator>,
+        creator_computation_offset: u64,     // For quiz creator analytics
+        ciphertext_answer1: [u8; 32],
+        ciphertext_answer2: [u8; 32],
+        ciphertext_correct_answer1: [u8; 32],
+        ciphertext_correct_answer2: [u8; 32],
+        ciphertext_points1: [u8; 32],
+        ciphertext_points2: [u8; 32],
+        ciphertext_threshold: [u8; 32],  // Encrypted passing threshold
+        ciphertext_class_total_students: [u8; 32],  // Encrypted class total students
+        ciphertext_class_total_score: [u8; 32],     // Encrypted class total score
+        ciphertext_class_completion_count: [u8; 32], // Encrypted class completion count
+        ciphertext_passing_requirement: [u8; 32],   // Encrypted passing requirement
+        student_pub_key: [u8; 32],       // Student's public key
+        student_nonce: u128,             // Student's nonce
+        instructor_pub_key: [u8; 32],    // Instructor's public key
+        instructor_nonce: u128,          // Instructor's nonce
+        creator_pub_key: [u8; 32],       // Creator's public key
+        creator_nonce: u128,             // Creator's nonce
+    ) -> Result<()> {
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Single computation for creator analytics
+        let creator_args = vec![
+            // user_answer1: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer1
+            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer1
+            Argument::EncryptedU32(ciphertext_answer1),       // Encrypted user_answer1
+            // user_answer2: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),           // Encryption key for user_answer2
+            Argument::PlaintextU128(student_nonce),           // Nonce for user_answer2
+            Argument::EncryptedU32(ciphertext_answer2),       // Encrypted user_answer2
+            // correct_answer1: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_correct_answer1), // Encrypted correct_answer1
+            // correct_answer2: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_correct_answer2), // Encrypted correct_answer2
+            // points1: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_points1),       // Encrypted points1
+            // points2: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_points2),       // Encrypted points2
+            // passing_threshold: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_threshold),     // Encrypted passing_threshold
+            // class_total_students: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_total_students), // Encrypted class_total_students
+            // class_total_score: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_total_score), // Encrypted class_total_score
+            // class_completion_count: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(ciphertext_class_completion_count), // Encrypted class_completion_count
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.quiz.report_decimals),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),           // student public key
+            Argument::PlaintextU128(student_nonce),           // student nonce
+            // instructor: Shared - 2 args
+            Argument::ArcisPubkey(instructor_pub_key),        // instructor public key
+            Argument::PlaintextU128(instructor_nonce),        // instructor nonce
+            // quiz_creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),           // quiz_creator public key
+            Argument::PlaintextU128(creator_nonce),           // quiz_creator nonce
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            creator_computation_offset,
+            creator_args,
+            None,
+            vec![QuizEvaluationCallback::callback_ix(&[])],
+        )?;
+
+        Ok(())
+    }
+
+    // Create special survey for high scorers
+    pub fn create_special_survey(
+        ctx: Context<CreateSpecialSurvey>,
+        title: String,
+        description: String,
+        max_responses: u32,
+    ) -> Result<()> {
+        // Input validation
+        validate_string_length(&title, MAX_TITLE_LENGTH, "title")?;
+        validate_string_length(&description, MAX_DESCRIPTION_LENGTH, "description")?;
+        validate_max_responses(max_responses)?;
+        
+        let special_survey = &mut ctx.accounts.special_survey;
+        let quiz = &mut ctx.accounts.quiz;
+        let clock = Clock::get()?;
+
+        require!(
+            quiz.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Only allow special survey if enabled on the quiz
+        require!(
+            matches!(quiz.survey_type, SurveyType::Quiz { special_survey_enabled: true, .. }),
+            ErrorCode::InvalidSurveyType
+        );
+
+        special_survey.creator = ctx.accounts.creator.key();
+        special_survey.title = title;
+        special_survey.description = description;
+        special_survey.survey_type = SurveyType::Special;
+        special_survey.max_responses = max_responses;
+        special_survey.current_responses = 0;  // Initialize response counter
+        special_survey.is_active = true;
+        special_survey.is_publicly_browsable = false;  // Special surveys are NOT publicly browsable
+        special_survey.default_language = quiz.default_language.clone();
+        special_survey.report_decimals = quiz.report_decimals;
+        special_survey.created_at = clock.unix_timestamp;
+
+        emit!(SpecialSurveyCreated {
+            quiz: quiz.key(),
+            special_survey: special_survey.key(),
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Submit quiz answers (lightweight - fixes transaction size issue)
+    /// This is STEP 1 of quiz submission - just stores encrypted answers
+    /// Auto-grading happens separately in STEP 2
+    pub fn submit_quiz_answers(
+        ctx: Context<SubmitQuizAnswers>,
+        encrypted_answers: [u8; 64],  // All answers encrypted as a single blob
+        answers_hash: [u8; 32],        // Hash for integrity verification
+    ) -> Result<()> {
+        let quiz = &mut ctx.accounts.quiz;
+        let answers_storage = &mut ctx.accounts.answers_storage;
+        let clock = Clock::get()?;
+
+        // Validate quiz is active
+        require!(quiz.is_active, ErrorCode::SurveyInactive);
+        
+        // Check if quiz has reached max responses
+        require!(
+            quiz.current_responses < quiz.max_responses,
+            ErrorCode::SurveyFull
+        );
+
+        // Initialize the answers storage
+        answers_storage.quiz = quiz.key();
+        answers_storage.student = ctx.accounts.payer.key();
+        answers_storage.encrypted_answers = encrypted_answers;
+        answers_storage.answers_hash = answers_hash;
+        answers_storage.submission_timestamp = clock.unix_timestamp;
+        answers_storage.grading_status = GradingStatus::Pending;
+        answers_storage.bump = ctx.bumps.answers_storage;
+        answers_storage.similarity_checked = false;
+
+        // Shuffled answers are in the order the attempt was dealt
+        if quiz.shuffle_answers {
+            let quiz_attempt = ctx.accounts.quiz_attempt.as_ref().ok_or(ErrorCode::AttemptNotStarted)?;
+            answers_storage.shuffle_seed = quiz_attempt.shuffle_seed;
+        }
+
+        // Increment response count
+        quiz.current_responses = quiz.current_responses.checked_add(1)
+            .ok_or(ErrorCode::IntegerOverflow)?;
+
+        msg!("Quiz answers submitted successfully. Quiz: {}, Student: {}", 
+            quiz.key(), ctx.accounts.payer.key());
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Compute quiz grade (triggered separately by backend/creator)
+    /// This is STEP 2 of quiz submission - heavy MPC computation
+    pub fn compute_quiz_grade(
+        ctx: Context<ComputeQuizGrade>,
+        computation_offset: u64,
+        // Minimal args - references the stored answers
+        student_pub_key: [u8; 32],
+        student_nonce: u128,
+        threshold: u8,  // Plaintext threshold (public parameter)
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let attempt_key = ctx.accounts.answers_storage.key();
+
+        // A Computing attempt whose lock lapsed lost its computation and may be graded again
+        let lock_lapsed = !ctx.accounts.item_stats.lock.is_held(now);
+        begin_grading(
+            &mut ctx.accounts.quiz,
+            &ctx.accounts.app_preferences,
+            &mut ctx.accounts.answers_storage,
+            lock_lapsed,
+        )?;
+
+        // Attempts are folded into the item stats one at a time
+        let item_stats = &mut ctx.accounts.item_stats;
+        require!(!item_stats.lock.is_held(now), ErrorCode::GradingBusy);
+        item_stats.lock.acquire(attempt_key, now)?;
+
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // ✅ NICO FIXES: Reduced args from 26 → 11 using Argument::Account
+        // Circuit signature: user_answers: Enc<Shared, [u32; 2]>, answer_shuffle: u128, quiz_data: Enc<Mxe, [u32; 14]>,
+        //                    item_stats: Enc<Mxe, ItemStats>, report_decimals: u8,
+        //                    student: Shared, instructor: Shared, quiz_creator: Shared
+        
+        let answer_shuffle = ctx.accounts.quiz.answer_shuffle(&ctx.accounts.answers_storage);
+        let grading_args = vec![
+            // user_answers: Enc<Shared, [u32; 2]> - pubkey, nonce, Account = 3 args
+            // ✅ NICO FIX: Use Argument::Account instead of individual EncryptedU32
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+            Argument::Account(
+                ctx.accounts.answers_storage.key(),
+                8 + 32 + 32,  // Discriminator + quiz + student = 72 bytes
+                64            // 2 answers * 32 bytes each
+            ),
+            
+            // answer_shuffle: u128 - 1 arg (shown order -> canonical, 0 when shuffling is off)
+            Argument::PlaintextU128(answer_shuffle),
+            
+            // quiz_data: Enc<Mxe, [u32; 14]> - nonce, Account = 2 args
+            // [correct1, correct2, points1, points2, threshold, stat1, stat2, stat3,
+            //  scheme1, scheme2, tolerance1, tolerance2, penalty1, penalty2]
+            // ✅ NICO FIX: MXE knows its own pubkey + use Argument::Account!
+            Argument::PlaintextU128(ctx.accounts.quiz_grading_data.quiz_data_nonce),
+            Argument::Account(
+                ctx.accounts.quiz_grading_data.key(),
+                8 + 32,  // Discriminator + quiz = 40 bytes
+                (32 * QUIZ_GRADING_VALUES) as u32  // 14 values * 32 bytes each
+            ),
+            
+            // item_stats: Enc<Mxe, ItemStats> - nonce, Account = 2 args
+            Argument::PlaintextU128(ctx.accounts.item_stats.stats_nonce),
+            Argument::Account(
+                ctx.accounts.item_stats.key(),
+                ItemStatsStorage::ENCRYPTED_STATS_OFFSET,
+                (32 * ITEM_STATS_CIPHERTEXTS) as u32,
+            ),
+            
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.quiz.report_decimals),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+            
+            // instructor: Shared - 2 args
+            Argument::ArcisPubkey(ctx.accounts.quiz.instructor_arcium_pubkey),
+            Argument::PlaintextU128(Clock::get()?.unix_timestamp as u128),
+            
+            // quiz_creator: Shared - 2 args
+            Argument::ArcisPubkey(ctx.accounts.quiz.creator_arcium_pubkey),
+            Argument::PlaintextU128(Clock::get()?.unix_timestamp as u128 + 1),
+        ];
+        // Total: 3 + 1 + 2 + 2 + 1 + 2 + 2 + 2 = 15 args (down from 26!!!)
+        // ✅ NICO FIXES: Removed MXE pubkey + used Argument::Account for both arrays!
+
+        // Queue the MPC computation for quiz grading using Arcium SDK
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            grading_args,
+            None,
+            vec![QuizEvaluationCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.item_stats.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: attempt_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        msg!("Quiz grading computation queued. Offset: {}", computation_offset);
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Verify quiz threshold to grant special survey access
+    /// 
+    /// This instruction queues the `quiz_threshold_check` computation with correct argument count (11 args).
+    /// The arguments passed here MUST match the circuit signature:
+    /// 
+    /// Circuit parameters → Instruction arguments mapping:
+    /// - `encrypted_score: Enc<Shared, u32>` → 3 args: ArcisPubkey + PlaintextU128 + EncryptedU32
+    /// - `threshold: Enc<Mxe, u8>` → 1 arg: EncryptedU8
+    /// - `passing_requirement: Enc<Mxe, u32>` → 1 arg: EncryptedU32
+    /// - `student: Shared` → 2 args: ArcisPubkey + PlaintextU128
+    /// - `access_controller: Shared` → 2 args: ArcisPubkey + PlaintextU128
+    /// - `auditor: Shared` → 2 args: ArcisPubkey + PlaintextU128
+    /// 
+    /// # Flow
+    /// 1. Student completes quiz and gets encrypted score
+    /// 2. This instruction verifies the score meets the threshold
+    /// 3. If verification passes, student gains access to special surveys
+    /// 4. Results are re-encrypted for student, access controller, and auditor
+    // Separate instruction for student quiz verification
+    pub fn verify_quiz_student(
+        ctx: Context<VerifyQuizStudent>,
+        quiz: Pubkey,
+        student_computation_offset: u64,     // For student verification result
+        encrypted_score: [u8; 32], // This will be processed by MPC
+        encrypted_threshold: [u8; 32], // Encrypted threshold
+        encrypted_requirement: [u8; 32], // Encrypted additional requirement
+        student_pub_key: [u8; 32], // Student's public key
+        student_nonce: u128,       // Student's nonce
+        access_controller_pub_key: [u8; 32], // Access controller's public key
+        access_controller_nonce: u128,       // Access controller's nonce
+        auditor_pub_key: [u8; 32], // Auditor's public key
+        auditor_nonce: u128,       // Auditor's nonce
+    ) -> Result<()> {
+        // Set the sign PDA account bump
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        
+        let clock = Clock::get()?;
+        
+        // Verify the quiz exists and is active
+        require!(ctx.accounts.quiz.is_active, ErrorCode::SurveyInactive);
+        
+        // Verify the quiz has special survey enabled
+        require!(
+            matches!(ctx.accounts.quiz.survey_type, SurveyType::Quiz { special_survey_enabled: true, .. }),
+            ErrorCode::InvalidSurveyType
+        );
+        
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Computation 1: Verification result for student
+        // Circuit signature: encrypted_score: Enc<Shared, u32>, threshold: Enc<Mxe, u8>, passing_requirement: Enc<Mxe, u32>, student: Shared, access_controller: Shared, auditor: Shared
+        let student_args = vec![
+            // encrypted_score: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),           // Encryption key for encrypted_score
+            Argument::PlaintextU128(student_nonce),           // Nonce for encrypted_score
+            Argument::EncryptedU32(encrypted_score),          // Encrypted score
+            // threshold: Enc<Mxe, u8> - 1 arg
+            Argument::EncryptedU8(encrypted_threshold),       // Encrypted threshold
+            // passing_requirement: Enc<Mxe, u32> - 1 arg
+            Argument::EncryptedU32(encrypted_requirement),    // Encrypted passing requirement
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),           // student public key
+            Argument::PlaintextU128(student_nonce),           // student nonce
+            // access_controller: Shared - 2 args
+            Argument::ArcisPubkey(access_controller_pub_key), // access_controller public key
+            Argument::PlaintextU128(access_controller_nonce), // access_controller nonce
+            // auditor: Shared - 2 args
+            Argument::ArcisPubkey(auditor_pub_key),           // auditor public key
+            Argument::PlaintextU128(auditor_nonce),           // auditor nonce
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            student_computation_offset,
+            student_args,
+            None,
+            vec![QuizThresholdCheckCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.completion_proof.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+        
+        // ✅ FIXED: Store completion proof with proper data handling
+        let completion_proof = &mut ctx.accounts.completion_proof;
+        
+        // Check if this is a new completion proof or updating an existing one
+        if completion_proof.verified {
+            // If already verified, check if it's expired
+            let clock = Clock::get()?;
+            if completion_proof.expires_at > 0 && clock.unix_timestamp > completion_proof.expires_at {
+                // Expired - allow re-verification
+                completion_proof.verified = false;
+                completion_proof.verified_at = 0;
+            } else {
+                // Still valid - prevent re-verification
+                return Err(ErrorCode::AlreadyVerified.into());
+            }
+        }
+        
+        // Initialize or update completion proof data
+        completion_proof.quiz = quiz;
+        completion_proof.user = ctx.accounts.payer.key();
+        completion_proof.encrypted_score = encrypted_score;  // Store as bytes
+        completion_proof.threshold = 0;  // Threshold is now encrypted, not stored as plaintext
+        completion_proof.verified = false; // Will be set to true by MPC callback
+        completion_proof.verified_at = 0; // Will be set by MPC callback
+        completion_proof.mpc_computation_id = student_computation_offset; // Use first computation ID
+        
+        // Set expiration timestamp based on quiz configuration
+        let expiration_timestamp = match &ctx.accounts.quiz.survey_type {
+            SurveyType::Quiz { completion_proof_expiration_value, completion_proof_expiration_unit, .. } => {
+                calculate_expiration_timestamp(
+                    clock.unix_timestamp,
+                    *completion_proof_expiration_value,  // Borrow, so dereference
+                    completion_proof_expiration_unit    // Already a reference
+                )?
+            },
+            _ => clock.unix_timestamp + (7 * 24 * 60 * 60), // Default 7 days fallback
+        };
+        
+        completion_proof.expires_at = expiration_timestamp;
+        
+        // ✅ FIXED: Don't emit event here - wait for MPC callback to complete verification
+        // The QuizCompletionVerified event will be emitted by the callback with the actual result
+        
+        Ok(())
+    }
+
+
+
+    // Submit special survey response with access control
+    pub fn submit_special_survey_response(
+        ctx: Context<SubmitSpecialSurveyResponse>,
+        computation_offset: u64,
+        // User data (all 6 values encrypted with same key/nonce)
+        ciphertext_answer1: [u8; 32],
+        ciphertext_answer2: [u8; 32],
+        ciphertext_question_type1: [u8; 32],
+        ciphertext_question_type2: [u8; 32],
+        ciphertext_total_responses: [u8; 32],
+        ciphertext_completion_rate: [u8; 32],
+        user_pub_key: [u8; 32],
+        user_nonce: u128,
+        // Recipients for re-encryption
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+        viewer_pub_key: [u8; 32],
+        viewer_nonce: u128,
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+    ) -> Result<()> {
+        // Set the sign PDA account bump
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        
+        // Access control: Verify user has valid quiz completion proof
+        require!(ctx.accounts.completion_proof.verified, ErrorCode::Unauthorized);
+        require!(ctx.accounts.completion_proof.user == ctx.accounts.payer.key(), ErrorCode::Unauthorized);
+        require!(ctx.accounts.completion_proof.quiz == ctx.accounts.quiz.key(), ErrorCode::Unauthorized);
+        
+        // Check if completion proof has expired
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp <= ctx.accounts.completion_proof.expires_at,
+            ErrorCode::ProofExpired
+        );
+        
+        // Verify special survey is active
+        require!(ctx.accounts.special_survey.is_active, ErrorCode::SurveyInactive);
+        
+        // Check if special survey has reached max responses
+        require!(
+            ctx.accounts.special_survey.current_responses < ctx.accounts.special_survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        
+        // ✅ FIXED: Queue computation with array approach (Following Nico's advice)
+        // Circuit signature: user_data: Enc<Shared, [u32; 6]>, survey_creator: Shared, public_viewer: Shared, respondent: Shared
+        let args = vec![
+            // user_data: Enc<Shared, [u32; 6]> - pubkey, nonce, then 6 ciphertexts
+            Argument::ArcisPubkey(user_pub_key),
+            Argument::PlaintextU128(user_nonce),
+            Argument::EncryptedU32(ciphertext_answer1),
+            Argument::EncryptedU32(ciphertext_answer2),
+            Argument::EncryptedU32(ciphertext_question_type1),
+            Argument::EncryptedU32(ciphertext_question_type2),
+            Argument::EncryptedU32(ciphertext_total_responses),
+            Argument::EncryptedU32(ciphertext_completion_rate),
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.special_survey.report_decimals),
+            // survey_creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+            // public_viewer: Shared - 2 args
+            Argument::ArcisPubkey(viewer_pub_key),
+            Argument::PlaintextU128(viewer_nonce),
+            // respondent: Shared - 2 args
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SurveyAnalyticsCallback::callback_ix(&[])],
+        )?;
+
+        // Increment response counter
+        ctx.accounts.special_survey.current_responses += 1;
+
+        emit!(SpecialSurveyResponseSubmitted {
+            special_survey: ctx.accounts.special_survey.key(),
+            quiz: ctx.accounts.quiz.key(),
+        });
+
+        Ok(())
+    }
+
+    // Delete survey/quiz
+    pub fn delete_survey(ctx: Context<DeleteSurvey>) -> Result<()> {
+        let survey = &mut ctx.accounts.survey;
+        let clock = Clock::get()?;
+        
+        require!(
+            survey.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+
+        survey.is_active = false;
+
+        emit!(SurveyDeleted {
+            survey: survey.key(),
+            creator: survey.creator,
+            deleted_at: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ✅ REVERTED: Use standard Arcium v0.3.0 callback pattern
+    // Note: Callbacks will be handled via standard Anchor event patterns
+
+    // ✅ REVERTED: Use standard Arcium v0.3.0 callback pattern
+
+    // ✅ FIXED: Implement callbacks using standard Arcium v0.3.0 patterns
+    #[arcium_callback(encrypted_ix = "survey_analytics")]
+    pub fn survey_analytics_callback(
+        ctx: Context<SurveyAnalyticsCallback>,
+        output: ComputationOutputs<SurveyAnalyticsOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(data) => data,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // ✅ FIX: Store analytics data in the analytics storage account
+        let analytics_storage = &mut ctx.accounts.analytics_storage;
+        let clock = Clock::get()?;
+        
+        // Store the encrypted analytics data
+        // The MPC output structure has field_0, field_1, field_2 - we need to access the correct fields
+        analytics_storage.survey = ctx.accounts.survey.key();
+        analytics_storage.creator_analytics = result.field_0.field_0.ciphertexts[0];  // Extract ciphertext from first field
+        analytics_storage.public_summary = result.field_0.field_1.ciphertexts[0];     // Extract ciphertext from second field
+        analytics_storage.respondent_feedback = result.field_0.field_2.ciphertexts[0]; // Extract ciphertext from third field
+        analytics_storage.nonce = 0; // TODO: Extract nonce from MPC output if available
+        analytics_storage.created_at = clock.unix_timestamp;
+        analytics_storage.updated_at = clock.unix_timestamp;
+
+        // ✅ TODO: Re-enable event after fixing output struct types
+        // emit!(SurveyAnalyticsComplete {
+        //     analytics_data_ct: result.field_0,
+        // });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "quiz_evaluation")]
+    pub fn quiz_evaluation_callback(
+        ctx: Context<QuizEvaluationCallback>,
+        output: ComputationOutputs<QuizEvaluationOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(data) => data.field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over old stats
+        let attempt_key = ctx.accounts.answers_storage.key();
+        let item_stats = &mut ctx.accounts.item_stats;
+        item_stats.lock.release(attempt_key)?;
+
+        // field_3 is the updated Enc<Mxe, ItemStats>
+        item_stats.encrypted_stats = result.field_3.ciphertexts;
+        item_stats.stats_nonce = result.field_3.nonce;
+        item_stats.graded_attempts = item_stats.graded_attempts.saturating_add(1);
+        ctx.accounts.answers_storage.grading_status = GradingStatus::Completed;
+
+        // ✅ TODO: Re-enable event after fixing output struct types
+        // emit!(QuizEvaluationComplete {
+        //     evaluation_data_ct: result.field_0,
+        // });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "analytics_computation")]
+    pub fn analytics_computation_callback(
+        ctx: Context<AnalyticsComputationCallback>,
+        output: ComputationOutputs<AnalyticsComputationOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(data) => data.field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over old sums
+        let receipt_key = ctx.accounts.receipt.key();
+        let correlation_sums = &mut ctx.accounts.correlation_sums;
+        correlation_sums.lock.release(receipt_key)?;
+
+        // field_3 is the updated Enc<Mxe, RunningSums>
+        correlation_sums.encrypted_sums = result.field_3.ciphertexts;
+        correlation_sums.sums_nonce = result.field_3.nonce;
+        correlation_sums.submissions = correlation_sums.submissions.saturating_add(1);
+
+        emit!(AnalyticsComputed {
+            survey: correlation_sums.survey,
+            analyst_analytics: result.field_0,
+            business_metrics: result.field_1,
+            research_insights: result.field_2,
+        });
+        ctx.accounts.receipt.status = BallotStatus::Recorded;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "quiz_threshold_check")]
+    pub fn quiz_threshold_check_callback(
+        ctx: Context<QuizThresholdCheckCallback>,
+        output: ComputationOutputs<QuizThresholdCheckOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(data) => data,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let clock = Clock::get()?;
+        
+        // Update completion proof account with MPC result
+        let completion_proof = &mut ctx.accounts.completion_proof;
+        completion_proof.verified = true; // MPC has verified the threshold check
+        completion_proof.verified_at = clock.unix_timestamp;
+        // Extract the actual encrypted verification result from MPC output
+        // result.field_0 = ThresholdVerification (for student)
+        // result.field_1 = AccessControl (for access controller)
+        // result.field_2 = AuditRecord (for auditor)
+        completion_proof.encrypted_verification_result = result.field_0.field_0.ciphertexts[0];
+        
+        // TODO: Update quiz aggregation account here
+        // This would require adding the quiz_aggregation account to the callback struct
+        // and implementing the aggregation logic
+        
+        // ✅ TODO: Re-enable event after fixing output struct types
+        // emit!(QuizCompletionVerified {
+        //     verified_at: clock.unix_timestamp,
+        //     verification_data_ct: result.field_0,
+        // });
+
+        Ok(())
+    }
+
+
+
+    /// ✅ NEW: Create the encrypted tally for a Matrix question
+    ///
+    /// Circuit signature: init_matrix_tally(mxe: Mxe) -> Enc<Mxe, MatrixTally>
+    /// - `mxe: Mxe` → 1 arg: PlaintextU128 (output nonce)
+    pub fn init_matrix_tally(
+        ctx: Context<InitMatrixTally>,
+        computation_offset: u64,
+        question_id: u32,
+        mxe_nonce: u128,
+        committed_question: Option<CommittedQuestion>,  // Only for surveys with committed content
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Shape comes from the survey's question so respondents can't pick their own bounds
+        let question = survey.resolve_question(question_id, committed_question)?;
+        let (rows, columns) = match &question.question_type {
+            QuestionType::Matrix { rows, columns } => (rows.len() as u8, columns.len() as u8),
+            _ => return Err(ErrorCode::QuestionTypeMismatch.into()),
+        };
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.survey = survey_key;
+        tally_storage.question_id = question_id;
+        tally_storage.rows = rows;
+        tally_storage.columns = columns;
+        tally_storage.submissions = 0;
+        tally_storage.bump = ctx.bumps.tally_storage;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitMatrixTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        msg!("Matrix tally init queued for question {}", question_id);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_matrix_tally")]
+    pub fn init_matrix_tally_callback(
+        ctx: Context<InitMatrixTallyCallback>,
+        output: ComputationOutputs<InitMatrixTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(InitMatrixTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.encrypted_tally = tally.ciphertexts;
+        tally_storage.tally_nonce = tally.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Submit one encrypted matrix answer
+    ///
+    /// Circuit signature: accumulate_matrix_response(response: Enc<Shared, [u8; MAX_MATRIX_ROWS]>, row_count: u8, column_count: u8, tally: Enc<Mxe, MatrixTally>)
+    /// - `response` → ArcisPubkey + PlaintextU128 + MAX_MATRIX_ROWS × EncryptedU8
+    /// - `row_count`, `column_count` → PlaintextU8 each (from the tally account)
+    /// - `tally` → PlaintextU128 (stored nonce) + Account (stored ciphertexts)
+    ///
+    /// Only the running aggregate is stored; the individual grid never leaves MPC.
+    /// One answer per wallet (TallyReceipt), folded in one at a time (TallyLock).
+    pub fn submit_matrix_response(
+        ctx: Context<SubmitMatrixResponse>,
+        computation_offset: u64,
+        encrypted_answers: [[u8; 32]; MAX_MATRIX_ROWS],
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.tally_storage.submissions < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        let receipt_key = ctx.accounts.receipt.key();
+        ctx.accounts.tally_storage.lock.acquire(receipt_key, now)?;
+        let tally_storage_key = ctx.accounts.tally_storage.key();
+        let respondent = ctx.accounts.payer.key();
+        ctx.accounts.receipt.queue(tally_storage_key, respondent, now, ctx.bumps.receipt)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let tally_storage = &ctx.accounts.tally_storage;
+        let mut args = vec![
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+        ];
+        args.extend(encrypted_answers.iter().map(|answer| Argument::EncryptedU8(*answer)));
+        args.push(Argument::PlaintextU8(tally_storage.rows));
+        args.push(Argument::PlaintextU8(tally_storage.columns));
+        args.push(Argument::PlaintextU128(tally_storage.tally_nonce));
+        args.push(Argument::Account(
+            tally_storage.key(),
+            MatrixTallyStorage::ENCRYPTED_TALLY_OFFSET,
+            (32 * MATRIX_TALLY_CIPHERTEXTS) as u32,
+        ));
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AccumulateMatrixResponseCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: receipt_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "accumulate_matrix_response")]
+    pub fn accumulate_matrix_response_callback(
+        ctx: Context<AccumulateMatrixResponseCallback>,
+        output: ComputationOutputs<AccumulateMatrixResponseOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(AccumulateMatrixResponseOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old tally
+        let receipt_key = ctx.accounts.receipt.key();
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.lock.release(receipt_key)?;
+        tally_storage.encrypted_tally = tally.ciphertexts;
+        tally_storage.tally_nonce = tally.nonce;
+        tally_storage.submissions = tally_storage.submissions.saturating_add(1);
+        ctx.accounts.receipt.status = BallotStatus::Recorded;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal the aggregate matrix tally to the survey creator
+    ///
+    /// Circuit signature: reveal_matrix_tally(tally: Enc<Mxe, MatrixTally>, creator: Shared) -> Enc<Shared, MatrixTally>
+    pub fn reveal_matrix_tally(
+        ctx: Context<RevealMatrixTally>,
+        computation_offset: u64,
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.tally_storage.submissions >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let tally_storage = &ctx.accounts.tally_storage;
+        let args = vec![
+            // tally: Enc<Mxe, MatrixTally> - 2 args
+            Argument::PlaintextU128(tally_storage.tally_nonce),
+            Argument::Account(
+                tally_storage.key(),
+                MatrixTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * MATRIX_TALLY_CIPHERTEXTS) as u32,
+            ),
+            // creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealMatrixTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_matrix_tally")]
+    pub fn reveal_matrix_tally_callback(
+        ctx: Context<RevealMatrixTallyCallback>,
+        output: ComputationOutputs<RevealMatrixTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(RevealMatrixTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(MatrixTallyRevealed {
+            survey: ctx.accounts.tally_storage.survey,
+            question_id: ctx.accounts.tally_storage.question_id,
+            encryption_key: tally.encryption_key,
+            nonce: tally.nonce,
+            ciphertexts: tally.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted tally for a Ranking question
+    ///
+    /// Circuit signature: init_ranking_tally(mxe: Mxe) -> Enc<Mxe, RankingTally>
+    /// - `mxe: Mxe` → 1 arg: PlaintextU128 (output nonce)
+    pub fn init_ranking_tally(
+        ctx: Context<InitRankingTally>,
+        computation_offset: u64,
+        question_id: u32,
+        mxe_nonce: u128,
+        committed_question: Option<CommittedQuestion>,  // Only for surveys with committed content
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Shape comes from the survey's question so respondents can't pick their own bounds
+        let question = survey.resolve_question(question_id, committed_question)?;
+        let option_count = match &question.question_type {
+            QuestionType::Ranking { options } => options.len() as u8,
+            _ => return Err(ErrorCode::QuestionTypeMismatch.into()),
+        };
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.survey = survey_key;
+        tally_storage.question_id = question_id;
+        tally_storage.option_count = option_count;
+        tally_storage.submissions = 0;
+        tally_storage.bump = ctx.bumps.tally_storage;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitRankingTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        msg!("Ranking tally init queued for question {}", question_id);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_ranking_tally")]
+    pub fn init_ranking_tally_callback(
+        ctx: Context<InitRankingTallyCallback>,
+        output: ComputationOutputs<InitRankingTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(InitRankingTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.encrypted_tally = tally.ciphertexts;
+        tally_storage.tally_nonce = tally.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Submit one encrypted ranking answer
+    ///
+    /// Circuit signature: accumulate_ranking_response(response: Enc<Shared, [u8; MAX_RANKING_OPTIONS]>, option_count: u8, tally: Enc<Mxe, RankingTally>)
+    /// - `response` → ArcisPubkey + PlaintextU128 + MAX_RANKING_OPTIONS × EncryptedU8
+    /// - `option_count` → PlaintextU8 (from the tally account)
+    /// - `tally` → PlaintextU128 (stored nonce) + Account (stored ciphertexts)
+    ///
+    /// Only the running aggregate is stored; the individual ordering never leaves MPC.
+    /// One answer per wallet (TallyReceipt), folded in one at a time (TallyLock).
+    pub fn submit_ranking_response(
+        ctx: Context<SubmitRankingResponse>,
+        computation_offset: u64,
+        encrypted_answers: [[u8; 32]; MAX_RANKING_OPTIONS],
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.tally_storage.submissions < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        let receipt_key = ctx.accounts.receipt.key();
+        ctx.accounts.tally_storage.lock.acquire(receipt_key, now)?;
+        let tally_storage_key = ctx.accounts.tally_storage.key();
+        let respondent = ctx.accounts.payer.key();
+        ctx.accounts.receipt.queue(tally_storage_key, respondent, now, ctx.bumps.receipt)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let tally_storage = &ctx.accounts.tally_storage;
+        let mut args = vec![
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+        ];
+        args.extend(encrypted_answers.iter().map(|answer| Argument::EncryptedU8(*answer)));
+        args.push(Argument::PlaintextU8(tally_storage.option_count));
+        args.push(Argument::PlaintextU128(tally_storage.tally_nonce));
+        args.push(Argument::Account(
+            tally_storage.key(),
+            RankingTallyStorage::ENCRYPTED_TALLY_OFFSET,
+            (32 * RANKING_TALLY_CIPHERTEXTS) as u32,
+        ));
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AccumulateRankingResponseCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: receipt_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "accumulate_ranking_response")]
+    pub fn accumulate_ranking_response_callback(
+        ctx: Context<AccumulateRankingResponseCallback>,
+        output: ComputationOutputs<AccumulateRankingResponseOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(AccumulateRankingResponseOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old tally
+        let receipt_key = ctx.accounts.receipt.key();
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.lock.release(receipt_key)?;
+        tally_storage.encrypted_tally = tally.ciphertexts;
+        tally_storage.tally_nonce = tally.nonce;
+        tally_storage.submissions = tally_storage.submissions.saturating_add(1);
+        ctx.accounts.receipt.status = BallotStatus::Recorded;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal the aggregate ranking tally to the survey creator
+    ///
+    /// Circuit signature: reveal_ranking_tally(tally: Enc<Mxe, RankingTally>, creator: Shared) -> Enc<Shared, RankingTally>
+    pub fn reveal_ranking_tally(
+        ctx: Context<RevealRankingTally>,
+        computation_offset: u64,
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.tally_storage.submissions >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let tally_storage = &ctx.accounts.tally_storage;
+        let args = vec![
+            // tally: Enc<Mxe, RankingTally> - 2 args
+            Argument::PlaintextU128(tally_storage.tally_nonce),
+            Argument::Account(
+                tally_storage.key(),
+                RankingTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * RANKING_TALLY_CIPHERTEXTS) as u32,
+            ),
+            // creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealRankingTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_ranking_tally")]
+    pub fn reveal_ranking_tally_callback(
+        ctx: Context<RevealRankingTallyCallback>,
+        output: ComputationOutputs<RevealRankingTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(RevealRankingTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(RankingTallyRevealed {
+            survey: ctx.accounts.tally_storage.survey,
+            question_id: ctx.accounts.tally_storage.question_id,
+            encryption_key: tally.encryption_key,
+            nonce: tally.nonce,
+            ciphertexts: tally.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted NPS tally for a 0-10 Rating question
+    ///
+    /// Circuit signature: init_nps_tally(mxe: Mxe) -> Enc<Mxe, NpsTally>
+    /// - `mxe: Mxe` → 1 arg: PlaintextU128 (output nonce)
+    pub fn init_nps_tally(
+        ctx: Context<InitNpsTally>,
+        computation_offset: u64,
+        question_id: u32,
+        min_responses: u32,
+        mxe_nonce: u128,
+        committed_question: Option<CommittedQuestion>,  // Only for surveys with committed content
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            min_responses >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+
+        let question = survey.resolve_question(question_id, committed_question)?;
+        require!(
+            matches!(
+                question.question_type,
+                QuestionType::Rating { min: NPS_SCALE_MIN, max: NPS_SCALE_MAX }
+            ),
+            ErrorCode::InvalidNpsQuestion
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.survey = survey_key;
+        tally_storage.question_id = question_id;
+        tally_storage.min_responses = min_responses;
+        tally_storage.submissions = 0;
+        tally_storage.bump = ctx.bumps.tally_storage;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitNpsTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        msg!("NPS tally init queued for question {}", question_id);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_nps_tally")]
+    pub fn init_nps_tally_callback(
+        ctx: Context<InitNpsTallyCallback>,
+        output: ComputationOutputs<InitNpsTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(InitNpsTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.encrypted_tally = tally.ciphertexts;
+        tally_storage.tally_nonce = tally.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Submit one encrypted 0-10 rating to the NPS tally
+    ///
+    /// Circuit signature: accumulate_nps_response(response: Enc<Shared, u32>, tally: Enc<Mxe, NpsTally>)
+    /// - `response` → ArcisPubkey + PlaintextU128 + EncryptedU32 (NOT_ANSWERED if skipped)
+    /// - `tally` → PlaintextU128 (stored nonce) + Account (stored ciphertexts)
+    ///
+    /// One answer per wallet (TallyReceipt), folded in one at a time (TallyLock).
+    pub fn submit_nps_response(
+        ctx: Context<SubmitNpsResponse>,
+        computation_offset: u64,
+        encrypted_rating: [u8; 32],
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.tally_storage.submissions < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        let receipt_key = ctx.accounts.receipt.key();
+        ctx.accounts.tally_storage.lock.acquire(receipt_key, now)?;
+        let tally_storage_key = ctx.accounts.tally_storage.key();
+        let respondent = ctx.accounts.payer.key();
+        ctx.accounts.receipt.queue(tally_storage_key, respondent, now, ctx.bumps.receipt)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let tally_storage = &ctx.accounts.tally_storage;
+        let args = vec![
+            // response: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+            Argument::EncryptedU32(encrypted_rating),
+            // tally: Enc<Mxe, NpsTally> - 2 args
+            Argument::PlaintextU128(tally_storage.tally_nonce),
+            Argument::Account(
+                tally_storage.key(),
+                NpsTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * NPS_TALLY_CIPHERTEXTS) as u32,
+            ),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AccumulateNpsResponseCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: receipt_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "accumulate_nps_response")]
+    pub fn accumulate_nps_response_callback(
+        ctx: Context<AccumulateNpsResponseCallback>,
+        output: ComputationOutputs<AccumulateNpsResponseOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(AccumulateNpsResponseOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old tally
+        let receipt_key = ctx.accounts.receipt.key();
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.lock.release(receipt_key)?;
+        tally_storage.encrypted_tally = tally.ciphertexts;
+        tally_storage.tally_nonce = tally.nonce;
+        tally_storage.submissions = tally_storage.submissions.saturating_add(1);
+        ctx.accounts.receipt.status = BallotStatus::Recorded;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal the NPS (and optionally the three buckets) to the survey creator
+    ///
+    /// Circuit signature: reveal_nps(tally: Enc<Mxe, NpsTally>, min_responses: u32, include_buckets: bool, creator: Shared)
+    /// The minimum is checked inside MPC against answered ratings only, so skipped
+    /// submissions can't be used to get past it. Later reveals need min_responses new
+    /// submissions, so comparing two of them can't single out a few ratings.
+    pub fn reveal_nps(
+        ctx: Context<RevealNps>,
+        computation_offset: u64,
+        include_buckets: bool,
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Reveal a settled tally, so last_reveal_submissions is exactly what MPC reads
+        let now = Clock::get()?.unix_timestamp;
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        require!(!tally_storage.lock.is_held(now), ErrorCode::TallyBusy);
+        check_reveal_interval(
+            tally_storage.submissions,
+            tally_storage.last_reveal_submissions,
+            tally_storage.min_responses,
+        )?;
+        tally_storage.last_reveal_submissions = tally_storage.submissions;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let tally_storage = &ctx.accounts.tally_storage;
+        let args = vec![
+            // tally: Enc<Mxe, NpsTally> - 2 args
+            Argument::PlaintextU128(tally_storage.tally_nonce),
+            Argument::Account(
+                tally_storage.key(),
+                NpsTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * NPS_TALLY_CIPHERTEXTS) as u32,
+            ),
+            Argument::PlaintextU32(tally_storage.min_responses),
+            Argument::PlaintextBool(include_buckets),
+            // creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealNpsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_nps")]
+    pub fn reveal_nps_callback(
+        ctx: Context<RevealNpsCallback>,
+        output: ComputationOutputs<RevealNpsOutput>,
+    ) -> Result<()> {
+        let report = match output {
+            ComputationOutputs::Success(RevealNpsOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(NpsRevealed {
+            survey: ctx.accounts.tally_storage.survey,
+            question_id: ctx.accounts.tally_storage.question_id,
+            encryption_key: report.encryption_key,
+            nonce: report.nonce,
+            ciphertexts: report.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted histogram behind median / percentile reporting
+    ///
+    /// Bucket edges are public (`bucket_min + b * bucket_width`); only the counts are secret.
+    /// Works for Rating questions and TextInput questions that collect a number
+    /// (e.g. salary), which clients encrypt as a u32.
+    ///
+    /// Circuit signature: init_histogram(mxe: Mxe) -> Enc<Mxe, Histogram>
+    pub fn init_histogram(
+        ctx: Context<InitHistogram>,
+        computation_offset: u64,
+        question_id: u32,
+        bucket_min: u32,
+        bucket_width: u32,
+        min_responses: u32,
+        mxe_nonce: u128,
+        committed_question: Option<CommittedQuestion>,  // Only for surveys with committed content
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            min_responses >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+        validate_histogram_layout(bucket_min, bucket_width)?;
+
+        let question = survey.resolve_question(question_id, committed_question)?;
+        require!(
+            matches!(
+                question.question_type,
+                QuestionType::Rating { .. } | QuestionType::TextInput
+            ),
+            ErrorCode::QuestionTypeMismatch
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let histogram_storage = &mut ctx.accounts.histogram_storage;
+        histogram_storage.survey = survey_key;
+        histogram_storage.question_id = question_id;
+        histogram_storage.bucket_min = bucket_min;
+        histogram_storage.bucket_width = bucket_width;
+        histogram_storage.min_responses = min_responses;
+        histogram_storage.submissions = 0;
+        histogram_storage.bump = ctx.bumps.histogram_storage;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitHistogramCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.histogram_storage.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        msg!("Histogram init queued for question {}", question_id);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_histogram")]
+    pub fn init_histogram_callback(
+        ctx: Context<InitHistogramCallback>,
+        output: ComputationOutputs<InitHistogramOutput>,
+    ) -> Result<()> {
+        let histogram = match output {
+            ComputationOutputs::Success(InitHistogramOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let histogram_storage = &mut ctx.accounts.histogram_storage;
+        histogram_storage.encrypted_counts = histogram.ciphertexts;
+        histogram_storage.counts_nonce = histogram.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Submit one encrypted numeric answer to the histogram
+    ///
+    /// Circuit signature: accumulate_histogram_response(response: Enc<Shared, u32>, bucket_min: u32, bucket_width: u32, histogram: Enc<Mxe, Histogram>)
+    /// - `response` → ArcisPubkey + PlaintextU128 + EncryptedU32 (NOT_ANSWERED if skipped)
+    /// - `bucket_min`, `bucket_width` → PlaintextU32 each (from the storage account)
+    /// - `histogram` → PlaintextU128 (stored nonce) + Account (stored ciphertexts)
+    ///
+    /// One answer per wallet (TallyReceipt), folded in one at a time (TallyLock).
+    pub fn submit_histogram_response(
+        ctx: Context<SubmitHistogramResponse>,
+        computation_offset: u64,
+        encrypted_value: [u8; 32],
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.histogram_storage.submissions < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        let receipt_key = ctx.accounts.receipt.key();
+        ctx.accounts.histogram_storage.lock.acquire(receipt_key, now)?;
+        let histogram_storage_key = ctx.accounts.histogram_storage.key();
+        let respondent = ctx.accounts.payer.key();
+        ctx.accounts.receipt.queue(histogram_storage_key, respondent, now, ctx.bumps.receipt)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let histogram_storage = &ctx.accounts.histogram_storage;
+        let args = vec![
+            // response: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+            Argument::EncryptedU32(encrypted_value),
+            Argument::PlaintextU32(histogram_storage.bucket_min),
+            Argument::PlaintextU32(histogram_storage.bucket_width),
+            // histogram: Enc<Mxe, Histogram> - 2 args
+            Argument::PlaintextU128(histogram_storage.counts_nonce),
+            Argument::Account(
+                histogram_storage.key(),
+                HistogramStorage::ENCRYPTED_COUNTS_OFFSET,
+                (32 * HISTOGRAM_BUCKETS) as u32,
+            ),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AccumulateHistogramResponseCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.histogram_storage.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: receipt_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "accumulate_histogram_response")]
+    pub fn accumulate_histogram_response_callback(
+        ctx: Context<AccumulateHistogramResponseCallback>,
+        output: ComputationOutputs<AccumulateHistogramResponseOutput>,
+    ) -> Result<()> {
+        let histogram = match output {
+            ComputationOutputs::Success(AccumulateHistogramResponseOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old tally
+        let receipt_key = ctx.accounts.receipt.key();
+        let histogram_storage = &mut ctx.accounts.histogram_storage;
+        histogram_storage.lock.release(receipt_key)?;
+        histogram_storage.encrypted_counts = histogram.ciphertexts;
+        histogram_storage.counts_nonce = histogram.nonce;
+        histogram_storage.submissions = histogram_storage.submissions.saturating_add(1);
+        ctx.accounts.receipt.status = BallotStatus::Recorded;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal median, quartiles and two chosen percentiles to the survey creator
+    ///
+    /// Circuit signature: reveal_percentiles(histogram: Enc<Mxe, Histogram>, bucket_min: u32, bucket_width: u32, min_responses: u32, percentile_a: u8, percentile_b: u8, creator: Shared)
+    /// Values are interpolated inside a bucket, so precision is bounded by `bucket_width`.
+    /// Later reveals need min_responses new submissions (see check_reveal_interval).
+    pub fn reveal_percentiles(
+        ctx: Context<RevealPercentiles>,
+        computation_offset: u64,
+        percentile_a: u8,
+        percentile_b: u8,
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            percentile_a <= 100 && percentile_b <= 100,
+            ErrorCode::InvalidPercentile
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let histogram_storage = &mut ctx.accounts.histogram_storage;
+        require!(!histogram_storage.lock.is_held(now), ErrorCode::TallyBusy);
+        check_reveal_interval(
+            histogram_storage.submissions,
+            histogram_storage.last_reveal_submissions,
+            histogram_storage.min_responses,
+        )?;
+        histogram_storage.last_reveal_submissions = histogram_storage.submissions;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let histogram_storage = &ctx.accounts.histogram_storage;
+        let args = vec![
+            // histogram: Enc<Mxe, Histogram> - 2 args
+            Argument::PlaintextU128(histogram_storage.counts_nonce),
+            Argument::Account(
+                histogram_storage.key(),
+                HistogramStorage::ENCRYPTED_COUNTS_OFFSET,
+                (32 * HISTOGRAM_BUCKETS) as u32,
+            ),
+            Argument::PlaintextU32(histogram_storage.bucket_min),
+            Argument::PlaintextU32(histogram_storage.bucket_width),
+            Argument::PlaintextU32(histogram_storage.min_responses),
+            Argument::PlaintextU8(percentile_a),
+            Argument::PlaintextU8(percentile_b),
+            // creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealPercentilesCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.histogram_storage.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_percentiles")]
+    pub fn reveal_percentiles_callback(
+        ctx: Context<RevealPercentilesCallback>,
+        output: ComputationOutputs<RevealPercentilesOutput>,
+    ) -> Result<()> {
+        let report = match output {
+            ComputationOutputs::Success(RevealPercentilesOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(PercentilesRevealed {
+            survey: ctx.accounts.histogram_storage.survey,
+            question_id: ctx.accounts.histogram_storage.question_id,
+            encryption_key: report.encryption_key,
+            nonce: report.nonce,
+            ciphertexts: report.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted contingency table for a pair of questions
+    ///
+    /// Both questions must be MultipleChoice (categories = options) or Rating
+    /// (categories = min..=max) with at most MAX_CROSSTAB_CATEGORIES categories.
+    ///
+    /// Circuit signature: init_crosstab(mxe: Mxe) -> Enc<Mxe, CrosstabTable>
+    pub fn init_crosstab(
+        ctx: Context<InitCrosstab>,
+        computation_offset: u64,
+        question_a: u32,
+        question_b: u32,
+        suppression_threshold: u32,
+        mxe_nonce: u128,
+        committed_question_a: Option<CommittedQuestion>,  // Only for surveys with committed content
+        committed_question_b: Option<CommittedQuestion>,
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(question_a != question_b, ErrorCode::InvalidCrosstabQuestion);
+        require!(
+            suppression_threshold >= MIN_CROSSTAB_SUPPRESSION_THRESHOLD,
+            ErrorCode::InvalidSuppressionThreshold
+        );
+
+        let (offset_a, categories_a) = crosstab_dimension(&survey.resolve_question(question_a, committed_question_a)?)?;
+        let (offset_b, categories_b) = crosstab_dimension(&survey.resolve_question(question_b, committed_question_b)?)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let crosstab_storage = &mut ctx.accounts.crosstab_storage;
+        crosstab_storage.survey = survey_key;
+        crosstab_storage.question_a = question_a;
+        crosstab_storage.question_b = question_b;
+        crosstab_storage.offset_a = offset_a;
+        crosstab_storage.categories_a = categories_a;
+        crosstab_storage.offset_b = offset_b;
+        crosstab_storage.categories_b = categories_b;
+        crosstab_storage.suppression_threshold = suppression_threshold;
+        crosstab_storage.submissions = 0;
+        crosstab_storage.bump = ctx.bumps.crosstab_storage;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitCrosstabCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.crosstab_storage.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        msg!("Crosstab init queued for questions {} x {}", question_a, question_b);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_crosstab")]
+    pub fn init_crosstab_callback(
+        ctx: Context<InitCrosstabCallback>,
+        output: ComputationOutputs<InitCrosstabOutput>,
+    ) -> Result<()> {
+        let table = match output {
+            ComputationOutputs::Success(InitCrosstabOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let crosstab_storage = &mut ctx.accounts.crosstab_storage;
+        crosstab_storage.encrypted_table = table.ciphertexts;
+        crosstab_storage.table_nonce = table.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Submit one respondent's encrypted (A, B) answer pair
+    ///
+    /// Circuit signature: accumulate_crosstab_response(answers: Enc<Shared, [u32; 2]>, offset_a: u32, categories_a: u8, offset_b: u32, categories_b: u8, table: Enc<Mxe, CrosstabTable>)
+    /// - `answers` → ArcisPubkey + PlaintextU128 + 2 × EncryptedU32 (NOT_ANSWERED if skipped)
+    /// - question dimensions → PlaintextU32 / PlaintextU8 (from the storage account)
+    /// - `table` → PlaintextU128 (stored nonce) + Account (stored ciphertexts)
+    ///
+    /// One answer pair per wallet (TallyReceipt), folded in one at a time (TallyLock).
+    pub fn submit_crosstab_response(
+        ctx: Context<SubmitCrosstabResponse>,
+        computation_offset: u64,
+        encrypted_answers: [[u8; 32]; 2],
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.crosstab_storage.submissions < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        let receipt_key = ctx.accounts.receipt.key();
+        ctx.accounts.crosstab_storage.lock.acquire(receipt_key, now)?;
+        let crosstab_storage_key = ctx.accounts.crosstab_storage.key();
+        let respondent = ctx.accounts.payer.key();
+        ctx.accounts.receipt.queue(crosstab_storage_key, respondent, now, ctx.bumps.receipt)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let crosstab_storage = &ctx.accounts.crosstab_storage;
+        let args = vec![
+            // answers: Enc<Shared, [u32; 2]> - 4 args
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+            Argument::EncryptedU32(encrypted_answers[0]),
+            Argument::EncryptedU32(encrypted_answers[1]),
+            Argument::PlaintextU32(crosstab_storage.offset_a),
+            Argument::PlaintextU8(crosstab_storage.categories_a),
+            Argument::PlaintextU32(crosstab_storage.offset_b),
+            Argument::PlaintextU8(crosstab_storage.categories_b),
+            // table: Enc<Mxe, CrosstabTable> - 2 args
+            Argument::PlaintextU128(crosstab_storage.table_nonce),
+            Argument::Account(
+                crosstab_storage.key(),
+                CrosstabStorage::ENCRYPTED_TABLE_OFFSET,
+                (32 * CROSSTAB_CELLS) as u32,
+            ),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AccumulateCrosstabResponseCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.crosstab_storage.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: receipt_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "accumulate_crosstab_response")]
+    pub fn accumulate_crosstab_response_callback(
+        ctx: Context<AccumulateCrosstabResponseCallback>,
+        output: ComputationOutputs<AccumulateCrosstabResponseOutput>,
+    ) -> Result<()> {
+        let table = match output {
+            ComputationOutputs::Success(AccumulateCrosstabResponseOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old tally
+        let receipt_key = ctx.accounts.receipt.key();
+        let crosstab_storage = &mut ctx.accounts.crosstab_storage;
+        crosstab_storage.lock.release(receipt_key)?;
+        crosstab_storage.encrypted_table = table.ciphertexts;
+        crosstab_storage.table_nonce = table.nonce;
+        crosstab_storage.submissions = crosstab_storage.submissions.saturating_add(1);
+        ctx.accounts.receipt.status = BallotStatus::Recorded;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal the suppressed contingency table to the survey creator
+    ///
+    /// Circuit signature: reveal_crosstab(table: Enc<Mxe, CrosstabTable>, suppression_threshold: u32, creator: Shared) -> Enc<Shared, CrosstabReport>
+    /// Suppressed cells decrypt to u32::MAX.
+    pub fn reveal_crosstab(
+        ctx: Context<RevealCrosstab>,
+        computation_offset: u64,
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.crosstab_storage.submissions >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let crosstab_storage = &ctx.accounts.crosstab_storage;
+        let args = vec![
+            // table: Enc<Mxe, CrosstabTable> - 2 args
+            Argument::PlaintextU128(crosstab_storage.table_nonce),
+            Argument::Account(
+                crosstab_storage.key(),
+                CrosstabStorage::ENCRYPTED_TABLE_OFFSET,
+                (32 * CROSSTAB_CELLS) as u32,
+            ),
+            Argument::PlaintextU32(crosstab_storage.suppression_threshold),
+            // creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealCrosstabCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.crosstab_storage.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_crosstab")]
+    pub fn reveal_crosstab_callback(
+        ctx: Context<RevealCrosstabCallback>,
+        output: ComputationOutputs<RevealCrosstabOutput>,
+    ) -> Result<()> {
+        let report = match output {
+            ComputationOutputs::Success(RevealCrosstabOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(CrosstabRevealed {
+            survey: ctx.accounts.crosstab_storage.survey,
+            question_a: ctx.accounts.crosstab_storage.question_a,
+            question_b: ctx.accounts.crosstab_storage.question_b,
+            encryption_key: report.encryption_key,
+            nonce: report.nonce,
+            ciphertexts: report.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted correlation sums used by `analytics_computation`
+    ///
+    /// Circuit signature: init_correlation_sums(mxe: Mxe) -> Enc<Mxe, RunningSums>
+    pub fn init_correlation_sums(
+        ctx: Context<InitCorrelationSums>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let correlation_sums = &mut ctx.accounts.correlation_sums;
+        correlation_sums.survey = survey_key;
+        correlation_sums.bump = ctx.bumps.correlation_sums;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitCorrelationSumsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.correlation_sums.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_correlation_sums")]
+    pub fn init_correlation_sums_callback(
+        ctx: Context<InitCorrelationSumsCallback>,
+        output: ComputationOutputs<InitCorrelationSumsOutput>,
+    ) -> Result<()> {
+        let sums = match output {
+            ComputationOutputs::Success(InitCorrelationSumsOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let correlation_sums = &mut ctx.accounts.correlation_sums;
+        correlation_sums.encrypted_sums = sums.ciphertexts;
+        correlation_sums.sums_nonce = sums.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Run `analytics_computation` for one respondent and update the correlation sums
+    ///
+    /// Circuit parameters → Instruction arguments mapping:
+    /// - `response1: Enc<Shared, u32>` → 3 args: ArcisPubkey + PlaintextU128 + EncryptedU32
+    /// - `response2: Enc<Shared, u32>` → 3 args: ArcisPubkey + PlaintextU128 + EncryptedU32
+    /// - `survey_type: Enc<Mxe, u32>` → 2 args: PlaintextU128 + EncryptedU32
+    /// - `running_sums: Enc<Mxe, RunningSums>` → 2 args: PlaintextU128 + Account
+    /// - `data_analyst`, `business_stakeholder`, `researcher: Shared` → 2 args each
+    ///
+    /// One response per wallet (TallyReceipt), folded in one at a time (TallyLock).
+    pub fn submit_research_analytics(
+        ctx: Context<SubmitResearchAnalytics>,
+        analytics_computation_offset: u64,
+        ciphertext_response1: [u8; 32],
+        ciphertext_response2: [u8; 32],
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+        ciphertext_survey_type: [u8; 32],  // MXE-encrypted survey type
+        survey_type_nonce: u128,
+        data_analyst_pub_key: [u8; 32],
+        data_analyst_nonce: u128,
+        business_stakeholder_pub_key: [u8; 32],
+        business_stakeholder_nonce: u128,
+        researcher_pub_key: [u8; 32],
+        researcher_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.correlation_sums.submissions < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        let receipt_key = ctx.accounts.receipt.key();
+        ctx.accounts.correlation_sums.lock.acquire(receipt_key, now)?;
+        let correlation_sums_key = ctx.accounts.correlation_sums.key();
+        let respondent = ctx.accounts.payer.key();
+        ctx.accounts.receipt.queue(correlation_sums_key, respondent, now, ctx.bumps.receipt)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let correlation_sums = &ctx.accounts.correlation_sums;
+        let args = vec![
+            // response1: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+            Argument::EncryptedU32(ciphertext_response1),
+            // response2: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(respondent_nonce),
+            Argument::EncryptedU32(ciphertext_response2),
+            // survey_type: Enc<Mxe, u32> - 2 args
+            Argument::PlaintextU128(survey_type_nonce),
+            Argument::EncryptedU32(ciphertext_survey_type),
+            // running_sums: Enc<Mxe, RunningSums> - 2 args
+            Argument::PlaintextU128(correlation_sums.sums_nonce),
+            Argument::Account(
+                correlation_sums.key(),
+                CorrelationSumsStorage::ENCRYPTED_SUMS_OFFSET,
+                (32 * CORRELATION_SUMS_CIPHERTEXTS) as u32,
+            ),
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.survey.report_decimals),
+            // data_analyst: Shared - 2 args
+            Argument::ArcisPubkey(data_analyst_pub_key),
+            Argument::PlaintextU128(data_analyst_nonce),
+            // business_stakeholder: Shared - 2 args
+            Argument::ArcisPubkey(business_stakeholder_pub_key),
+            Argument::PlaintextU128(business_stakeholder_nonce),
+            // researcher: Shared - 2 args
+            Argument::ArcisPubkey(researcher_pub_key),
+            Argument::PlaintextU128(researcher_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            analytics_computation_offset,
+            args,
+            None,
+            vec![AnalyticsComputationCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.correlation_sums.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: receipt_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted item analysis totals updated by `quiz_evaluation`
+    ///
+    /// Must exist before `compute_quiz_grade`, which folds every graded attempt into it.
+    ///
+    /// Circuit signature: init_item_stats(mxe: Mxe) -> Enc<Mxe, ItemStats>
+    pub fn init_item_stats(
+        ctx: Context<InitItemStats>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.quiz.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            matches!(ctx.accounts.quiz.survey_type, SurveyType::Quiz { .. }),
+            ErrorCode::InvalidSurveyType
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let quiz_key = ctx.accounts.quiz.key();
+        let item_stats = &mut ctx.accounts.item_stats;
+        item_stats.quiz = quiz_key;
+        item_stats.bump = ctx.bumps.item_stats;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitItemStatsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.item_stats.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_item_stats")]
+    pub fn init_item_stats_callback(
+        ctx: Context<InitItemStatsCallback>,
+        output: ComputationOutputs<InitItemStatsOutput>,
+    ) -> Result<()> {
+        let stats = match output {
+            ComputationOutputs::Success(InitItemStatsOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let item_stats = &mut ctx.accounts.item_stats;
+        item_stats.encrypted_stats = stats.ciphertexts;
+        item_stats.stats_nonce = stats.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal per-question difficulty and discrimination (creator only)
+    ///
+    /// Circuit signature: reveal_item_analysis(item_stats: Enc<Mxe, ItemStats>, min_responses: u32,
+    ///                                         instructor: Shared) -> Enc<Shared, ItemAnalysisReport>
+    /// The report is zeroed inside MPC until MIN_TALLY_REVEAL_SUBMISSIONS attempts are graded,
+    /// and each later reveal needs that many newly graded attempts (see check_reveal_interval).
+    pub fn reveal_item_analysis(
+        ctx: Context<RevealItemAnalysis>,
+        computation_offset: u64,
+        instructor_pub_key: [u8; 32],
+        instructor_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.quiz.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let item_stats = &mut ctx.accounts.item_stats;
+        require!(!item_stats.lock.is_held(now), ErrorCode::GradingBusy);
+        check_reveal_interval(
+            item_stats.graded_attempts,
+            item_stats.last_reveal_submissions,
+            MIN_TALLY_REVEAL_SUBMISSIONS,
+        )?;
+        item_stats.last_reveal_submissions = item_stats.graded_attempts;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let item_stats = &ctx.accounts.item_stats;
+        let args = vec![
+            // item_stats: Enc<Mxe, ItemStats> - 2 args
+            Argument::PlaintextU128(item_stats.stats_nonce),
+            Argument::Account(
+                item_stats.key(),
+                ItemStatsStorage::ENCRYPTED_STATS_OFFSET,
+                (32 * ITEM_STATS_CIPHERTEXTS) as u32,
+            ),
+            Argument::PlaintextU32(MIN_TALLY_REVEAL_SUBMISSIONS),
+            // instructor: Shared - 2 args
+            Argument::ArcisPubkey(instructor_pub_key),
+            Argument::PlaintextU128(instructor_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealItemAnalysisCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.item_stats.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_item_analysis")]
+    pub fn reveal_item_analysis_callback(
+        ctx: Context<RevealItemAnalysisCallback>,
+        output: ComputationOutputs<RevealItemAnalysisOutput>,
+    ) -> Result<()> {
+        let report = match output {
+            ComputationOutputs::Success(RevealItemAnalysisOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(ItemAnalysisRevealed {
+            quiz: ctx.accounts.item_stats.quiz,
+            encryption_key: report.encryption_key,
+            nonce: report.nonce,
+            ciphertexts: report.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted leaderboard students can opt into
+    ///
+    /// Circuit signature: init_leaderboard(mxe: Mxe) -> Enc<Mxe, Leaderboard>
+    pub fn init_leaderboard(
+        ctx: Context<InitLeaderboard>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.quiz.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            matches!(ctx.accounts.quiz.survey_type, SurveyType::Quiz { .. }),
+            ErrorCode::InvalidSurveyType
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let quiz_key = ctx.accounts.quiz.key();
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.quiz = quiz_key;
+        leaderboard.entries = 0;
+        leaderboard.bump = ctx.bumps.leaderboard;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitLeaderboardCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.leaderboard.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_leaderboard")]
+    pub fn init_leaderboard_callback(
+        ctx: Context<InitLeaderboardCallback>,
+        output: ComputationOutputs<InitLeaderboardOutput>,
+    ) -> Result<()> {
+        let board = match output {
+            ComputationOutputs::Success(InitLeaderboardOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.encrypted_leaderboard = board.ciphertexts;
+        leaderboard.leaderboard_nonce = board.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Add the student's submitted attempt to the leaderboard (opt-in, once per student)
+    ///
+    /// The attempt is re-graded inside MPC with the same scoring as `quiz_evaluation`.
+    /// The pseudonym is encrypted by the student, so only they can find themselves
+    /// on the published board. A pseudonym already on the board is turned away
+    /// (`LeaderboardPseudonymTaken`) and the student may join again with another one.
+    ///
+    /// Circuit parameters → Instruction arguments mapping:
+    /// - `user_answers: Enc<Shared, [u32; 2]>` → 3 args: ArcisPubkey + PlaintextU128 + Account
+    /// - `answer_shuffle: u128` → 1 arg: PlaintextU128
+    /// - `quiz_data: Enc<Mxe, [u32; 14]>` → 2 args: PlaintextU128 + Account
+    /// - `pseudonym: Enc<Shared, u32>` → 3 args: ArcisPubkey + PlaintextU128 + EncryptedU32
+    /// - `leaderboard: Enc<Mxe, Leaderboard>` → 2 args: PlaintextU128 + Account
+    /// - `entry: Mxe` → 1 arg: PlaintextU128
+    pub fn join_leaderboard(
+        ctx: Context<JoinLeaderboard>,
+        computation_offset: u64,
+        student_pub_key: [u8; 32],
+        student_nonce: u128,          // Nonce the stored answers were encrypted with
+        ciphertext_pseudonym: [u8; 32],
+        pseudonym_nonce: u128,
+        entry_nonce: u128,            // Fresh MXE nonce for the student's entry
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let now = Clock::get()?.unix_timestamp;
+        let quiz_key = ctx.accounts.quiz.key();
+        let student = ctx.accounts.payer.key();
+        let entry_key = ctx.accounts.leaderboard_entry.key();
+        let leaderboard_entry = &mut ctx.accounts.leaderboard_entry;
+        require!(
+            leaderboard_entry.status != BallotStatus::Recorded,
+            ErrorCode::AlreadyOnLeaderboard
+        );
+        leaderboard_entry.quiz = quiz_key;
+        leaderboard_entry.student = student;
+        leaderboard_entry.status = BallotStatus::Queued;
+        leaderboard_entry.bump = ctx.bumps.leaderboard_entry;
+
+        // Entries are inserted one at a time so none is lost to a stale board
+        ctx.accounts.leaderboard.lock.acquire(entry_key, now)?;
+
+        let leaderboard = &ctx.accounts.leaderboard;
+        let args = vec![
+            // user_answers: Enc<Shared, [u32; 2]> - 3 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+            Argument::Account(
+                ctx.accounts.answers_storage.key(),
+                8 + 32 + 32,  // Discriminator + quiz + student
+                64            // 2 answers * 32 bytes each
+            ),
+            // answer_shuffle: u128 - 1 arg
+            Argument::PlaintextU128(ctx.accounts.quiz.answer_shuffle(&ctx.accounts.answers_storage)),
+            // quiz_data: Enc<Mxe, [u32; 14]> - 2 args
+            Argument::PlaintextU128(ctx.accounts.quiz_grading_data.quiz_data_nonce),
+            Argument::Account(
+                ctx.accounts.quiz_grading_data.key(),
+                8 + 32,  // Discriminator + quiz
+                (32 * QUIZ_GRADING_VALUES) as u32
+            ),
+            // pseudonym: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(pseudonym_nonce),
+            Argument::EncryptedU32(ciphertext_pseudonym),
+            // leaderboard: Enc<Mxe, Leaderboard> - 2 args
+            Argument::PlaintextU128(leaderboard.leaderboard_nonce),
+            Argument::Account(
+                leaderboard.key(),
+                LeaderboardStorage::ENCRYPTED_LEADERBOARD_OFFSET,
+                (32 * LEADERBOARD_CIPHERTEXTS) as u32,
+            ),
+            // entry: Mxe - 1 arg
+            Argument::PlaintextU128(entry_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RecordLeaderboardScoreCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.leaderboard.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.leaderboard_entry.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "record_leaderboard_score")]
+    pub fn record_leaderboard_score_callback(
+        ctx: Context<RecordLeaderboardScoreCallback>,
+        output: ComputationOutputs<RecordLeaderboardScoreOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(RecordLeaderboardScoreOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old board
+        let entry_key = ctx.accounts.leaderboard_entry.key();
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.lock.release(entry_key)?;
+
+        // Rejected entries leave the board unchanged; the student can join again
+        let accepted = result.field_0;
+        leaderboard.encrypted_leaderboard = result.field_1.ciphertexts;
+        leaderboard.leaderboard_nonce = result.field_1.nonce;
+        if !accepted {
+            emit!(LeaderboardPseudonymTaken {
+                quiz: leaderboard.quiz,
+                student: ctx.accounts.leaderboard_entry.student,
+            });
+            return Ok(());
+        }
+        leaderboard.entries = leaderboard.entries.saturating_add(1);
+
+        let leaderboard_entry = &mut ctx.accounts.leaderboard_entry;
+        leaderboard_entry.encrypted_entry = result.field_2.ciphertexts;
+        leaderboard_entry.entry_nonce = result.field_2.nonce;
+        leaderboard_entry.status = BallotStatus::Recorded;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal the caller's own rank and percentile, encrypted to their key
+    ///
+    /// Circuit signature: reveal_my_rank(entry: Enc<Mxe, LeaderboardEntry>, leaderboard: Enc<Mxe, Leaderboard>,
+    ///                                   min_responses: u32, student: Shared) -> Enc<Shared, RankReport>
+    pub fn reveal_my_rank(
+        ctx: Context<RevealMyRank>,
+        computation_offset: u64,
+        student_pub_key: [u8; 32],
+        student_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.leaderboard_entry.status == BallotStatus::Recorded,
+            ErrorCode::NotOnLeaderboard
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let leaderboard_entry = &ctx.accounts.leaderboard_entry;
+        let leaderboard = &ctx.accounts.leaderboard;
+        let args = vec![
+            // entry: Enc<Mxe, LeaderboardEntry> - 2 args
+            Argument::PlaintextU128(leaderboard_entry.entry_nonce),
+            Argument::Account(
+                leaderboard_entry.key(),
+                LeaderboardEntryStorage::ENCRYPTED_ENTRY_OFFSET,
+                (32 * LEADERBOARD_ENTRY_CIPHERTEXTS) as u32,
+            ),
+            // leaderboard: Enc<Mxe, Leaderboard> - 2 args
+            Argument::PlaintextU128(leaderboard.leaderboard_nonce),
+            Argument::Account(
+                leaderboard.key(),
+                LeaderboardStorage::ENCRYPTED_LEADERBOARD_OFFSET,
+                (32 * LEADERBOARD_CIPHERTEXTS) as u32,
+            ),
+            Argument::PlaintextU32(MIN_TALLY_REVEAL_SUBMISSIONS),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealMyRankCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.leaderboard_entry.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_my_rank")]
+    pub fn reveal_my_rank_callback(
+        ctx: Context<RevealMyRankCallback>,
+        output: ComputationOutputs<RevealMyRankOutput>,
+    ) -> Result<()> {
+        let report = match output {
+            ComputationOutputs::Success(RevealMyRankOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(RankRevealed {
+            quiz: ctx.accounts.leaderboard_entry.quiz,
+            student: ctx.accounts.leaderboard_entry.student,
+            encryption_key: report.encryption_key,
+            nonce: report.nonce,
+            ciphertexts: report.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Publish the pseudonymous top K (creator only)
+    ///
+    /// Circuit signature: reveal_leaderboard(leaderboard: Enc<Mxe, Leaderboard>, min_responses: u32)
+    ///                    -> (bool, [u32; K], [u32; K]), revealed in plaintext
+    /// Each later publication needs MIN_TALLY_REVEAL_SUBMISSIONS new entries (see check_reveal_interval).
+    pub fn publish_leaderboard(
+        ctx: Context<PublishLeaderboard>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.quiz.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.leaderboard.entries >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        require!(!leaderboard.lock.is_held(now), ErrorCode::TallyBusy);
+        check_reveal_interval(
+            leaderboard.entries,
+            leaderboard.last_reveal_entries,
+            MIN_TALLY_REVEAL_SUBMISSIONS,
+        )?;
+        leaderboard.last_reveal_entries = leaderboard.entries;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let leaderboard = &ctx.accounts.leaderboard;
+        let args = vec![
+            // leaderboard: Enc<Mxe, Leaderboard> - 2 args
+            Argument::PlaintextU128(leaderboard.leaderboard_nonce),
+            Argument::Account(
+                leaderboard.key(),
+                LeaderboardStorage::ENCRYPTED_LEADERBOARD_OFFSET,
+                (32 * LEADERBOARD_CIPHERTEXTS) as u32,
+            ),
+            Argument::PlaintextU32(MIN_TALLY_REVEAL_SUBMISSIONS),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealLeaderboardCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.leaderboard.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_leaderboard")]
+    pub fn reveal_leaderboard_callback(
+        ctx: Context<RevealLeaderboardCallback>,
+        output: ComputationOutputs<RevealLeaderboardOutput>,
+    ) -> Result<()> {
+        let board = match output {
+            ComputationOutputs::Success(RevealLeaderboardOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(LeaderboardPublished {
+            quiz: ctx.accounts.leaderboard.quiz,
+            threshold_met: board.field_0,
+            pseudonyms: board.field_1,
+            scores: board.field_2,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted answer history used for similarity checks
+    ///
+    /// Circuit signature: init_answer_history(mxe: Mxe) -> Enc<Mxe, AnswerHistory>
+    pub fn init_answer_history(
+        ctx: Context<InitAnswerHistory>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+        similarity_threshold: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.quiz.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            matches!(ctx.accounts.quiz.survey_type, SurveyType::Quiz { .. }),
+            ErrorCode::InvalidSurveyType
+        );
+        require!(
+            similarity_threshold >= 1 && similarity_threshold as usize <= QUIZ_ITEMS,
+            ErrorCode::InvalidSimilarityThreshold
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let quiz_key = ctx.accounts.quiz.key();
+        let answer_history = &mut ctx.accounts.answer_history;
+        answer_history.quiz = quiz_key;
+        answer_history.similarity_threshold = similarity_threshold;
+        answer_history.checked = 0;
+        answer_history.bump = ctx.bumps.answer_history;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitAnswerHistoryCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.answer_history.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_answer_history")]
+    pub fn init_answer_history_callback(
+        ctx: Context<InitAnswerHistoryCallback>,
+        output: ComputationOutputs<InitAnswerHistoryOutput>,
+    ) -> Result<()> {
+        let history = match output {
+            ComputationOutputs::Success(InitAnswerHistoryOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let answer_history = &mut ctx.accounts.answer_history;
+        answer_history.encrypted_history = history.ciphertexts;
+        answer_history.history_nonce = history.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Flag how many recent submissions match this one (student or quiz creator)
+    ///
+    /// Each submission is checked once, then joins the history for later ones.
+    /// Only shared wrong answers count, so students who both got everything right
+    /// are never flagged. Checks run one at a time (TallyLock on the history).
+    /// The match count is encrypted to the quiz's `instructor_arcium_pubkey`.
+    ///
+    /// Circuit signature: check_answer_similarity(user_answers: Enc<Shared, [u32; 2]>, answer_shuffle: u128,
+    ///                    quiz_data: Enc<Mxe, [u32; 14]>,
+    ///                    history: Enc<Mxe, AnswerHistory>, similarity_threshold: u8,
+    ///                    instructor: Shared) -> (Enc<Mxe, AnswerHistory>, Enc<Shared, u32>)
+    pub fn check_answer_similarity(
+        ctx: Context<CheckAnswerSimilarity>,
+        computation_offset: u64,
+        student_pub_key: [u8; 32],
+        student_nonce: u128,        // Nonce the stored answers were encrypted with
+        instructor_nonce: u128,
+    ) -> Result<()> {
+        let payer = ctx.accounts.payer.key();
+        require!(
+            payer == ctx.accounts.quiz.creator || payer == ctx.accounts.answers_storage.student,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !ctx.accounts.answers_storage.similarity_checked,
+            ErrorCode::SimilarityAlreadyChecked
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let answers_key = ctx.accounts.answers_storage.key();
+        ctx.accounts.answer_history.lock.acquire(answers_key, now)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let answer_history = &ctx.accounts.answer_history;
+        let args = vec![
+            // user_answers: Enc<Shared, [u32; 2]> - 3 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+            Argument::Account(
+                ctx.accounts.answers_storage.key(),
+                8 + 32 + 32,  // Discriminator + quiz + student
+                64            // 2 answers * 32 bytes each
+            ),
+            // answer_shuffle: u128 - 1 arg
+            Argument::PlaintextU128(ctx.accounts.quiz.answer_shuffle(&ctx.accounts.answers_storage)),
+            // quiz_data: Enc<Mxe, [u32; 14]> - 2 args
+            Argument::PlaintextU128(ctx.accounts.quiz_grading_data.quiz_data_nonce),
+            Argument::Account(
+                ctx.accounts.quiz_grading_data.key(),
+                8 + 32,  // Discriminator + quiz
+                (32 * QUIZ_GRADING_VALUES) as u32
+            ),
+            // history: Enc<Mxe, AnswerHistory> - 2 args
+            Argument::PlaintextU128(answer_history.history_nonce),
+            Argument::Account(
+                answer_history.key(),
+                AnswerHistoryStorage::ENCRYPTED_HISTORY_OFFSET,
+                (32 * ANSWER_HISTORY_CIPHERTEXTS) as u32,
+            ),
+            Argument::PlaintextU8(answer_history.similarity_threshold),
+            // instructor: Shared - 2 args
+            Argument::ArcisPubkey(ctx.accounts.quiz.instructor_arcium_pubkey),
+            Argument::PlaintextU128(instructor_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CheckAnswerSimilarityCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.answer_history.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.answers_storage.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        msg!("Similarity check queued. Quiz: {}, Student: {}",
+            ctx.accounts.quiz.key(), ctx.accounts.answers_storage.student);
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "check_answer_similarity")]
+    pub fn check_answer_similarity_callback(
+        ctx: Context<CheckAnswerSimilarityCallback>,
+        output: ComputationOutputs<CheckAnswerSimilarityOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(CheckAnswerSimilarityOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was compared against an old history
+        let answers_key = ctx.accounts.answers_storage.key();
+        let answer_history = &mut ctx.accounts.answer_history;
+        answer_history.lock.release(answers_key)?;
+        answer_history.encrypted_history = result.field_0.ciphertexts;
+        answer_history.history_nonce = result.field_0.nonce;
+        answer_history.checked = answer_history.checked.saturating_add(1);
+        ctx.accounts.answers_storage.similarity_checked = true;
+
+        emit!(SimilarityChecked {
+            quiz: answer_history.quiz,
+            student: ctx.accounts.answers_storage.student,
+            encryption_key: result.field_1.encryption_key,
+            nonce: result.field_1.nonce,
+            flagged_matches: result.field_1.ciphertexts[0],
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Grade a pooled quiz attempt (STEP 2 for pooled quizzes, like compute_quiz_grade)
+    ///
+    /// Circuit signature: pool_quiz_evaluation(user_answers: Enc<Shared, [u32; 2]>,
+    ///                    pool_keys: Enc<Mxe, PoolKeys>, drawn1: u8, drawn2: u8, report_decimals: u8,
+    ///                    student: Shared, quiz_creator: Shared)
+    ///                    -> (Enc<Shared, QuizResult>, Enc<Shared, StudentFeedback>)
+    pub fn grade_pool_attempt(
+        ctx: Context<GradePoolAttempt>,
+        computation_offset: u64,
+        student_pub_key: [u8; 32],
+        student_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.answers_storage.submission_timestamp >= ctx.accounts.quiz_attempt.started_at,
+            ErrorCode::AnswersBeforeAttempt
+        );
+        begin_grading(
+            &mut ctx.accounts.quiz,
+            &ctx.accounts.app_preferences,
+            &mut ctx.accounts.answers_storage,
+            false,
+        )?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let drawn = ctx.accounts.quiz_attempt.drawn_questions;
+        let question_pool = &ctx.accounts.question_pool;
+        let args = vec![
+            // user_answers: Enc<Shared, [u32; 2]> - 3 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+            Argument::Account(
+                ctx.accounts.answers_storage.key(),
+                8 + 32 + 32,  // Discriminator + quiz + student
+                64            // 2 answers * 32 bytes each
+            ),
+            // pool_keys: Enc<Mxe, PoolKeys> - 2 args
+            Argument::PlaintextU128(question_pool.pool_keys_nonce),
+            Argument::Account(
+                question_pool.key(),
+                QuestionPoolStorage::ENCRYPTED_POOL_KEYS_OFFSET,
+                (32 * POOL_KEYS_VALUES) as u32,
+            ),
+            // drawn1, drawn2: u8 - 2 args
+            Argument::PlaintextU8(drawn[0]),
+            Argument::PlaintextU8(drawn[1]),
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.quiz.report_decimals),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+            // quiz_creator: Shared - 2 args
+            Argument::ArcisPubkey(ctx.accounts.quiz.creator_arcium_pubkey),
+            Argument::PlaintextU128(Clock::get()?.unix_timestamp as u128 + 1),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![PoolQuizEvaluationCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.answers_storage.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        msg!("Pooled quiz grading queued. Offset: {}", computation_offset);
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "pool_quiz_evaluation")]
+    pub fn pool_quiz_evaluation_callback(
+        ctx: Context<PoolQuizEvaluationCallback>,
+        output: ComputationOutputs<PoolQuizEvaluationOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(PoolQuizEvaluationOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let answers_storage = &mut ctx.accounts.answers_storage;
+        answers_storage.grading_status = GradingStatus::Completed;
+
+        emit!(PoolQuizGraded {
+            quiz: answers_storage.quiz,
+            student: answers_storage.student,
+            student_result: result.field_0,
+            creator_feedback: result.field_1,
+        });
+
+        Ok(())
+    }
+
+    // ✅ NEW: Store the answer keys for an adaptive (placement) quiz
+    /// Questions are laid out as a grid: step s, tier t is on-chain question
+    /// s * ADAPTIVE_TIERS + t, tiers ordered easiest to hardest.
+    pub fn set_adaptive_bank(
+        ctx: Context<SetAdaptiveBank>,
+        encrypted_adaptive_keys: [[u8; 32]; ADAPTIVE_BANK_SIZE],  // Enc<Mxe, AdaptiveKeys>
+        mxe_encryption_pubkey: [u8; 32],
+        adaptive_keys_nonce: u128,
+    ) -> Result<()> {
+        let quiz = &ctx.accounts.quiz;
+
+        require!(
+            quiz.creator == ctx.accounts.creator.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            matches!(quiz.survey_type, SurveyType::Quiz { .. }),
+            ErrorCode::InvalidSurveyType
+        );
+        require!(
+            quiz.questions.len() >= ADAPTIVE_BANK_SIZE && quiz.current_responses == 0,
+            ErrorCode::InvalidAdaptiveBank
+        );
+
+        let adaptive_bank = &mut ctx.accounts.adaptive_bank;
+        adaptive_bank.quiz = quiz.key();
+        adaptive_bank.encrypted_adaptive_keys = encrypted_adaptive_keys;
+        adaptive_bank.adaptive_keys_nonce = adaptive_keys_nonce;
+        adaptive_bank.mxe_encryption_pubkey = mxe_encryption_pubkey;
+        adaptive_bank.bump = ctx.bumps.adaptive_bank;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Start an adaptive attempt at the middle tier
+    ///
+    /// The first question index is encrypted to the student like every later one,
+    /// so the event stream looks the same at every step.
+    ///
+    /// Circuit signature: init_adaptive_attempt(mxe: Mxe, student: Shared)
+    ///                    -> (Enc<Mxe, AdaptiveState>, Enc<Shared, u32>)
+    pub fn start_adaptive_attempt(
+        ctx: Context<StartAdaptiveAttempt>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+        student_pub_key: [u8; 32],
+        student_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.quiz.is_active, ErrorCode::SurveyInactive);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let quiz_key = ctx.accounts.quiz.key();
+        let adaptive_attempt = &mut ctx.accounts.adaptive_attempt;
+        adaptive_attempt.quiz = quiz_key;
+        adaptive_attempt.student = ctx.accounts.payer.key();
+        adaptive_attempt.steps_answered = 0;
+        adaptive_attempt.pending = true;
+        adaptive_attempt.finished = false;
+        adaptive_attempt.bump = ctx.bumps.adaptive_attempt;
+
+        let args = vec![
+            // mxe: Mxe - 1 arg
+            Argument::PlaintextU128(mxe_nonce),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitAdaptiveAttemptCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.adaptive_attempt.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_adaptive_attempt")]
+    pub fn init_adaptive_attempt_callback(
+        ctx: Context<InitAdaptiveAttemptCallback>,
+        output: ComputationOutputs<InitAdaptiveAttemptOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(InitAdaptiveAttemptOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let adaptive_attempt = &mut ctx.accounts.adaptive_attempt;
+        adaptive_attempt.encrypted_state = result.field_0.ciphertexts;
+        adaptive_attempt.state_nonce = result.field_0.nonce;
+        adaptive_attempt.pending = false;
+
+        emit!(AdaptiveQuestionRevealed {
+            quiz: adaptive_attempt.quiz,
+            student: adaptive_attempt.student,
+            step: 0,
+            encryption_key: result.field_1.encryption_key,
+            nonce: result.field_1.nonce,
+            next_question: result.field_1.ciphertexts[0],
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Answer the current adaptive question
+    ///
+    /// The circuit grades the answer against the question the encrypted tier
+    /// pointed at, updates the running score and tier, and returns only the next
+    /// question index. One answer may be in flight at a time.
+    ///
+    /// Circuit parameters → Instruction arguments mapping:
+    /// - `answer: Enc<Shared, u32>` → 3 args: ArcisPubkey + PlaintextU128 + EncryptedU32
+    /// - `state: Enc<Mxe, AdaptiveState>` → 2 args: PlaintextU128 + Account
+    /// - `adaptive_keys: Enc<Mxe, AdaptiveKeys>` → 2 args: PlaintextU128 + Account
+    /// - `step: u8` → 1 arg: PlaintextU8
+    /// - `student: Shared` → 2 args: ArcisPubkey + PlaintextU128
+    pub fn answer_adaptive_question(
+        ctx: Context<AnswerAdaptiveQuestion>,
+        computation_offset: u64,
+        ciphertext_answer: [u8; 32],
+        student_pub_key: [u8; 32],
+        answer_nonce: u128,
+        result_nonce: u128,       // Nonce for the returned question index
+    ) -> Result<()> {
+        require!(ctx.accounts.quiz.is_active, ErrorCode::SurveyInactive);
+
+        let adaptive_attempt = &mut ctx.accounts.adaptive_attempt;
+        require!(!adaptive_attempt.pending, ErrorCode::AdaptiveStepPending);
+        require!(
+            (adaptive_attempt.steps_answered as usize) < ADAPTIVE_STEPS,
+            ErrorCode::AdaptiveAttemptComplete
+        );
+        adaptive_attempt.pending = true;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let adaptive_attempt = &ctx.accounts.adaptive_attempt;
+        let adaptive_bank = &ctx.accounts.adaptive_bank;
+        let args = vec![
+            // answer: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(answer_nonce),
+            Argument::EncryptedU32(ciphertext_answer),
+            // state: Enc<Mxe, AdaptiveState> - 2 args
+            Argument::PlaintextU128(adaptive_attempt.state_nonce),
+            Argument::Account(
+                adaptive_attempt.key(),
+                AdaptiveAttempt::ENCRYPTED_STATE_OFFSET,
+                (32 * ADAPTIVE_STATE_CIPHERTEXTS) as u32,
+            ),
+            // adaptive_keys: Enc<Mxe, AdaptiveKeys> - 2 args
+            Argument::PlaintextU128(adaptive_bank.adaptive_keys_nonce),
+            Argument::Account(
+                adaptive_bank.key(),
+                AdaptiveBankStorage::ENCRYPTED_ADAPTIVE_KEYS_OFFSET,
+                (32 * ADAPTIVE_BANK_SIZE) as u32,
+            ),
+            // step: u8 - 1 arg
+            Argument::PlaintextU8(adaptive_attempt.steps_answered),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(result_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AdaptiveStepCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.adaptive_attempt.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "adaptive_step")]
+    pub fn adaptive_step_callback(
+        ctx: Context<AdaptiveStepCallback>,
+        output: ComputationOutputs<AdaptiveStepOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(AdaptiveStepOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let adaptive_attempt = &mut ctx.accounts.adaptive_attempt;
+        adaptive_attempt.encrypted_state = result.field_0.ciphertexts;
+        adaptive_attempt.state_nonce = result.field_0.nonce;
+        adaptive_attempt.steps_answered += 1;
+        adaptive_attempt.pending = false;
+
+        emit!(AdaptiveQuestionRevealed {
+            quiz: adaptive_attempt.quiz,
+            student: adaptive_attempt.student,
+            step: adaptive_attempt.steps_answered,
+            encryption_key: result.field_1.encryption_key,
+            nonce: result.field_1.nonce,
+            next_question: result.field_1.ciphertexts[0],
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal the placement (score, final tier, correct answers)
+    ///
+    /// Encrypted separately to the student and the quiz creator.
+    ///
+    /// Circuit signature: reveal_adaptive_result(state: Enc<Mxe, AdaptiveState>,
+    ///                    student: Shared, quiz_creator: Shared)
+    ///                    -> (Enc<Shared, AdaptiveResult>, Enc<Shared, AdaptiveResult>)
+    pub fn finish_adaptive_attempt(
+        ctx: Context<FinishAdaptiveAttempt>,
+        computation_offset: u64,
+        student_pub_key: [u8; 32],
+        student_nonce: u128,
+    ) -> Result<()> {
+        let adaptive_attempt = &ctx.accounts.adaptive_attempt;
+        require!(
+            !adaptive_attempt.pending
+                && adaptive_attempt.steps_answered as usize == ADAPTIVE_STEPS,
+            ErrorCode::AdaptiveAttemptIncomplete
+        );
+        require!(!adaptive_attempt.finished, ErrorCode::AlreadyGraded);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let args = vec![
+            // state: Enc<Mxe, AdaptiveState> - 2 args
+            Argument::PlaintextU128(adaptive_attempt.state_nonce),
+            Argument::Account(
+                adaptive_attempt.key(),
+                AdaptiveAttempt::ENCRYPTED_STATE_OFFSET,
+                (32 * ADAPTIVE_STATE_CIPHERTEXTS) as u32,
+            ),
+            // student: Shared - 2 args
+            Argument::ArcisPubkey(student_pub_key),
+            Argument::PlaintextU128(student_nonce),
+            // quiz_creator: Shared - 2 args
+            Argument::ArcisPubkey(ctx.accounts.quiz.creator_arcium_pubkey),
+            Argument::PlaintextU128(Clock::get()?.unix_timestamp as u128 + 1),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealAdaptiveResultCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.adaptive_attempt.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_adaptive_result")]
+    pub fn reveal_adaptive_result_callback(
+        ctx: Context<RevealAdaptiveResultCallback>,
+        output: ComputationOutputs<RevealAdaptiveResultOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(RevealAdaptiveResultOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let adaptive_attempt = &mut ctx.accounts.adaptive_attempt;
+        adaptive_attempt.finished = true;
+
+        emit!(AdaptivePlacementRevealed {
+            quiz: adaptive_attempt.quiz,
+            student: adaptive_attempt.student,
+            student_result: result.field_0,
+            creator_result: result.field_1,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted tally for a poll
+    ///
+    /// Circuit signature: init_poll_tally(mxe: Mxe) -> Enc<Mxe, PollTally>
+    pub fn init_poll_tally(
+        ctx: Context<InitPollTally>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(!survey.poll_config()?.ranked_choice, ErrorCode::WrongBallotType);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let poll_tally = &mut ctx.accounts.poll_tally;
+        poll_tally.survey = survey_key;
+        poll_tally.ballots_recorded = 0;
+        poll_tally.pending_ballot = None;
+        poll_tally.results_published = false;
+        poll_tally.bump = ctx.bumps.poll_tally;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitPollTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.poll_tally.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_poll_tally")]
+    pub fn init_poll_tally_callback(
+        ctx: Context<InitPollTallyCallback>,
+        output: ComputationOutputs<InitPollTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(InitPollTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let poll_tally = &mut ctx.accounts.poll_tally;
+        poll_tally.encrypted_tally = tally.ciphertexts;
+        poll_tally.tally_nonce = tally.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Cast an encrypted ballot (an option index) in a poll
+    ///
+    /// One ballot per wallet, enforced by the ballot PDA. Polls with a voter list
+    /// take the voter's index and Merkle proof. Ballots are counted one at a time
+    /// so none can be lost to a stale tally; the voter gets an encrypted receipt
+    /// (`BallotRecorded`) once theirs is in.
+    ///
+    /// Circuit parameters → Instruction arguments mapping:
+    /// - `ballot: Enc<Shared, u32>` → 3 args: ArcisPubkey + PlaintextU128 + EncryptedU32
+    /// - `tally: Enc<Mxe, PollTally>` → 2 args: PlaintextU128 + Account
+    /// - `option_count: u8` → 1 arg: PlaintextU8
+    /// - `voter: Shared` → 2 args: ArcisPubkey + PlaintextU128
+    pub fn cast_poll_vote(
+        ctx: Context<CastPollVote>,
+        computation_offset: u64,
+        ciphertext_ballot: [u8; 32],
+        voter_pub_key: [u8; 32],
+        ballot_nonce: u128,
+        receipt_nonce: u128,
+        voter_index: u32,                      // Ignored without a voter list
+        eligibility_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+        let survey = &ctx.accounts.survey;
+        let poll = survey.poll_config()?;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(survey.is_active, ErrorCode::SurveyInactive);
+        require!(!poll.ranked_choice, ErrorCode::WrongBallotType);
+        let voter = ctx.accounts.payer.key();
+        poll.check_can_vote(now, &voter, voter_index, &eligibility_proof)?;
+
+        let poll_tally = &ctx.accounts.poll_tally;
+        require!(
+            !poll_lock_held(poll_tally.pending_ballot, poll_tally.pending_since, now),
+            ErrorCode::PollTallyBusy
+        );
+        require!(
+            poll_tally.ballots_recorded < survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+
+        ctx.accounts.ballot.queue(
+            survey.key(),
+            voter,
+            PollBallot::commitment(&voter_pub_key, ballot_nonce, &ciphertext_ballot),
+            now,
+            ctx.bumps.ballot,
+        )?;
+
+        let ballot_key = ctx.accounts.ballot.key();
+        let poll_tally = &mut ctx.accounts.poll_tally;
+        poll_tally.pending_ballot = Some(ballot_key);
+        poll_tally.pending_since = now;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let poll_tally = &ctx.accounts.poll_tally;
+        let args = vec![
+            // ballot: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(voter_pub_key),
+            Argument::PlaintextU128(ballot_nonce),
+            Argument::EncryptedU32(ciphertext_ballot),
+            // tally: Enc<Mxe, PollTally> - 2 args
+            Argument::PlaintextU128(poll_tally.tally_nonce),
+            Argument::Account(
+                poll_tally.key(),
+                PollTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * POLL_TALLY_CIPHERTEXTS) as u32,
+            ),
+            // option_count: u8 - 1 arg
+            Argument::PlaintextU8(poll.option_count),
+            // voter: Shared - 2 args
+            Argument::ArcisPubkey(voter_pub_key),
+            Argument::PlaintextU128(receipt_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CastPollVoteCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.poll_tally.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ballot_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_poll_vote")]
+    pub fn cast_poll_vote_callback(
+        ctx: Context<CastPollVoteCallback>,
+        output: ComputationOutputs<CastPollVoteOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(CastPollVoteOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old tally
+        let ballot_key = ctx.accounts.ballot.key();
+        let poll_tally = &mut ctx.accounts.poll_tally;
+        require!(
+            poll_tally.pending_ballot == Some(ballot_key),
+            ErrorCode::StaleBallotComputation
+        );
+
+        poll_tally.encrypted_tally = result.field_0.ciphertexts;
+        poll_tally.tally_nonce = result.field_0.nonce;
+        poll_tally.ballots_recorded = poll_tally.ballots_recorded.saturating_add(1);
+        poll_tally.pending_ballot = None;
+
+        let ballot = &mut ctx.accounts.ballot;
+        ballot.status = BallotStatus::Recorded;
+
+        emit!(BallotRecorded {
+            survey: ballot.survey,
+            voter: ballot.voter,
+            ballot_commitment: ballot.ballot_commitment,
+            ballots_recorded: poll_tally.ballots_recorded,
+            encryption_key: result.field_1.encryption_key,
+            nonce: result.field_1.nonce,
+            receipt: result.field_1.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Publish the plaintext tally of a closed poll (anyone may call)
+    ///
+    /// Circuit signature: reveal_poll_tally(tally: Enc<Mxe, PollTally>) -> (u32, [u32; 8])
+    pub fn publish_poll_results(
+        ctx: Context<PublishPollResults>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let poll = ctx.accounts.survey.poll_config()?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= poll.closes_at, ErrorCode::PollStillOpen);
+
+        let poll_tally = &mut ctx.accounts.poll_tally;
+        require!(!poll_tally.results_published, ErrorCode::PollResultsPublished);
+        require!(
+            !poll_lock_held(poll_tally.pending_ballot, poll_tally.pending_since, now),
+            ErrorCode::PollTallyBusy
+        );
+        // Anything still pending has timed out; its output must not land after this
+        poll_tally.pending_ballot = None;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let poll_tally = &ctx.accounts.poll_tally;
+        let args = vec![
+            // tally: Enc<Mxe, PollTally> - 2 args
+            Argument::PlaintextU128(poll_tally.tally_nonce),
+            Argument::Account(
+                poll_tally.key(),
+                PollTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * POLL_TALLY_CIPHERTEXTS) as u32,
+            ),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealPollTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.poll_tally.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_poll_tally")]
+    pub fn reveal_poll_tally_callback(
+        ctx: Context<RevealPollTallyCallback>,
+        output: ComputationOutputs<RevealPollTallyOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(RevealPollTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let poll_tally = &mut ctx.accounts.poll_tally;
+        poll_tally.results_published = true;
+        poll_tally.total_votes = result.field_0;
+        poll_tally.final_counts = result.field_1;
+
+        emit!(PollResultsPublished {
+            survey: poll_tally.survey,
+            ballots_recorded: poll_tally.ballots_recorded,
+            total_votes: poll_tally.total_votes,
+            counts: poll_tally.final_counts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted ranked-choice tally for a `ranked_choice` poll
+    ///
+    /// `public_viewer_pubkey` is the x25519 key results are encrypted to; publish
+    /// its secret half alongside the poll so anyone can read the outcome.
+    ///
+    /// Circuit signature: init_ranked_tally(mxe: Mxe) -> Enc<Mxe, RankedTally>
+    pub fn init_ranked_tally(
+        ctx: Context<InitRankedTally>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+        public_viewer_pubkey: [u8; 32],
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(survey.poll_config()?.ranked_choice, ErrorCode::WrongBallotType);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let ranked_tally = &mut ctx.accounts.ranked_tally;
+        ranked_tally.survey = survey_key;
+        ranked_tally.public_viewer_pubkey = public_viewer_pubkey;
+        ranked_tally.ballots_recorded = 0;
+        ranked_tally.pending_ballot = None;
+        ranked_tally.results_published = false;
+        ranked_tally.bump = ctx.bumps.ranked_tally;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitRankedTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.ranked_tally.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_ranked_tally")]
+    pub fn init_ranked_tally_callback(
+        ctx: Context<InitRankedTallyCallback>,
+        output: ComputationOutputs<InitRankedTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(InitRankedTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let ranked_tally = &mut ctx.accounts.ranked_tally;
+        ranked_tally.encrypted_tally = tally.ciphertexts;
+        ranked_tally.tally_nonce = tally.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Cast an encrypted ranked ballot
+    ///
+    /// The ballot is the voter's preference order packed 4 bits per rank, first
+    /// choice in the low nibble and 0xF for unranked places (e.g. [2, 0] -> 0xFF02).
+    /// Eligibility, one-ballot-per-wallet and receipts work as in `cast_poll_vote`.
+    ///
+    /// Circuit parameters → Instruction arguments mapping:
+    /// - `ballot: Enc<Shared, u32>` → 3 args: ArcisPubkey + PlaintextU128 + EncryptedU32
+    /// - `tally: Enc<Mxe, RankedTally>` → 2 args: PlaintextU128 + Account
+    /// - `option_count: u8` → 1 arg: PlaintextU8
+    /// - `voter: Shared` → 2 args: ArcisPubkey + PlaintextU128
+    pub fn cast_ranked_ballot(
+        ctx: Context<CastRankedBallot>,
+        computation_offset: u64,
+        ciphertext_ranking: [u8; 32],
+        voter_pub_key: [u8; 32],
+        ballot_nonce: u128,
+        receipt_nonce: u128,
+        voter_index: u32,                      // Ignored without a voter list
+        eligibility_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+        let survey = &ctx.accounts.survey;
+        let poll = survey.poll_config()?;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(survey.is_active, ErrorCode::SurveyInactive);
+        require!(poll.ranked_choice, ErrorCode::WrongBallotType);
+        let voter = ctx.accounts.payer.key();
+        poll.check_can_vote(now, &voter, voter_index, &eligibility_proof)?;
+
+        let ranked_tally = &ctx.accounts.ranked_tally;
+        require!(
+            !poll_lock_held(ranked_tally.pending_ballot, ranked_tally.pending_since, now),
+            ErrorCode::PollTallyBusy
+        );
+        require!(
+            ranked_tally.ballots_recorded < survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+
+        ctx.accounts.ballot.queue(
+            survey.key(),
+            voter,
+            PollBallot::commitment(&voter_pub_key, ballot_nonce, &ciphertext_ranking),
+            now,
+            ctx.bumps.ballot,
+        )?;
+
+        let ballot_key = ctx.accounts.ballot.key();
+        let ranked_tally = &mut ctx.accounts.ranked_tally;
+        ranked_tally.pending_ballot = Some(ballot_key);
+        ranked_tally.pending_since = now;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let ranked_tally = &ctx.accounts.ranked_tally;
+        let args = vec![
+            // ballot: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(voter_pub_key),
+            Argument::PlaintextU128(ballot_nonce),
+            Argument::EncryptedU32(ciphertext_ranking),
+            // tally: Enc<Mxe, RankedTally> - 2 args
+            Argument::PlaintextU128(ranked_tally.tally_nonce),
+            Argument::Account(
+                ranked_tally.key(),
+                RankedTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * IRV_PROFILES) as u32,
+            ),
+            // option_count: u8 - 1 arg
+            Argument::PlaintextU8(poll.option_count),
+            // voter: Shared - 2 args
+            Argument::ArcisPubkey(voter_pub_key),
+            Argument::PlaintextU128(receipt_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CastRankedBallotCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.ranked_tally.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ballot_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_ranked_ballot")]
+    pub fn cast_ranked_ballot_callback(
+        ctx: Context<CastRankedBallotCallback>,
+        output: ComputationOutputs<CastRankedBallotOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(CastRankedBallotOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let ballot_key = ctx.accounts.ballot.key();
+        let ranked_tally = &mut ctx.accounts.ranked_tally;
+        require!(
+            ranked_tally.pending_ballot == Some(ballot_key),
+            ErrorCode::StaleBallotComputation
+        );
+
+        ranked_tally.encrypted_tally = result.field_0.ciphertexts;
+        ranked_tally.tally_nonce = result.field_0.nonce;
+        ranked_tally.ballots_recorded = ranked_tally.ballots_recorded.saturating_add(1);
+        ranked_tally.pending_ballot = None;
+
+        let ballot = &mut ctx.accounts.ballot;
+        ballot.status = BallotStatus::Recorded;
+
+        emit!(BallotRecorded {
+            survey: ballot.survey,
+            voter: ballot.voter,
+            ballot_commitment: ballot.ballot_commitment,
+            ballots_recorded: ranked_tally.ballots_recorded,
+            encryption_key: result.field_1.encryption_key,
+            nonce: result.field_1.nonce,
+            receipt: result.field_1.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Run instant-runoff on a closed ranked poll (anyone may call)
+    ///
+    /// Only the winner and each round's elimination leave MPC, encrypted to the
+    /// poll's public viewer key; per-round vote counts stay secret.
+    ///
+    /// Circuit signature: run_instant_runoff(tally: Enc<Mxe, RankedTally>, option_count: u8,
+    ///                    public_viewer: Shared) -> Enc<Shared, IrvResult>
+    pub fn publish_ranked_poll_results(
+        ctx: Context<PublishRankedPollResults>,
+        computation_offset: u64,
+        viewer_nonce: u128,
+    ) -> Result<()> {
+        let poll = ctx.accounts.survey.poll_config()?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= poll.closes_at, ErrorCode::PollStillOpen);
+
+        let ranked_tally = &mut ctx.accounts.ranked_tally;
+        require!(!ranked_tally.results_published, ErrorCode::PollResultsPublished);
+        require!(
+            !poll_lock_held(ranked_tally.pending_ballot, ranked_tally.pending_since, now),
+            ErrorCode::PollTallyBusy
+        );
+        // Anything still pending has timed out; its output must not land after this
+        ranked_tally.pending_ballot = None;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let ranked_tally = &ctx.accounts.ranked_tally;
+        let args = vec![
+            // tally: Enc<Mxe, RankedTally> - 2 args
+            Argument::PlaintextU128(ranked_tally.tally_nonce),
+            Argument::Account(
+                ranked_tally.key(),
+                RankedTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * IRV_PROFILES) as u32,
+            ),
+            // option_count: u8 - 1 arg
+            Argument::PlaintextU8(poll.option_count),
+            // public_viewer: Shared - 2 args
+            Argument::ArcisPubkey(ranked_tally.public_viewer_pubkey),
+            Argument::PlaintextU128(viewer_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RunInstantRunoffCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.ranked_tally.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "run_instant_runoff")]
+    pub fn run_instant_runoff_callback(
+        ctx: Context<RunInstantRunoffCallback>,
+        output: ComputationOutputs<RunInstantRunoffOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(RunInstantRunoffOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let ranked_tally = &mut ctx.accounts.ranked_tally;
+        ranked_tally.results_published = true;
+
+        emit!(RankedPollResultsPublished {
+            survey: ranked_tally.survey,
+            ballots_recorded: ranked_tally.ballots_recorded,
+            encryption_key: result.encryption_key,
+            nonce: result.nonce,
+            ciphertexts: result.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted answer counter for a TextInput question
+    ///
+    /// Circuit signature: init_text_tally(mxe: Mxe) -> Enc<Mxe, TextTally>
+    pub fn init_text_tally(
+        ctx: Context<InitTextTally>,
+        computation_offset: u64,
+        question_id: u32,
+        min_responses: u32,
+        mxe_nonce: u128,
+        committed_question: Option<CommittedQuestion>,  // Only for surveys with committed content
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            min_responses >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+
+        let question = survey.resolve_question(question_id, committed_question)?;
+        require!(
+            matches!(question.question_type, QuestionType::TextInput),
+            ErrorCode::InvalidTextQuestion
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.survey = survey_key;
+        tally_storage.question_id = question_id;
+        tally_storage.min_responses = min_responses;
+        tally_storage.submissions = 0;
+        tally_storage.bump = ctx.bumps.tally_storage;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitTextTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        msg!("Text tally init queued for question {}", question_id);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_text_tally")]
+    pub fn init_text_tally_callback(
+        ctx: Context<InitTextTallyCallback>,
+        output: ComputationOutputs<InitTextTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(InitTextTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.encrypted_tally = tally.ciphertexts;
+        tally_storage.tally_nonce = tally.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Submit an encrypted free-text answer
+    ///
+    /// The text is encrypted to `Survey.creator_arcium_pubkey`, never to the MXE, and
+    /// stored inline or off-chain behind its hash. Separately, the respondent encrypts
+    /// the trimmed byte length to the MXE (0 if blank, NOT_ANSWERED if skipped) so
+    /// MPC can count answers. Blank answers should be submitted too, padded like any
+    /// other, so the chain can't tell them apart.
+    /// One answer per wallet (TallyReceipt), folded in one at a time (TallyLock).
+    ///
+    /// Circuit signature: accumulate_text_response(length: Enc<Shared, u32>, tally: Enc<Mxe, TextTally>)
+    /// - `length` → ArcisPubkey + PlaintextU128 + EncryptedU32
+    /// - `tally` → PlaintextU128 (stored nonce) + Account (stored ciphertexts)
+    pub fn submit_text_response(
+        ctx: Context<SubmitTextResponse>,
+        computation_offset: u64,
+        respondent_pub_key: [u8; 32],
+        nonce: u128,                  // Nonce of the creator-encrypted text
+        body: TextResponseBody,
+        encrypted_length: [u8; 32],
+        length_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.tally_storage.submissions < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        let receipt_key = ctx.accounts.receipt.key();
+        ctx.accounts.tally_storage.lock.acquire(receipt_key, now)?;
+        let tally_storage_key = ctx.accounts.tally_storage.key();
+        let respondent = ctx.accounts.payer.key();
+        ctx.accounts.receipt.queue(tally_storage_key, respondent, now, ctx.bumps.receipt)?;
+
+        let (ciphertext_hash, content_uri, ciphertexts) = match body {
+            TextResponseBody::Inline { ciphertexts } => {
+                require!(
+                    !ciphertexts.is_empty()
+                        && ciphertexts.len() <= MAX_INLINE_TEXT_CIPHERTEXTS
+                        && ciphertexts.len() % TEXT_PAD_BLOCKS == 0,
+                    ErrorCode::InvalidTextResponse
+                );
+                (TextResponse::ciphertext_hash(&ciphertexts), String::new(), ciphertexts)
+            }
+            TextResponseBody::OffChain { content_uri, ciphertext_hash } => {
+                validate_content_uri(&content_uri)?;
+                require!(ciphertext_hash != [0u8; 32], ErrorCode::InvalidTextResponse);
+                (ciphertext_hash, content_uri, Vec::new())
+            }
+        };
+
+        let survey_key = ctx.accounts.survey.key();
+        let question_id = ctx.accounts.tally_storage.question_id;
+        let text_response = &mut ctx.accounts.text_response;
+        text_response.survey = survey_key;
+        text_response.respondent = ctx.accounts.payer.key();
+        text_response.question_id = question_id;
+        text_response.respondent_pub_key = respondent_pub_key;
+        text_response.nonce = nonce;
+        text_response.ciphertext_hash = ciphertext_hash;
+        text_response.content_uri = content_uri;
+        text_response.ciphertexts = ciphertexts;
+        text_response.submitted_at = now;
+        text_response.bump = ctx.bumps.text_response;
+
+        emit!(TextResponseSubmitted {
+            survey: survey_key,
+            question_id,
+            respondent: text_response.respondent,
+            ciphertext_hash,
+        });
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let tally_storage = &ctx.accounts.tally_storage;
+        let args = vec![
+            // length: Enc<Shared, u32> - 3 args
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(length_nonce),
+            Argument::EncryptedU32(encrypted_length),
+            // tally: Enc<Mxe, TextTally> - 2 args
+            Argument::PlaintextU128(tally_storage.tally_nonce),
+            Argument::Account(
+                tally_storage.key(),
+                TextTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * TEXT_TALLY_CIPHERTEXTS) as u32,
+            ),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AccumulateTextResponseCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: receipt_key,
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "accumulate_text_response")]
+    pub fn accumulate_text_response_callback(
+        ctx: Context<AccumulateTextResponseCallback>,
+        output: ComputationOutputs<AccumulateTextResponseOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(AccumulateTextResponseOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old tally
+        let receipt_key = ctx.accounts.receipt.key();
+        let tally_storage = &mut ctx.accounts.tally_storage;
+        tally_storage.lock.release(receipt_key)?;
+        tally_storage.encrypted_tally = tally.ciphertexts;
+        tally_storage.tally_nonce = tally.nonce;
+        tally_storage.submissions = tally_storage.submissions.saturating_add(1);
+        ctx.accounts.receipt.status = BallotStatus::Recorded;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal how many text answers came in and how many were non-empty
+    ///
+    /// Circuit signature: reveal_text_stats(tally: Enc<Mxe, TextTally>, min_responses: u32, creator: Shared)
+    pub fn reveal_text_stats(
+        ctx: Context<RevealTextStats>,
+        computation_offset: u64,
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let tally_storage = &ctx.accounts.tally_storage;
+        let args = vec![
+            // tally: Enc<Mxe, TextTally> - 2 args
+            Argument::PlaintextU128(tally_storage.tally_nonce),
+            Argument::Account(
+                tally_storage.key(),
+                TextTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * TEXT_TALLY_CIPHERTEXTS) as u32,
+            ),
+            Argument::PlaintextU32(tally_storage.min_responses),
+            // creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealTextStatsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_storage.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_text_stats")]
+    pub fn reveal_text_stats_callback(
+        ctx: Context<RevealTextStatsCallback>,
+        output: ComputationOutputs<RevealTextStatsOutput>,
+    ) -> Result<()> {
+        let report = match output {
+            ComputationOutputs::Success(RevealTextStatsOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(TextStatsRevealed {
+            survey: ctx.accounts.tally_storage.survey,
+            question_id: ctx.accounts.tally_storage.question_id,
+            encryption_key: report.encryption_key,
+            nonce: report.nonce,
+            ciphertexts: report.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the encrypted keyword counters for a TextInput question
+    ///
+    /// The creator publishes the vocabulary (one keyword per line, id = line number)
+    /// and commits to its sha256 here so every client tokenizes against the same list.
+    ///
+    /// Circuit signature: init_keyword_tally(mxe: Mxe) -> Enc<Mxe, KeywordTally>
+    pub fn init_keyword_tally(
+        ctx: Context<InitKeywordTally>,
+        computation_offset: u64,
+        question_id: u32,
+        vocabulary_size: u8,
+        vocabulary_hash: [u8; 32],
+        min_responses: u32,
+        mxe_nonce: u128,
+        committed_question: Option<CommittedQuestion>,  // Only for surveys with committed content
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            vocabulary_size > 0
+                && vocabulary_size as usize <= MAX_KEYWORD_VOCABULARY
+                && vocabulary_hash != [0u8; 32],
+            ErrorCode::InvalidKeywordVocabulary
+        );
+        require!(
+            min_responses >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+
+        let question = survey.resolve_question(question_id, committed_question)?;
+        require!(
+            matches!(question.question_type, QuestionType::TextInput),
+            ErrorCode::InvalidTextQuestion
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let keyword_tally = &mut ctx.accounts.keyword_tally;
+        keyword_tally.survey = survey_key;
+        keyword_tally.question_id = question_id;
+        keyword_tally.vocabulary_size = vocabulary_size;
+        keyword_tally.vocabulary_hash = vocabulary_hash;
+        keyword_tally.min_responses = min_responses;
+        keyword_tally.submissions = 0;
+        keyword_tally.bump = ctx.bumps.keyword_tally;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitKeywordTallyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.keyword_tally.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        msg!("Keyword tally init queued for question {}", question_id);
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_keyword_tally")]
+    pub fn init_keyword_tally_callback(
+        ctx: Context<InitKeywordTallyCallback>,
+        output: ComputationOutputs<InitKeywordTallyOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(InitKeywordTallyOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let keyword_tally = &mut ctx.accounts.keyword_tally;
+        keyword_tally.encrypted_tally = tally.ciphertexts;
+        keyword_tally.tally_nonce = tally.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Count the keywords of the payer's text answer
+    ///
+    /// The client maps the answer onto the vocabulary and encrypts up to
+    /// MAX_ANSWER_KEYWORDS token ids to the MXE with the answer's respondent key.
+    /// Only the counts are kept; nobody learns which answer mentioned what.
+    /// One answer per wallet (TallyReceipt), folded in one at a time (TallyLock).
+    ///
+    /// Circuit signature: accumulate_keywords(tokens: Enc<Shared, [u32; 8]>, vocabulary_size: u8, tally: Enc<Mxe, KeywordTally>)
+    /// - `tokens` → ArcisPubkey + PlaintextU128 + MAX_ANSWER_KEYWORDS × EncryptedU32
+    pub fn submit_text_keywords(
+        ctx: Context<SubmitTextKeywords>,
+        computation_offset: u64,
+        encrypted_keywords: [[u8; 32]; MAX_ANSWER_KEYWORDS],
+        keywords_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.survey.is_active, ErrorCode::SurveyInactive);
+        ctx.accounts.survey.route_direct(&ctx.accounts.app_preferences)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.keyword_tally.submissions < ctx.accounts.survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+        let receipt_key = ctx.accounts.receipt.key();
+        ctx.accounts.keyword_tally.lock.acquire(receipt_key, now)?;
+        let keyword_tally_key = ctx.accounts.keyword_tally.key();
+        let respondent = ctx.accounts.payer.key();
+        ctx.accounts.receipt.queue(keyword_tally_key, respondent, now, ctx.bumps.receipt)?;
+
+        let text_response = &ctx.accounts.text_response;
+        require!(!text_response.keywords_submitted, ErrorCode::KeywordsAlreadySubmitted);
+        let respondent_pub_key = text_response.respondent_pub_key;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let keyword_tally = &ctx.accounts.keyword_tally;
+        let mut args = vec![
+            Argument::ArcisPubkey(respondent_pub_key),
+            Argument::PlaintextU128(keywords_nonce),
+        ];
+        args.extend(encrypted_keywords.iter().map(|keyword| Argument::EncryptedU32(*keyword)));
+        args.push(Argument::PlaintextU8(keyword_tally.vocabulary_size));
+        args.push(Argument::PlaintextU128(keyword_tally.tally_nonce));
+        args.push(Argument::Account(
+            keyword_tally.key(),
+            KeywordTallyStorage::ENCRYPTED_TALLY_OFFSET,
+            (32 * KEYWORD_TALLY_CIPHERTEXTS) as u32,
+        ));
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AccumulateKeywordsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.keyword_tally.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: receipt_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.text_response.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "accumulate_keywords")]
+    pub fn accumulate_keywords_callback(
+        ctx: Context<AccumulateKeywordsCallback>,
+        output: ComputationOutputs<AccumulateKeywordsOutput>,
+    ) -> Result<()> {
+        let tally = match output {
+            ComputationOutputs::Success(AccumulateKeywordsOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A computation that outlived its lock was computed over an old tally
+        let receipt_key = ctx.accounts.receipt.key();
+        let keyword_tally = &mut ctx.accounts.keyword_tally;
+        keyword_tally.lock.release(receipt_key)?;
+        keyword_tally.encrypted_tally = tally.ciphertexts;
+        keyword_tally.tally_nonce = tally.nonce;
+        keyword_tally.submissions = keyword_tally.submissions.saturating_add(1);
+        ctx.accounts.receipt.status = BallotStatus::Recorded;
+        ctx.accounts.text_response.keywords_submitted = true;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Reveal the most mentioned keywords to the creator
+    ///
+    /// Circuit signature: reveal_keyword_table(tally: Enc<Mxe, KeywordTally>, vocabulary_size: u8,
+    ///                    min_responses: u32, creator: Shared) -> Enc<Shared, KeywordTable>
+    pub fn reveal_keyword_table(
+        ctx: Context<RevealKeywordTable>,
+        computation_offset: u64,
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let keyword_tally = &ctx.accounts.keyword_tally;
+        let args = vec![
+            // tally: Enc<Mxe, KeywordTally> - 2 args
+            Argument::PlaintextU128(keyword_tally.tally_nonce),
+            Argument::Account(
+                keyword_tally.key(),
+                KeywordTallyStorage::ENCRYPTED_TALLY_OFFSET,
+                (32 * KEYWORD_TALLY_CIPHERTEXTS) as u32,
+            ),
+            Argument::PlaintextU8(keyword_tally.vocabulary_size),
+            Argument::PlaintextU32(keyword_tally.min_responses),
+            // creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealKeywordTableCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.keyword_tally.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_keyword_table")]
+    pub fn reveal_keyword_table_callback(
+        ctx: Context<RevealKeywordTableCallback>,
+        output: ComputationOutputs<RevealKeywordTableOutput>,
+    ) -> Result<()> {
+        let table = match output {
+            ComputationOutputs::Success(RevealKeywordTableOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let keyword_tally = &ctx.accounts.keyword_tally;
+        emit!(KeywordTableRevealed {
+            survey: keyword_tally.survey,
+            question_id: keyword_tally.question_id,
+            vocabulary_hash: keyword_tally.vocabulary_hash,
+            encryption_key: table.encryption_key,
+            nonce: table.nonce,
+            ciphertexts: table.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Create the response buffer of a Batch survey
+    ///
+    /// Circuit signature: init_batch_totals(mxe: Mxe) -> Enc<Mxe, BatchTotals>
+    pub fn init_response_batch(
+        ctx: Context<InitResponseBatch>,
+        computation_offset: u64,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        let survey = &ctx.accounts.survey;
+        require!(
+            survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        survey
+            .computation_route(&ctx.accounts.app_preferences)?
+            .require_provisioning(DataProvisioningMethod::Batch)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let survey_key = ctx.accounts.survey.key();
+        let response_batch = &mut ctx.accounts.response_batch;
+        response_batch.survey = survey_key;
+        response_batch.buffered = 0;
+        response_batch.processing = false;
+        response_batch.batches_processed = 0;
+        response_batch.processed_responses = 0;
+        response_batch.batches_queued = 0;
+        response_batch.last_revealed_responses = 0;
+        response_batch.bump = ctx.bumps.response_batch;
+
+        let args = vec![Argument::PlaintextU128(mxe_nonce)];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitBatchTotalsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.response_batch.key(),
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_batch_totals")]
+    pub fn init_batch_totals_callback(
+        ctx: Context<InitBatchTotalsCallback>,
+        output: ComputationOutputs<InitBatchTotalsOutput>,
+    ) -> Result<()> {
+        let totals = match output {
+            ComputationOutputs::Success(InitBatchTotalsOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let response_batch = &mut ctx.accounts.response_batch;
+        response_batch.encrypted_totals = totals.ciphertexts;
+        response_batch.totals_nonce = totals.nonce;
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Buffer one encrypted response to a Batch survey (no MPC until the batch runs)
+    ///
+    /// Same encrypted [answer1, answer2, question_type1, question_type2, total_responses,
+    /// completion_rate] array as `submit_survey_analytics`, under one key and nonce.
+    pub fn buffer_survey_response(
+        ctx: Context<BufferSurveyResponse>,
+        encrypted_response: [[u8; 32]; BATCH_RESPONSE_CIPHERTEXTS],
+        user_pub_key: [u8; 32],
+        user_nonce: u128,
+    ) -> Result<()> {
+        let survey = &mut ctx.accounts.survey;
+        require!(survey.is_active, ErrorCode::SurveyInactive);
+        let route = survey.computation_route(&ctx.accounts.app_preferences)?;
+        route.require_provisioning(DataProvisioningMethod::Batch)?;
+        require!(
+            survey.current_responses < survey.max_responses,
+            ErrorCode::SurveyFull
+        );
+
+        ctx.accounts.response_batch.buffer_response(
+            user_pub_key,
+            user_nonce,
+            encrypted_response,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        survey.pin_data_provisioning(&route);
+        survey.current_responses = survey.current_responses.checked_add(1)
+            .ok_or(ErrorCode::IntegerOverflow)?;
+
+        emit!(ResponseSubmitted {
+            survey: survey.key(),
+        });
+
+        Ok(())
+    }
+
+    /// ✅ NEW: Run one MPC computation over every buffered response (anyone may call)
+    ///
+    /// Empty slots are padded with a copy of slot 0 and ignored by the circuit. If the
+    /// computation is lost the batch can be queued again after BATCH_LOCK_TIMEOUT_SECONDS;
+    /// only the output of the latest queue is applied.
+    ///
+    /// Circuit parameters → Instruction arguments mapping:
+    /// - `response0..response3: Enc<Shared, [u32; 6]>` → 3 args each: ArcisPubkey + PlaintextU128 + Account
+    /// - `batch_size: u8` → 1 arg: PlaintextU8
+    /// - `batch_number: u32` → 1 arg: PlaintextU32
+    /// - `totals: Enc<Mxe, BatchTotals>` → 2 args: PlaintextU128 + Account
+    pub fn process_response_batch(
+        ctx: Context<ProcessResponseBatch>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let route = ctx.accounts.survey.computation_route(&ctx.accounts.app_preferences)?;
+        route.require_provisioning(DataProvisioningMethod::Batch)?;
+        route.require_protocol(MPCProtocol::Cerberus)?;
+
+        ctx.accounts.response_batch.start_batch(now)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let batch_key = ctx.accounts.response_batch.key();
+        let args = ctx.accounts.response_batch.batch_args(batch_key);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProcessResponseBatchCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: batch_key,
+                    is_writable: true,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "process_response_batch")]
+    pub fn process_response_batch_callback(
+        ctx: Context<ProcessResponseBatchCallback>,
+        output: ComputationOutputs<ProcessResponseBatchOutput>,
+    ) -> Result<()> {
+        let result = match output {
+            ComputationOutputs::Success(ProcessResponseBatchOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        ctx.accounts
+            .response_batch
+            .finish_batch(result.field_0.ciphertexts, result.field_0.nonce, result.field_1)
+    }
+
+    /// ✅ NEW: Reveal averages over every processed batch to the survey creator
+    ///
+    /// Circuit signature: reveal_batch_analytics(totals: Enc<Mxe, BatchTotals>, report_decimals: u8,
+    ///                    creator: Shared) -> Enc<Shared, BatchAnalytics>
+    pub fn reveal_batch_analytics(
+        ctx: Context<RevealBatchAnalytics>,
+        computation_offset: u64,
+        creator_pub_key: [u8; 32],
+        creator_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.survey.creator == ctx.accounts.payer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.response_batch.processed_responses >= MIN_TALLY_REVEAL_SUBMISSIONS,
+            ErrorCode::InsufficientTallySubmissions
+        );
+
+        // Reveal settled totals, so last_revealed_responses is exactly what MPC reads
+        let response_batch = &mut ctx.accounts.response_batch;
+        require!(!response_batch.processing, ErrorCode::ResponseBatchBusy);
+        check_reveal_interval(
+            response_batch.processed_responses,
+            response_batch.last_revealed_responses,
+            MIN_TALLY_REVEAL_SUBMISSIONS,
+        )?;
+        response_batch.last_revealed_responses = response_batch.processed_responses;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let response_batch = &ctx.accounts.response_batch;
+        let args = vec![
+            // totals: Enc<Mxe, BatchTotals> - 2 args
+            Argument::PlaintextU128(response_batch.totals_nonce),
+            Argument::Account(
+                response_batch.key(),
+                ResponseBatchBuffer::ENCRYPTED_TOTALS_OFFSET,
+                (32 * BATCH_TOTALS_CIPHERTEXTS) as u32,
+            ),
+            // report_decimals: u8 - 1 arg
+            Argument::PlaintextU8(ctx.accounts.survey.report_decimals),
+            // creator: Shared - 2 args
+            Argument::ArcisPubkey(creator_pub_key),
+            Argument::PlaintextU128(creator_nonce),
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealBatchAnalyticsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.response_batch.key(),
+                    is_writable: false,
+                }
+            ])],
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_batch_analytics")]
+    pub fn reveal_batch_analytics_callback(
+        ctx: Context<RevealBatchAnalyticsCallback>,
+        output: ComputationOutputs<RevealBatchAnalyticsOutput>,
+    ) -> Result<()> {
+        let analytics = match output {
+            ComputationOutputs::Success(RevealBatchAnalyticsOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(BatchAnalyticsRevealed {
+            survey: ctx.accounts.response_batch.survey,
+            processed_responses: ctx.accounts.response_batch.processed_responses,
+            encryption_key: analytics.encryption_key,
+            nonce: analytics.nonce,
+            ciphertexts: analytics.ciphertexts,
+        });
+
+        Ok(())
+    }
+
+    // ✅ DASHBOARD: Create user account for personalized dashboard
+    pub fn create_user_account(
+        ctx: Context<CreateUserAccount>,
+        username: String,
+        email: String,
+    ) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+        let clock = Clock::get()?;
+        
+        // Input validation
+        require!(username.len() <= 50, ErrorCode::UsernameTooLong);
+        require!(email.len() <= 100, ErrorCode::EmailTooLong);
+        require!(username.len() >= 3, ErrorCode::UsernameTooShort);
+        
+        // Security validations
+        validate_no_xss(&username)?;
+        validate_no_sql_injection(&username)?;
+        validate_no_xss(&email)?;
+        validate_no_sql_injection(&email)?;
+        
+        user_account.creator = ctx.accounts.creator.key();
+        user_account.username = username;
+        user_account.email = email;
+        user_account.created_at = clock.unix_timestamp;
+        user_account.is_active = true;
+        user_account.last_login = clock.unix_timestamp;
+        
+        emit!(UserAccountCreated {
+            user: ctx.accounts.creator.key(),
+            username: user_account.username.clone(),
+            created_at: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    // ✅ DASHBOARD: Get user's personalized dashboard data
+    pub fn get_user_dashboard(
+        ctx: Context<GetUserDashboard>,
+    ) -> Result<UserDashboard> {
+        let user = ctx.accounts.user.key();
+        let clock = Clock::get()?;
+        
+        // Update last login
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.last_login = clock.unix_timestamp;
+        
+        // Build dashboard data (this would typically query from events/logs)
+        let dashboard = UserDashboard {
+            surveys_created: vec![], // Would be populated from survey creation events
+            responses_submitted: vec![], // Would be populated from response events
+            quiz_results: vec![], // Would be populated from quiz completion events
+            special_survey_access: vec![], // Would be populated from special survey events
+        };
+        
+        emit!(UserDashboardAccessed {
+            user,
+            accessed_at: clock.unix_timestamp,
+        });
+        
+        Ok(dashboard)
+    }
+
+
+}
+
+
+// Account structures for our survey/quiz DApp
+
+#[derive(Accounts)]
+#[instruction(timestamp: i64)]
+pub struct CreateSurveyWithTimestamp<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = Survey::INIT_SPACE,
+        seeds = [b"survey", creator.key().as_ref(), &timestamp.to_le_bytes()],
+        bump
+    )]
+    pub survey: Account<'info, Survey>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    questions: Vec<QuestionData>,
+    translations: Vec<QuestionTranslations>,
+    display_conditions: Vec<QuestionDisplayConditions>,
+    grading: Vec<QuestionGrading>,
+)]
+pub struct AddQuestions<'info> {
+    #[account(
+        mut,
+        realloc = survey.to_account_info().data_len()
+            + Survey::questions_space(&questions)
+            + Survey::questions_space(&translations)
+            + Survey::questions_space(&display_conditions)
+            + Survey::questions_space(&grading),
+        realloc::payer = creator,
+        realloc::zero = false,
+    )]
+    pub survey: Account<'info, Survey>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(scheme: ContentCommitmentScheme, content_hash: [u8; 32], content_uri: String)]
+pub struct SetQuestionContentCommitment<'info> {
+    #[account(
+        mut,
+        realloc = survey.space_with_commitment(survey.to_account_info().data_len(), &content_uri),
+        realloc::payer = creator,
+        realloc::zero = false,
+    )]
+    pub survey: Account<'info, Survey>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReportPrecision<'info> {
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetComputationOverrides<'info> {
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    pub creator: Signer<'info>,
+}
+
+// ✅ NEW: One respondent's creator-encrypted answers (once per respondent)
+#[derive(Accounts)]
+#[instruction(respondent_pub_key: [u8; 32], nonce: u128, ciphertexts: Vec<[u8; 32]>)]
+pub struct SubmitCreatorEncryptedResponse<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    // Seeded by the one-time respondent key, never the wallet
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CreatorEncryptedResponse::space(ciphertexts.len()),
+        seeds = [b"creator_response", survey.key().as_ref(), respondent_pub_key.as_ref()],
+        bump
+    )]
+    pub response: Account<'info, CreatorEncryptedResponse>,
+    // One per wallet; holds no reference to the response
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"creator_response_receipt", survey.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TallyReceipt>,
+    pub system_program: Program<'info, System>,
+}
+
+// ✅ NEW: Add one encrypted response to a Batch survey's buffer
+#[derive(Accounts)]
+pub struct BufferSurveyResponse<'info> {
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"response_batch", survey.key().as_ref()],
+        bump = response_batch.bump
+    )]
+    pub response_batch: Account<'info, ResponseBatchBuffer>,
+}
+
+#[derive(Accounts)]
+pub struct SetAnswerShuffling<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    pub creator: Signer<'info>,
+    /// CHECK: only checked for existence; see require_single_order_mode
+    #[account(
+        seeds = [b"question_pool", quiz.key().as_ref()],
+        bump
+    )]
+    pub question_pool: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetQuizGradingData<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + 32 + 32 * QUIZ_GRADING_VALUES + 16 + 32 + 1, // Discriminator + quiz + encrypted_data + nonce + pubkey + bump
+        seeds = [b"quiz_grading_data", quiz.key().as_ref()],
+        bump
+    )]
+    pub quiz_grading_data: Account<'info, QuizGradingDataStorage>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("survey_analytics", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitSurveyResponse<'info> {
+    // Removed response account - following Arcium docs: no individual response storage
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SURVEY_ANALYTICS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Separate instruction for analytics computation
+#[queue_computation_accounts("survey_analytics", payer)]
+#[derive(Accounts)]
+#[instruction(analytics_computation_offset: u64)]
+pub struct SubmitSurveyAnalytics<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(analytics_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SURVEY_ANALYTICS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    
+    // ✅ FIX: Add analytics storage account for callback
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = SurveyAnalyticsStorage::INIT_SPACE,
+        seeds = [b"analytics", survey.key().as_ref()],
+        bump
+    )]
+    pub analytics_storage: Account<'info, SurveyAnalyticsStorage>,
+    
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Separate instruction for feedback computation
+#[queue_computation_accounts("survey_analytics", payer)]
+#[derive(Accounts)]
+#[instruction(feedback_computation_offset: u64)]
+pub struct SubmitSurveyFeedback<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(feedback_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SURVEY_ANALYTICS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("quiz_evaluation", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitQuizResponse<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    // Removed response account - following Arcium docs: no individual response storage
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Lightweight quiz answers submission (fixes transaction size issue)
+#[derive(Accounts)]
+pub struct SubmitQuizAnswers<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + QuizAnswersStorage::INIT_SPACE,
+        seeds = [b"quiz_answers", quiz.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub answers_storage: Account<'info, QuizAnswersStorage>,
+    // Required when the quiz shuffles answers (see start_shuffled_attempt)
+    #[account(
+        seeds = [b"quiz_attempt", quiz.key().as_ref(), payer.key().as_ref()],
+        bump = quiz_attempt.bump
+    )]
+    pub quiz_attempt: Option<Account<'info, QuizAttempt>>,
+    pub system_program: Program<'info, System>,
+}
+
+// ✅ NEW: Compute quiz grade separately (heavy MPC computation)
+#[queue_computation_accounts("quiz_evaluation", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ComputeQuizGrade<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"quiz_answers", quiz.key().as_ref(), answers_storage.student.as_ref()],
+        bump = answers_storage.bump
+    )]
+    pub answers_storage: Account<'info, QuizAnswersStorage>,
+    #[account(
+        seeds = [b"quiz_grading_data", quiz.key().as_ref()],
+        bump = quiz_grading_data.bump
+    )]
+    pub quiz_grading_data: Account<'info, QuizGradingDataStorage>,
+    #[account(
+        mut,
+        seeds = [b"item_stats", quiz.key().as_ref()],
+        bump = item_stats.bump
+    )]
+    pub item_stats: Account<'info, ItemStatsStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Separate instruction for student quiz computation
+#[queue_computation_accounts("quiz_evaluation", payer)]
+#[derive(Accounts)]
+#[instruction(student_computation_offset: u64)]
+pub struct SubmitQuizStudent<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(student_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Separate instruction for instructor quiz computation
+#[queue_computation_accounts("quiz_evaluation", payer)]
+#[derive(Accounts)]
+#[instruction(instructor_computation_offset: u64)]
+pub struct SubmitQuizInstructor<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(instructor_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Separate instruction for creator quiz computation
+#[queue_computation_accounts("quiz_evaluation", payer)]
+#[derive(Accounts)]
+#[instruction(creator_computation_offset: u64)]
+pub struct SubmitQuizCreator<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(creator_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSpecialSurvey<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = creator,
+        space = Survey::INIT_SPACE,
+        seeds = [b"special_survey", creator.key().as_ref(), quiz.key().as_ref()],
+        bump
+    )]
+    pub special_survey: Account<'info, Survey>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("quiz_threshold_check", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct VerifyQuizCompletion<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + QuizCompletionProof::INIT_SPACE,
+        seeds = [b"quiz_completion", payer.key().as_ref(), quiz.key().as_ref()],
+        bump
+    )]
+    pub completion_proof: Account<'info, QuizCompletionProof>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_THRESHOLD)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Separate instruction for student quiz verification
+#[queue_computation_accounts("quiz_threshold_check", payer)]
+#[derive(Accounts)]
+#[instruction(student_computation_offset: u64)]
+pub struct VerifyQuizStudent<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + QuizCompletionProof::INIT_SPACE,
+        seeds = [b"quiz_completion", payer.key().as_ref(), quiz.key().as_ref()],
+        bump
+    )]
+    pub completion_proof: Account<'info, QuizCompletionProof>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(student_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_THRESHOLD)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Separate instruction for access controller quiz verification
+#[queue_computation_accounts("quiz_threshold_check", payer)]
+#[derive(Accounts)]
+#[instruction(access_controller_computation_offset: u64)]
+pub struct VerifyQuizAccessController<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + QuizCompletionProof::INIT_SPACE,
+        seeds = [b"quiz_completion", payer.key().as_ref(), quiz.key().as_ref()],
+        bump
+    )]
+    pub completion_proof: Account<'info, QuizCompletionProof>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(access_controller_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_THRESHOLD)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Separate instruction for auditor quiz verification
+#[queue_computation_accounts("quiz_threshold_check", payer)]
+#[derive(Accounts)]
+#[instruction(auditor_computation_offset: u64)]
+pub struct VerifyQuizAuditor<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + QuizCompletionProof::INIT_SPACE,
+        seeds = [b"quiz_completion", payer.key().as_ref(), quiz.key().as_ref()],
+        bump
+    )]
+    pub completion_proof: Account<'info, QuizCompletionProof>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(auditor_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_THRESHOLD)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("survey_analytics", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitSpecialSurveyResponse<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub special_survey: Account<'info, Survey>,
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub completion_proof: Account<'info, QuizCompletionProof>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SURVEY_ANALYTICS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteSurvey<'info> {
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    pub creator: Signer<'info>,
+}
+
+// ✅ NEW: Create the encrypted matrix tally for one question (creator only)
+#[queue_computation_accounts("init_matrix_tally", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, question_id: u32)]
+pub struct InitMatrixTally<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MatrixTallyStorage::INIT_SPACE,
+        seeds = [b"matrix_tally", survey.key().as_ref(), &question_id.to_le_bytes()],
+        bump
+    )]
+    pub tally_storage: Account<'info, MatrixTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MATRIX_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Fold one encrypted matrix answer into the question's tally
+#[queue_computation_accounts("accumulate_matrix_response", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitMatrixResponse<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"matrix_tally", survey.key().as_ref(), &tally_storage.question_id.to_le_bytes()],
+        bump = tally_storage.bump
+    )]
+    pub tally_storage: Account<'info, MatrixTallyStorage>,
+    // One per respondent; re-used only to retry an answer whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"tally_receipt", tally_storage.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TallyReceipt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_MATRIX_RESPONSE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Re-encrypt the aggregate matrix tally for the creator
+#[queue_computation_accounts("reveal_matrix_tally", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealMatrixTally<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"matrix_tally", survey.key().as_ref(), &tally_storage.question_id.to_le_bytes()],
+        bump = tally_storage.bump
+    )]
+    pub tally_storage: Account<'info, MatrixTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_MATRIX_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted ranking tally for one question (creator only)
+#[queue_computation_accounts("init_ranking_tally", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, question_id: u32)]
+pub struct InitRankingTally<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RankingTallyStorage::INIT_SPACE,
+        seeds = [b"ranking_tally", survey.key().as_ref(), &question_id.to_le_bytes()],
+        bump
+    )]
+    pub tally_storage: Account<'info, RankingTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RANKING_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Fold one encrypted ranking answer into the question's tally
+#[queue_computation_accounts("accumulate_ranking_response", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitRankingResponse<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"ranking_tally", survey.key().as_ref(), &tally_storage.question_id.to_le_bytes()],
+        bump = tally_storage.bump
+    )]
+    pub tally_storage: Account<'info, RankingTallyStorage>,
+    // One per respondent; re-used only to retry an answer whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"tally_receipt", tally_storage.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TallyReceipt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_RANKING_RESPONSE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Re-encrypt the aggregate ranking tally for the creator
+#[queue_computation_accounts("reveal_ranking_tally", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealRankingTally<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"ranking_tally", survey.key().as_ref(), &tally_storage.question_id.to_le_bytes()],
+        bump = tally_storage.bump
+    )]
+    pub tally_storage: Account<'info, RankingTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RANKING_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted NPS tally for one Rating question (creator only)
+#[queue_computation_accounts("init_nps_tally", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, question_id: u32)]
+pub struct InitNpsTally<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + NpsTallyStorage::INIT_SPACE,
+        seeds = [b"nps_tally", survey.key().as_ref(), &question_id.to_le_bytes()],
+        bump
+    )]
+    pub tally_storage: Account<'info, NpsTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_NPS_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Fold one encrypted rating into the NPS tally
+#[queue_computation_accounts("accumulate_nps_response", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitNpsResponse<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"nps_tally", survey.key().as_ref(), &tally_storage.question_id.to_le_bytes()],
+        bump = tally_storage.bump
+    )]
+    pub tally_storage: Account<'info, NpsTallyStorage>,
+    // One per respondent; re-used only to retry an answer whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"tally_receipt", tally_storage.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TallyReceipt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_NPS_RESPONSE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Re-encrypt the NPS report for the creator
+#[queue_computation_accounts("reveal_nps", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealNps<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        mut,
+        seeds = [b"nps_tally", survey.key().as_ref(), &tally_storage.question_id.to_le_bytes()],
+        bump = tally_storage.bump
+    )]
+    pub tally_storage: Account<'info, NpsTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_NPS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted histogram for one numeric question (creator only)
+#[queue_computation_accounts("init_histogram", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, question_id: u32)]
+pub struct InitHistogram<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + HistogramStorage::INIT_SPACE,
+        seeds = [b"histogram", survey.key().as_ref(), &question_id.to_le_bytes()],
+        bump
+    )]
+    pub histogram_storage: Account<'info, HistogramStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_HISTOGRAM)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Fold one encrypted numeric answer into the histogram
+#[queue_computation_accounts("accumulate_histogram_response", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitHistogramResponse<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"histogram", survey.key().as_ref(), &histogram_storage.question_id.to_le_bytes()],
+        bump = histogram_storage.bump
+    )]
+    pub histogram_storage: Account<'info, HistogramStorage>,
+    // One per respondent; re-used only to retry an answer whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"tally_receipt", histogram_storage.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TallyReceipt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_HISTOGRAM_RESPONSE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Re-encrypt median / percentile summary for the creator
+#[queue_computation_accounts("reveal_percentiles", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealPercentiles<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        mut,
+        seeds = [b"histogram", survey.key().as_ref(), &histogram_storage.question_id.to_le_bytes()],
+        bump = histogram_storage.bump
+    )]
+    pub histogram_storage: Account<'info, HistogramStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_PERCENTILES)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted contingency table for a question pair (creator only)
+#[queue_computation_accounts("init_crosstab", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, question_a: u32, question_b: u32)]
+pub struct InitCrosstab<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CrosstabStorage::INIT_SPACE,
+        seeds = [b"crosstab", survey.key().as_ref(), &question_a.to_le_bytes(), &question_b.to_le_bytes()],
+        bump
+    )]
+    pub crosstab_storage: Account<'info, CrosstabStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_CROSSTAB)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Fold one encrypted answer pair into the contingency table
+#[queue_computation_accounts("accumulate_crosstab_response", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitCrosstabResponse<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [
+            b"crosstab",
+            survey.key().as_ref(),
+            &crosstab_storage.question_a.to_le_bytes(),
+            &crosstab_storage.question_b.to_le_bytes(),
+        ],
+        bump = crosstab_storage.bump
+    )]
+    pub crosstab_storage: Account<'info, CrosstabStorage>,
+    // One per respondent; re-used only to retry an answer whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"tally_receipt", crosstab_storage.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TallyReceipt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_CROSSTAB_RESPONSE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Re-encrypt the suppressed contingency table for the creator
+#[queue_computation_accounts("reveal_crosstab", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealCrosstab<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [
+            b"crosstab",
+            survey.key().as_ref(),
+            &crosstab_storage.question_a.to_le_bytes(),
+            &crosstab_storage.question_b.to_le_bytes(),
+        ],
+        bump = crosstab_storage.bump
+    )]
+    pub crosstab_storage: Account<'info, CrosstabStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_CROSSTAB)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted correlation sums for a survey (creator only)
+#[queue_computation_accounts("init_correlation_sums", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitCorrelationSums<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CorrelationSumsStorage::INIT_SPACE,
+        seeds = [b"correlation_sums", survey.key().as_ref()],
+        bump
+    )]
+    pub correlation_sums: Account<'info, CorrelationSumsStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_CORRELATION_SUMS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Queue analytics_computation against the survey's correlation sums
+#[queue_computation_accounts("analytics_computation", payer)]
+#[derive(Accounts)]
+#[instruction(analytics_computation_offset: u64)]
+pub struct SubmitResearchAnalytics<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"correlation_sums", survey.key().as_ref()],
+        bump = correlation_sums.bump
+    )]
+    pub correlation_sums: Account<'info, CorrelationSumsStorage>,
+    // One per respondent; re-used only to retry an answer whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"tally_receipt", correlation_sums.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TallyReceipt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(analytics_computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ANALYTICS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted item analysis totals for a quiz (creator only)
+#[queue_computation_accounts("init_item_stats", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitItemStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ItemStatsStorage::INIT_SPACE,
+        seeds = [b"item_stats", quiz.key().as_ref()],
+        bump
+    )]
+    pub item_stats: Account<'info, ItemStatsStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ITEM_STATS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Re-encrypt the item analysis report for the instructor
+#[queue_computation_accounts("reveal_item_analysis", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealItemAnalysis<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        mut,
+        seeds = [b"item_stats", quiz.key().as_ref()],
+        bump = item_stats.bump
+    )]
+    pub item_stats: Account<'info, ItemStatsStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_ITEM_ANALYSIS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted leaderboard for a quiz (creator only)
+#[queue_computation_accounts("init_leaderboard", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitLeaderboard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LeaderboardStorage::INIT_SPACE,
+        seeds = [b"leaderboard", quiz.key().as_ref()],
+        bump
+    )]
+    pub leaderboard: Account<'info, LeaderboardStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_LEADERBOARD)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Opt a student's graded attempt into the leaderboard (once per student)
+#[queue_computation_accounts("record_leaderboard_score", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct JoinLeaderboard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        seeds = [b"quiz_answers", quiz.key().as_ref(), payer.key().as_ref()],
+        bump = answers_storage.bump
+    )]
+    pub answers_storage: Account<'info, QuizAnswersStorage>,
+    #[account(
+        seeds = [b"quiz_grading_data", quiz.key().as_ref()],
+        bump = quiz_grading_data.bump
+    )]
+    pub quiz_grading_data: Account<'info, QuizGradingDataStorage>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", quiz.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Account<'info, LeaderboardStorage>,
+    // Re-used only to retry an entry that was turned away or whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + LeaderboardEntryStorage::INIT_SPACE,
+        seeds = [b"leaderboard_entry", quiz.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub leaderboard_entry: Account<'info, LeaderboardEntryStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_RECORD_LEADERBOARD_SCORE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Re-encrypt a student's own rank for that student
+#[queue_computation_accounts("reveal_my_rank", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealMyRank<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        seeds = [b"leaderboard", quiz.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Account<'info, LeaderboardStorage>,
+    #[account(
+        seeds = [b"leaderboard_entry", quiz.key().as_ref(), payer.key().as_ref()],
+        bump = leaderboard_entry.bump
+    )]
+    pub leaderboard_entry: Account<'info, LeaderboardEntryStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_MY_RANK)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Publish the pseudonymous top K of a quiz leaderboard
+#[queue_computation_accounts("reveal_leaderboard", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct PublishLeaderboard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", quiz.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Account<'info, LeaderboardStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_LEADERBOARD)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted answer history for similarity checks (creator only)
+#[queue_computation_accounts("init_answer_history", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitAnswerHistory<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AnswerHistoryStorage::INIT_SPACE,
+        seeds = [b"answer_history", quiz.key().as_ref()],
+        bump
+    )]
+    pub answer_history: Account<'info, AnswerHistoryStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ANSWER_HISTORY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Compare one submission against the answer history
+#[queue_computation_accounts("check_answer_similarity", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CheckAnswerSimilarity<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        mut,
+        seeds = [b"quiz_answers", quiz.key().as_ref(), answers_storage.student.as_ref()],
+        bump = answers_storage.bump
+    )]
+    pub answers_storage: Account<'info, QuizAnswersStorage>,
+    #[account(
+        seeds = [b"quiz_grading_data", quiz.key().as_ref()],
+        bump = quiz_grading_data.bump
+    )]
+    pub quiz_grading_data: Account<'info, QuizGradingDataStorage>,
+    #[account(
+        mut,
+        seeds = [b"answer_history", quiz.key().as_ref()],
+        bump = answer_history.bump
+    )]
+    pub answer_history: Account<'info, AnswerHistoryStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_ANSWER_SIMILARITY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[derive(Accounts)]
+pub struct SetQuestionPool<'info> {
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + QuestionPoolStorage::INIT_SPACE,
+        seeds = [b"question_pool", quiz.key().as_ref()],
+        bump
+    )]
+    pub question_pool: Account<'info, QuestionPoolStorage>,
+    pub system_program: Program<'info, System>,
+}
+
+// ✅ NEW: Draw this student's questions from the pool (once per student)
+#[derive(Accounts)]
+pub struct StartQuizAttempt<'info> {
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub student: Signer<'info>,
+    #[account(
+        seeds = [b"question_pool", quiz.key().as_ref()],
+        bump = question_pool.bump
+    )]
+    pub question_pool: Account<'info, QuestionPoolStorage>,
+    #[account(
+        init,
+        payer = student,
+        space = 8 + QuizAttempt::INIT_SPACE,
+        seeds = [b"quiz_attempt", quiz.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub quiz_attempt: Account<'info, QuizAttempt>,
+    /// CHECK: SlotHashes sysvar, read raw (too large to deserialize)
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ✅ NEW: Record the shuffle seed for one student's attempt (once per student)
+#[derive(Accounts)]
+pub struct StartShuffledAttempt<'info> {
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub student: Signer<'info>,
+    #[account(
+        init,
+        payer = student,
+        space = 8 + QuizAttempt::INIT_SPACE,
+        seeds = [b"quiz_attempt", quiz.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub quiz_attempt: Account<'info, QuizAttempt>,
+    /// CHECK: SlotHashes sysvar, read raw (too large to deserialize)
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ✅ NEW: Grade a pooled quiz attempt against the drawn answer keys
+#[queue_computation_accounts("pool_quiz_evaluation", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct GradePoolAttempt<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"quiz_answers", quiz.key().as_ref(), answers_storage.student.as_ref()],
+        bump = answers_storage.bump
+    )]
+    pub answers_storage: Account<'info, QuizAnswersStorage>,
+    #[account(
+        seeds = [b"quiz_attempt", quiz.key().as_ref(), answers_storage.student.as_ref()],
+        bump = quiz_attempt.bump
+    )]
+    pub quiz_attempt: Account<'info, QuizAttempt>,
+    #[account(
+        seeds = [b"question_pool", quiz.key().as_ref()],
+        bump = question_pool.bump
+    )]
+    pub question_pool: Account<'info, QuestionPoolStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_POOL_QUIZ_EVALUATION)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[derive(Accounts)]
+pub struct SetAdaptiveBank<'info> {
+    pub quiz: Account<'info, Survey>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AdaptiveBankStorage::INIT_SPACE,
+        seeds = [b"adaptive_bank", quiz.key().as_ref()],
+        bump
+    )]
+    pub adaptive_bank: Account<'info, AdaptiveBankStorage>,
+    pub system_program: Program<'info, System>,
+}
+
+// ✅ NEW: Open a student's adaptive attempt (once per student)
+#[queue_computation_accounts("init_adaptive_attempt", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct StartAdaptiveAttempt<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        seeds = [b"adaptive_bank", quiz.key().as_ref()],
+        bump = adaptive_bank.bump
+    )]
+    pub adaptive_bank: Account<'info, AdaptiveBankStorage>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AdaptiveAttempt::INIT_SPACE,
+        seeds = [b"adaptive_attempt", quiz.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub adaptive_attempt: Account<'info, AdaptiveAttempt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ADAPTIVE_ATTEMPT)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Answer the current adaptive question
+#[queue_computation_accounts("adaptive_step", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AnswerAdaptiveQuestion<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        seeds = [b"adaptive_bank", quiz.key().as_ref()],
+        bump = adaptive_bank.bump
+    )]
+    pub adaptive_bank: Account<'info, AdaptiveBankStorage>,
+    #[account(
+        mut,
+        seeds = [b"adaptive_attempt", quiz.key().as_ref(), payer.key().as_ref()],
+        bump = adaptive_attempt.bump
+    )]
+    pub adaptive_attempt: Account<'info, AdaptiveAttempt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADAPTIVE_STEP)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Reveal the placement of a finished adaptive attempt
+#[queue_computation_accounts("reveal_adaptive_result", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct FinishAdaptiveAttempt<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub quiz: Account<'info, Survey>,
+    #[account(
+        mut,
+        seeds = [b"adaptive_attempt", quiz.key().as_ref(), payer.key().as_ref()],
+        bump = adaptive_attempt.bump
+    )]
+    pub adaptive_attempt: Account<'info, AdaptiveAttempt>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_ADAPTIVE_RESULT)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the encrypted tally for a poll (creator only)
+#[queue_computation_accounts("init_poll_tally", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitPollTally<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PollTallyStorage::INIT_SPACE,
+        seeds = [b"poll_tally", survey.key().as_ref()],
+        bump
+    )]
+    pub poll_tally: Account<'info, PollTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POLL_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Cast one encrypted ballot (one per wallet)
+#[queue_computation_accounts("cast_poll_vote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CastPollVote<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"poll_tally", survey.key().as_ref()],
+        bump = poll_tally.bump
+    )]
+    pub poll_tally: Account<'info, PollTallyStorage>,
+    // Re-used only to retry a ballot whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PollBallot::INIT_SPACE,
+        seeds = [b"poll_ballot", survey.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub ballot: Account<'info, PollBallot>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_POLL_VOTE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Publish a closed poll's tally (anyone)
+#[queue_computation_accounts("reveal_poll_tally", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct PublishPollResults<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        mut,
+        seeds = [b"poll_tally", survey.key().as_ref()],
+        bump = poll_tally.bump
+    )]
+    pub poll_tally: Account<'info, PollTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_POLL_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
 }
 
-
-// Account structures for our survey/quiz DApp
-
+// ✅ NEW: Create the encrypted ranked-choice tally for a poll (creator only)
+#[queue_computation_accounts("init_ranked_tally", payer)]
 #[derive(Accounts)]
-#[instruction(timestamp: i64)]
-pub struct CreateSurveyWithTimestamp<'info> {
+#[instruction(computation_offset: u64)]
+pub struct InitRankedTally<'info> {
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
     #[account(
         init,
-        payer = creator,
-        space = 8 + Survey::INIT_SPACE + 2000, // ✅ FIXED: Extra space for longer strings
-        seeds = [b"survey", creator.key().as_ref(), &timestamp.to_le_bytes()],
+        payer = payer,
+        space = 8 + RankedTallyStorage::INIT_SPACE,
+        seeds = [b"ranked_tally", survey.key().as_ref()],
+        bump
+    )]
+    pub ranked_tally: Account<'info, RankedTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RANKED_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Cast one encrypted ranked ballot (one per wallet, shared with plurality polls)
+#[queue_computation_accounts("cast_ranked_ballot", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CastRankedBallot<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"ranked_tally", survey.key().as_ref()],
+        bump = ranked_tally.bump
+    )]
+    pub ranked_tally: Account<'info, RankedTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PollBallot::INIT_SPACE,
+        seeds = [b"poll_ballot", survey.key().as_ref(), payer.key().as_ref()],
         bump
     )]
+    pub ballot: Account<'info, PollBallot>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_RANKED_BALLOT)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Run instant-runoff on a closed ranked poll (anyone)
+#[queue_computation_accounts("run_instant_runoff", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct PublishRankedPollResults<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
     pub survey: Account<'info, Survey>,
+    #[account(
+        mut,
+        seeds = [b"ranked_tally", survey.key().as_ref()],
+        bump = ranked_tally.bump
+    )]
+    pub ranked_tally: Account<'info, RankedTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_RUN_INSTANT_RUNOFF)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
 }
 
+// ✅ NEW: Create the encrypted answer counter for one TextInput question (creator only)
+#[queue_computation_accounts("init_text_tally", payer)]
 #[derive(Accounts)]
-pub struct AddQuestions<'info> {
+#[instruction(computation_offset: u64, question_id: u32)]
+pub struct InitTextTally<'info> {
     #[account(mut)]
+    pub payer: Signer<'info>,
     pub survey: Account<'info, Survey>,
-    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TextTallyStorage::INIT_SPACE,
+        seeds = [b"text_tally", survey.key().as_ref(), &question_id.to_le_bytes()],
+        bump
+    )]
+    pub tally_storage: Account<'info, TextTallyStorage>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_TEXT_TALLY)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
 }
 
+// ✅ NEW: Store one encrypted text answer and count it (once per respondent and question)
+#[queue_computation_accounts("accumulate_text_response", payer)]
 #[derive(Accounts)]
-pub struct SetQuizGradingData<'info> {
+#[instruction(computation_offset: u64)]
+pub struct SubmitTextResponse<'info> {
     #[account(mut)]
-    pub quiz: Account<'info, Survey>,
+    pub payer: Signer<'info>,
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub survey: Account<'info, Survey>,
     #[account(
-        init,
-        payer = creator,
-        space = 8 + 32 + 256 + 16 + 32 + 1, // Discriminator + quiz + encrypted_data + nonce + pubkey + bump
-        seeds = [b"quiz_grading_data", quiz.key().as_ref()],
+        seeds = [b"survey_computation_preferences"],
         bump
     )]
-    pub quiz_grading_data: Account<'info, QuizGradingDataStorage>,
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"text_tally", survey.key().as_ref(), &tally_storage.question_id.to_le_bytes()],
+        bump = tally_storage.bump
+    )]
+    pub tally_storage: Account<'info, TextTallyStorage>,
+    // One per respondent; re-used only to retry an answer whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"tally_receipt", tally_storage.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TallyReceipt>,
+    // Rewritten only when retrying an answer whose computation was lost
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TextResponse::MAX_SPACE,
+        seeds = [
+            b"text_response",
+            survey.key().as_ref(),
+            payer.key().as_ref(),
+            &tally_storage.question_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub text_response: Account<'info, TextResponse>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_TEXT_RESPONSE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("survey_analytics", payer)]
+// ✅ NEW: Reveal text answer counts to the creator
+#[queue_computation_accounts("reveal_text_stats", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct SubmitSurveyResponse<'info> {
-    // Removed response account - following Arcium docs: no individual response storage
+pub struct RevealTextStats<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"text_tally", survey.key().as_ref(), &tally_storage.question_id.to_le_bytes()],
+        bump = tally_storage.bump
+    )]
+    pub tally_storage: Account<'info, TextTallyStorage>,
     #[account(
         init_if_needed,
         payer = payer,
@@ -1513,7 +11604,7 @@ pub struct SubmitSurveyResponse<'info> {
     /// CHECK: computation_account, checked by the arcium program.
     pub computation_account: UncheckedAccount<'info>,
     #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SURVEY_ANALYTICS)
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_TEXT_STATS)
     )]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(
@@ -1530,19 +11621,26 @@ pub struct SubmitSurveyResponse<'info> {
         address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
     )]
     pub clock_account: Account<'info, ClockAccount>,
-    #[account(mut)]
-    pub survey: Account<'info, Survey>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
 }
 
-// Separate instruction for analytics computation
-#[queue_computation_accounts("survey_analytics", payer)]
+// ✅ NEW: Create the encrypted keyword counters for one TextInput question (creator only)
+#[queue_computation_accounts("init_keyword_tally", payer)]
 #[derive(Accounts)]
-#[instruction(analytics_computation_offset: u64)]
-pub struct SubmitSurveyAnalytics<'info> {
+#[instruction(computation_offset: u64, question_id: u32)]
+pub struct InitKeywordTally<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + KeywordTallyStorage::INIT_SPACE,
+        seeds = [b"keyword_tally", survey.key().as_ref(), &question_id.to_le_bytes()],
+        bump
+    )]
+    pub keyword_tally: Account<'info, KeywordTallyStorage>,
     #[account(
         init_if_needed,
         payer = payer,
@@ -1571,12 +11669,12 @@ pub struct SubmitSurveyAnalytics<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(
         mut,
-        address = derive_comp_pda!(analytics_computation_offset)
+        address = derive_comp_pda!(computation_offset)
     )]
     /// CHECK: computation_account, checked by the arcium program.
     pub computation_account: UncheckedAccount<'info>,
     #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SURVEY_ANALYTICS)
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_KEYWORD_TALLY)
     )]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(
@@ -1593,30 +11691,116 @@ pub struct SubmitSurveyAnalytics<'info> {
         address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
     )]
     pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Count the keywords of the payer's own text answer (once per answer)
+#[queue_computation_accounts("accumulate_keywords", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitTextKeywords<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
     #[account(mut)]
     pub survey: Account<'info, Survey>,
-    
-    // ✅ FIX: Add analytics storage account for callback
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"keyword_tally", survey.key().as_ref(), &keyword_tally.question_id.to_le_bytes()],
+        bump = keyword_tally.bump
+    )]
+    pub keyword_tally: Account<'info, KeywordTallyStorage>,
+    // One per respondent; re-used only to retry an answer whose computation was lost
     #[account(
         init_if_needed,
         payer = payer,
-        space = SurveyAnalyticsStorage::INIT_SPACE,
-        seeds = [b"analytics", survey.key().as_ref()],
+        space = 8 + TallyReceipt::INIT_SPACE,
+        seeds = [b"tally_receipt", keyword_tally.key().as_ref(), payer.key().as_ref()],
         bump
     )]
-    pub analytics_storage: Account<'info, SurveyAnalyticsStorage>,
-    
+    pub receipt: Account<'info, TallyReceipt>,
+    #[account(
+        seeds = [
+            b"text_response",
+            survey.key().as_ref(),
+            payer.key().as_ref(),
+            &keyword_tally.question_id.to_le_bytes()
+        ],
+        bump = text_response.bump
+    )]
+    pub text_response: Account<'info, TextResponse>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_ACCUMULATE_KEYWORDS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
 }
 
-// Separate instruction for feedback computation
-#[queue_computation_accounts("survey_analytics", payer)]
+// ✅ NEW: Reveal the top keywords of a TextInput question to the creator
+#[queue_computation_accounts("reveal_keyword_table", payer)]
 #[derive(Accounts)]
-#[instruction(feedback_computation_offset: u64)]
-pub struct SubmitSurveyFeedback<'info> {
+#[instruction(computation_offset: u64)]
+pub struct RevealKeywordTable<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"keyword_tally", survey.key().as_ref(), &keyword_tally.question_id.to_le_bytes()],
+        bump = keyword_tally.bump
+    )]
+    pub keyword_tally: Account<'info, KeywordTallyStorage>,
     #[account(
         init_if_needed,
         payer = payer,
@@ -1645,12 +11829,12 @@ pub struct SubmitSurveyFeedback<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(
         mut,
-        address = derive_comp_pda!(feedback_computation_offset)
+        address = derive_comp_pda!(computation_offset)
     )]
     /// CHECK: computation_account, checked by the arcium program.
     pub computation_account: UncheckedAccount<'info>,
     #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SURVEY_ANALYTICS)
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_KEYWORD_TABLE)
     )]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(
@@ -1667,21 +11851,104 @@ pub struct SubmitSurveyFeedback<'info> {
         address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
     )]
     pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// ✅ NEW: Create the response buffer and encrypted totals of a Batch survey (creator only)
+#[queue_computation_accounts("init_batch_totals", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitResponseBatch<'info> {
     #[account(mut)]
+    pub payer: Signer<'info>,
     pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ResponseBatchBuffer::INIT_SPACE,
+        seeds = [b"response_batch", survey.key().as_ref()],
+        bump
+    )]
+    pub response_batch: Account<'info, ResponseBatchBuffer>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 9,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH_TOTALS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("quiz_evaluation", payer)]
+// ✅ NEW: Send the buffered responses of a Batch survey to MPC (anyone may call)
+#[queue_computation_accounts("process_response_batch", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct SubmitQuizResponse<'info> {
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    // Removed response account - following Arcium docs: no individual response storage
+pub struct ProcessResponseBatch<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
+    #[account(
+        seeds = [b"survey_computation_preferences"],
+        bump
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(
+        mut,
+        seeds = [b"response_batch", survey.key().as_ref()],
+        bump = response_batch.bump
+    )]
+    pub response_batch: Account<'info, ResponseBatchBuffer>,
     #[account(
         init_if_needed,
         payer = payer,
@@ -1715,7 +11982,7 @@ pub struct SubmitQuizResponse<'info> {
     /// CHECK: computation_account, checked by the arcium program.
     pub computation_account: UncheckedAccount<'info>,
     #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_RESPONSE_BATCH)
     )]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(
@@ -1736,44 +12003,20 @@ pub struct SubmitQuizResponse<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-// ✅ NEW: Lightweight quiz answers submission (fixes transaction size issue)
-#[derive(Accounts)]
-pub struct SubmitQuizAnswers<'info> {
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + QuizAnswersStorage::INIT_SPACE,
-        seeds = [b"quiz_answers", quiz.key().as_ref(), payer.key().as_ref()],
-        bump
-    )]
-    pub answers_storage: Account<'info, QuizAnswersStorage>,
-    pub system_program: Program<'info, System>,
-}
-
-// ✅ NEW: Compute quiz grade separately (heavy MPC computation)
-#[queue_computation_accounts("quiz_evaluation", payer)]
+// ✅ NEW: Reveal batch totals to the creator
+#[queue_computation_accounts("reveal_batch_analytics", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct ComputeQuizGrade<'info> {
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
+pub struct RevealBatchAnalytics<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    pub survey: Account<'info, Survey>,
     #[account(
         mut,
-        seeds = [b"quiz_answers", quiz.key().as_ref(), answers_storage.student.as_ref()],
-        bump = answers_storage.bump
-    )]
-    pub answers_storage: Account<'info, QuizAnswersStorage>,
-    #[account(
-        seeds = [b"quiz_grading_data", quiz.key().as_ref()],
-        bump = quiz_grading_data.bump
+        seeds = [b"response_batch", survey.key().as_ref()],
+        bump = response_batch.bump
     )]
-    pub quiz_grading_data: Account<'info, QuizGradingDataStorage>,
+    pub response_batch: Account<'info, ResponseBatchBuffer>,
     #[account(
         init_if_needed,
         payer = payer,
@@ -1807,7 +12050,7 @@ pub struct ComputeQuizGrade<'info> {
     /// CHECK: computation_account, checked by the arcium program.
     pub computation_account: UncheckedAccount<'info>,
     #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_BATCH_ANALYTICS)
     )]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(
@@ -1828,816 +12071,1336 @@ pub struct ComputeQuizGrade<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-// Separate instruction for student quiz computation
-#[queue_computation_accounts("quiz_evaluation", payer)]
-#[derive(Accounts)]
-#[instruction(student_computation_offset: u64)]
-pub struct SubmitQuizStudent<'info> {
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        space = 9,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(
-        mut,
-        address = derive_mempool_pda!()
-    )]
-    /// CHECK: mempool_account, checked by the arcium program.
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_execpool_pda!()
-    )]
-    /// CHECK: executing_pool, checked by the arcium program.
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_comp_pda!(student_computation_offset)
-    )]
-    /// CHECK: computation_account, checked by the arcium program.
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
-    )]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(
-        mut,
-        address = derive_cluster_pda!(mxe_account)
-    )]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(
-        mut,
-        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
-    )]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(
-        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
-    )]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+// ✅ FIXED: Account structure for initializing Sign PDA Account
+#[derive(Accounts)]
+pub struct InitSignPda<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+// ✅ ADDED: Account structures for new multi-recipient computations
+
+
+
+
+
+
+
+
+// ✅ REVERTED: Use proper Arcium macro-generated account structures
+// These will be automatically generated by the  macro
+
+// ✅ REVERTED: Using proper Arcium macro-based computation definition initialization
+
+// Data structures
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum SurveyType {
+    Basic,
+    Quiz {
+        time_per_question: Option<u64>,
+        total_time_limit: Option<u64>,
+        passing_threshold: u8,
+        special_survey_enabled: bool,
+        completion_proof_expiration_value: u32,  // Expiration value
+        completion_proof_expiration_unit: ExpirationUnit,  // Expiration unit (minutes, hours, days)
+    },
+    Special,
+    // ✅ NEW: One encrypted vote per eligible wallet; tally revealed after closes_at
+    Poll {
+        option_count: u8,                        // Ballots choose 0..option_count
+        closes_at: i64,                          // Unix timestamp; voting ends, reveal opens
+        eligible_voters_root: Option<[u8; 32]>,  // Merkle root over voter_leaf_hash; None = any wallet
+        ranked_choice: bool,                     // Ranked ballots tallied by instant-runoff (≤ MAX_IRV_CANDIDATES)
+    },
+}
+
+impl SurveyType {
+    // Largest variant (Poll): tag + option_count + closes_at + Option<[u8; 32]> + ranked_choice
+    pub const MAX_SPACE: usize = 1 + 1 + 8 + 1 + 32 + 1;
+}
+
+// Unpacked SurveyType::Poll (see Survey::poll_config)
+pub struct PollConfig {
+    pub option_count: u8,
+    pub closes_at: i64,
+    pub eligible_voters_root: Option<[u8; 32]>,
+    pub ranked_choice: bool,
+}
+
+impl PollConfig {
+    /// Voting is open and, for polls with a voter list, `voter` is on it
+    pub fn check_can_vote(&self, now: i64, voter: &Pubkey, voter_index: u32, proof: &[[u8; 32]]) -> Result<()> {
+        require!(now < self.closes_at, ErrorCode::PollClosed);
+        if let Some(root) = self.eligible_voters_root {
+            require!(
+                merkle_root_from_proof(voter_leaf_hash(voter_index, voter), voter_index, proof) == root,
+                ErrorCode::NotEligibleVoter
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ExpirationUnit {
+    Minutes,
+    Hours,
+    Days,
+}
+
+// ✅ SECURE: MXE Configuration enums for Arcium compatibility
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum EncryptionScheme {
+    Rescue,  // Arcium's preferred encryption scheme
+    AES,      // Alternative encryption scheme
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum DataProvisioningMethod {
+    Direct,   // Direct data provisioning for real-time computations
+    Batch,    // Batch data provisioning for efficient processing
+    CreatorOnly,  // Responses encrypted straight to the creator; no MPC at all
+}
+
+// ✅ FIXED: Add MPC protocol enum for Arcium compatibility
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum MPCProtocol {
+    Cerberus,    // Arcium's Cerberus MPC protocol
+    Manticore,   // Arcium's Manticore MPC protocol
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum QuestionType {
+    MultipleChoice { options: Vec<String> },
+    TrueFalse,
+    TextInput,
+    Rating { min: u8, max: u8 },
+    TimeLimit { duration: u64 },
+    Matrix { rows: Vec<String>, columns: Vec<String> },  // Likert grid: one column picked per row
+    Ranking { options: Vec<String> },                    // Respondent orders every option
+}
+
+// ✅ NEW: Per-question grading scheme for quizzes
+/// Penalties are only charged for answered questions, so skipping is never worse
+/// than guessing wrong. The quiz total is floored at zero.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum GradingScheme {
+    Exact { negative_marks: u8 },                     // All-or-nothing; negative_marks off for a wrong answer
+    MultiSelect { wrong_pick_penalty: u8 },           // Bitmask answer: points split across correct options
+    NumericTolerance { tolerance: u32, negative_marks: u8 },  // Correct within ±tolerance of the key
+}
+
+impl GradingScheme {
+    /// `[scheme, tolerance, penalty]` as the creator encrypts them into the quiz grading
+    /// data (see QUIZ_GRADING_VALUES for the full layout).
+    pub fn grading_words(&self) -> [u32; 3] {
+        match self {
+            GradingScheme::Exact { negative_marks } => {
+                [GRADING_SCHEME_EXACT, 0, *negative_marks as u32]
+            }
+            GradingScheme::MultiSelect { wrong_pick_penalty } => {
+                [GRADING_SCHEME_MULTI_SELECT, 0, *wrong_pick_penalty as u32]
+            }
+            GradingScheme::NumericTolerance { tolerance, negative_marks } => {
+                [GRADING_SCHEME_NUMERIC_TOLERANCE, *tolerance, *negative_marks as u32]
+            }
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestionData {
+    pub id: u32,
+    pub question_text: String,  // Text in the survey's default_language
+    pub question_type: QuestionType,
+    pub required: bool,  // Client hint only: an encrypted NOT_ANSWERED can't be rejected, so it counts as a skip
+    pub points: u8,
+}
+
+// ✅ NEW: Grading scheme of one quiz question, stored in Survey.question_grading
+// (beside QuestionData, so questions stored before grading schemes still deserialize)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestionGrading {
+    pub question_id: u32,
+    pub grading: GradingScheme,  // How `points` are awarded; encoded into the MXE grading data
+}
+
+impl QuestionGrading {
+    /// Scheme of `question_id` among `entries`; questions without an entry are graded Exact
+    pub fn scheme_for(entries: &[QuestionGrading], question_id: u32) -> GradingScheme {
+        entries
+            .iter()
+            .find(|entry| entry.question_id == question_id)
+            .map_or(GradingScheme::Exact { negative_marks: 0 }, |entry| entry.grading.clone())
+    }
+}
+
+// ✅ NEW: Per-attempt order of the graded questions and their options
+/// Derived from the `shuffle_seed` fixed when the attempt starts (QuizAttempt), so the
+/// client renders it and `quiz_evaluation` undoes it. Students answer with shown
+/// positions; the answer key in QuizGradingDataStorage stays canonical.
+/// Must match `answerShuffle` in client/answer_shuffle.ts.
+pub struct AnswerShuffle {
+    pub questions_swapped: bool,  // Shown question 0 is canonical question 1
+    pub shown_options: [[u8; MAX_OPTIONS_PER_QUESTION]; QUIZ_ITEMS],  // [canonical question][shown position] -> canonical option
+    pub bitmask_answers: [bool; QUIZ_ITEMS],  // MultiSelect answers are option bitmasks, not indices
+}
+
+impl AnswerShuffle {
+    pub fn for_attempt(shuffle_seed: &[u8; 32], quiz: &Survey) -> Self {
+        let questions = &quiz.questions;
+        let mut rng = ShuffleRng::from_seed(shuffle_seed);
+        let mut shuffle = AnswerShuffle {
+            questions_swapped: questions.len() >= QUIZ_ITEMS && rng.next() % 2 == 1,
+            shown_options: [[0; MAX_OPTIONS_PER_QUESTION]; QUIZ_ITEMS],
+            bitmask_answers: [false; QUIZ_ITEMS],
+        };
+
+        for (q, order) in shuffle.shown_options.iter_mut().enumerate() {
+            for (position, option) in order.iter_mut().enumerate() {
+                *option = position as u8;
+            }
+            let Some(question) = questions.get(q) else { continue };
+
+            let grading = quiz.grading_for(question.id);
+            shuffle.bitmask_answers[q] = matches!(grading, GradingScheme::MultiSelect { .. });
+            // Only option indices move; true/false, ratings and numeric answers keep their meaning
+            let option_count = match (&question.question_type, &grading) {
+                (QuestionType::MultipleChoice { options }, GradingScheme::Exact { .. } | GradingScheme::MultiSelect { .. }) => {
+                    options.len().min(MAX_OPTIONS_PER_QUESTION)
+                }
+                _ => 0,
+            };
+            // Fisher-Yates
+            for i in (1..option_count).rev() {
+                let j = (rng.next() % (i as u64 + 1)) as usize;
+                order.swap(i, j);
+            }
+        }
+
+        shuffle
+    }
+
+    /// Packed for quiz_evaluation: bit 0 = questions_swapped, bits 1..=QUIZ_ITEMS =
+    /// bitmask_answers, then 4 bits per shown option holding `position XOR canonical`
+    pub fn code(&self) -> u128 {
+        let mut code = self.questions_swapped as u128;
+        for q in 0..QUIZ_ITEMS {
+            code |= (self.bitmask_answers[q] as u128) << (1 + q);
+            for (position, option) in self.shown_options[q].iter().enumerate() {
+                let shift = 1 + QUIZ_ITEMS + 4 * (q * MAX_OPTIONS_PER_QUESTION + position);
+                code |= ((position as u8 ^ option) as u128) << shift;
+            }
+        }
+        code
+    }
+}
+
+// Shuffled attempts are graded in AnswerShuffle order over quiz.questions and pooled
+// ones in draw order, and both share the QuizAttempt PDA, so a quiz uses one at most
+fn require_single_order_mode(shuffle_answers: bool, has_question_pool: bool) -> Result<()> {
+    require!(!(shuffle_answers && has_question_pool), ErrorCode::ShuffledQuestionPool);
+    Ok(())
+}
+
+// Per-attempt randomness: the most recent slot hash, bound to the quiz and student
+fn attempt_seed(recent_slothashes: &AccountInfo, quiz: &Pubkey, student: &Pubkey) -> Result<[u8; 32]> {
+    // SlotHashes layout: u64 entry count, then (u64 slot, [u8; 32] hash) newest first
+    let slot_hashes = recent_slothashes.try_borrow_data()?;
+    Ok(hashv(&[&slot_hashes[16..48], quiz.as_ref(), student.as_ref()]).to_bytes())
+}
+
+// SplitMix64 over the XOR of the seed's four little-endian words
+struct ShuffleRng(u64);
+
+impl ShuffleRng {
+    fn from_seed(seed: &[u8; 32]) -> Self {
+        let state = seed
+            .chunks_exact(8)
+            .fold(0u64, |acc, word| acc ^ u64::from_le_bytes(word.try_into().unwrap()));
+        ShuffleRng(state)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+// ✅ NEW: Comparison used by a display condition
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum ConditionOperator {
+    Equals,
+    NotEquals,
+    GreaterThan,  // Rating questions only
+    LessThan,     // Rating questions only
+}
+
+// ✅ NEW: "Show this question only if <earlier question> <operator> <value>"
+// Evaluated by the client (client/skip_logic.ts); add_questions only validates the references
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DisplayCondition {
+    pub question_id: u32,  // Must refer to a question earlier in the survey
+    pub operator: ConditionOperator,
+    pub value: u32,        // Option index, 0/1 for TrueFalse, or rating value
+}
+
+// ✅ NEW: Skip logic of one question, stored in Survey.question_display_conditions
+// (beside QuestionData, so questions stored before skip logic still deserialize)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestionDisplayConditions {
+    pub question_id: u32,
+    pub conditions: Vec<DisplayCondition>,  // Shown only if ALL conditions hold
+}
+
+// ✅ NEW: Localized question text and option labels for one language
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestionTranslation {
+    pub language: String,       // BCP 47 language tag, e.g. "es" or "pt-BR"
+    pub question_text: String,
+    pub options: Vec<String>,   // Same order and count as the question's option labels
+}
+
+// ✅ NEW: All translations of one question, stored in Survey.question_translations
+/// Kept beside QuestionData rather than inside it, so questions stored before
+/// translations existed still deserialize.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestionTranslations {
+    pub question_id: u32,
+    pub translations: Vec<QuestionTranslation>,  // Localized variants (answers stay option indices)
+}
+
+// ✅ NEW: How the off-chain question document is committed on-chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum ContentCommitmentScheme {
+    Sha256,      // content_hash = sha256(entire question JSON document)
+    MerkleRoot,  // content_hash = Merkle root over per-question leaves (see question_leaf_hash); required for per-question tallies
+}
+
+// ✅ NEW: On-chain commitment to question content stored off-chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestionContentCommitment {
+    pub scheme: ContentCommitmentScheme,
+    pub content_hash: [u8; 32],  // SHA-256 digest or Merkle root, depending on scheme
+    pub content_uri: String,     // Where clients download the question JSON (https://, ipfs://, ar://)
+    pub question_count: u32,     // Circuits and clients address questions by index < question_count
+}
+
+impl QuestionContentCommitment {
+    /// Bytes the commitment adds over `None` (client-side checks live in client/question_content.ts)
+    pub fn space(content_uri: &str) -> usize {
+        1 // scheme (enum)
+        + 32 // content_hash
+        + STRING_LENGTH_PREFIX + content_uri.len() // content_uri
+        + 4 // question_count
+    }
+
+    /// Whether `question` is entry `index` of a `MerkleRoot` commitment.
+    /// `proof` lists sibling hashes from the leaf up to the root.
+    pub fn proves_question(&self, index: u32, question: &QuestionData, proof: &[[u8; 32]]) -> Result<bool> {
+        if self.scheme != ContentCommitmentScheme::MerkleRoot || index >= self.question_count {
+            return Ok(false);
+        }
+
+        let leaf = question_leaf_hash(index, &question.try_to_vec()?);
+        Ok(merkle_root_from_proof(leaf, index, proof) == self.content_hash)
+    }
+}
+
+// ✅ NEW: One question of a survey's committed content, proven against its MerkleRoot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CommittedQuestion {
+    pub question: QuestionData,  // question.id must equal its index in the document
+    pub proof: Vec<[u8; 32]>,    // Sibling hashes from the leaf up to the root
+}
+
+impl Survey {
+    /// Question `question_id`, from on-chain storage or, for surveys with committed content,
+    /// from `committed` proven against the commitment. Circuits and tallies then reference
+    /// committed questions by index without the survey ever storing them.
+    pub fn resolve_question(&self, question_id: u32, committed: Option<CommittedQuestion>) -> Result<QuestionData> {
+        let Some(content) = &self.question_content else {
+            return self
+                .questions
+                .iter()
+                .find(|question| question.id == question_id)
+                .cloned()
+                .ok_or(ErrorCode::QuestionNotFound.into());
+        };
+
+        let committed = committed.ok_or(ErrorCode::QuestionProofRequired)?;
+        require!(
+            committed.question.id == question_id
+                && content.proves_question(question_id, &committed.question, &committed.proof)?,
+            ErrorCode::InvalidQuestionProof
+        );
+        // add_questions never saw this question, so apply its shape rules here
+        validate_grid_and_ranking_shape(&committed.question.question_type)?;
+
+        Ok(committed.question)
+    }
+}
+
+/// Fold a leaf up to its root; `proof` lists sibling hashes from the leaf upward.
+fn merkle_root_from_proof(leaf: [u8; 32], index: u32, proof: &[[u8; 32]]) -> [u8; 32] {
+    let mut node = leaf;
+    let mut position = index;
+    for sibling in proof {
+        node = if position % 2 == 0 {
+            hashv(&[&[MERKLE_NODE_PREFIX], &node, sibling]).to_bytes()
+        } else {
+            hashv(&[&[MERKLE_NODE_PREFIX], sibling, &node]).to_bytes()
+        };
+        position /= 2;
+    }
+    node
+}
+
+/// Leaf hash for question `index` in a `MerkleRoot` commitment, over the Borsh-encoded QuestionData.
+/// Leaves and inner nodes use different prefixes so a node can't be replayed as a question.
+pub fn question_leaf_hash(index: u32, question: &[u8]) -> [u8; 32] {
+    hashv(&[&[MERKLE_LEAF_PREFIX], &index.to_le_bytes(), question]).to_bytes()
+}
+
+/// Leaf hash for the voter at `index` in a poll's `eligible_voters_root`.
+pub fn voter_leaf_hash(index: u32, voter: &Pubkey) -> [u8; 32] {
+    hashv(&[&[MERKLE_LEAF_PREFIX], &index.to_le_bytes(), voter.as_ref()]).to_bytes()
+}
+
+// Account definitions
+#[account]
+pub struct Survey {
+    pub creator: Pubkey,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub survey_type: SurveyType,
+    pub questions: Vec<QuestionData>,  // ✅ FIXED: Questions as plaintext (public for browsing)
+    pub max_responses: u32,
+    pub current_responses: u32,  // Track current number of responses
+    pub is_active: bool,
+    pub is_publicly_browsable: bool,  // Hide special surveys from public browsing
+    pub modification_count: u8,  // Track modifications (max 1)
+    pub created_at: i64,
+    pub mpc_computation_id: u64,  // ✅ FIXED: Reference to MPC computation
+    
+    // ✅ NICO FIX: Arcium encryption keys for quiz grading
+    pub instructor_arcium_pubkey: [u8; 32],  // Arcium encryption key for instructor
+    pub creator_arcium_pubkey: [u8; 32],     // Arcium encryption key for creator
+
+    // Fields added after launch go last so existing accounts keep their layout
+    pub question_content: Option<QuestionContentCommitment>,  // Off-chain questions (questions stays empty)
+    pub default_language: String,  // BCP 47 tag for question_text; empty on surveys created before translations
+    pub report_decimals: u8,  // Decimal places for averages/percentages computed in MPC (0..=MAX_REPORT_DECIMALS)
+    pub shuffle_answers: bool,  // Quiz only: per-attempt question/option order (see AnswerShuffle)
+    pub data_provisioning: Option<DataProvisioningMethod>,  // Override; pinned by the first response
+    pub mpc_protocol: Option<MPCProtocol>,  // Override; None follows SurveyComputationPreferences
+    pub question_translations: Vec<QuestionTranslations>,  // Keyed by question id; empty on older surveys
+    pub question_display_conditions: Vec<QuestionDisplayConditions>,  // Keyed by question id; none = always shown
+    pub question_grading: Vec<QuestionGrading>,  // Keyed by question id; none = Exact without negative marks
+    
+    // ✅ NICO FIX: Quiz grading data moved to separate QuizGradingDataStorage account
+    // This enables Argument::Account usage and reduces transaction size
+}
+
+#[account]
+pub struct QuizCompletionProof {
+    pub quiz: Pubkey,
+    pub user: Pubkey,  // ✅ FIXED: Direct user reference (simpler and more secure)
+    pub encrypted_score: [u8; 32],  // ✅ FIXED: Encrypted score as bytes
+    pub threshold: u8,  // ✅ FIXED: Threshold as plaintext (public parameter)
+    pub verified: bool,
+    pub verified_at: i64,
+    pub expires_at: i64,  // Expiration timestamp for access control
+    pub mpc_computation_id: u64,  // ✅ FIXED: Reference to MPC computation
+    pub encrypted_verification_result: [u8; 32],  // ✅ FIXED: Store MPC verification result
 }
 
-// Separate instruction for instructor quiz computation
-#[queue_computation_accounts("quiz_evaluation", payer)]
-#[derive(Accounts)]
-#[instruction(instructor_computation_offset: u64)]
-pub struct SubmitQuizInstructor<'info> {
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        space = 9,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(
-        mut,
-        address = derive_mempool_pda!()
-    )]
-    /// CHECK: mempool_account, checked by the arcium program.
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_execpool_pda!()
-    )]
-    /// CHECK: executing_pool, checked by the arcium program.
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_comp_pda!(instructor_computation_offset)
-    )]
-    /// CHECK: computation_account, checked by the arcium program.
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
-    )]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(
-        mut,
-        address = derive_cluster_pda!(mxe_account)
-    )]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(
-        mut,
-        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
-    )]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(
-        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
-    )]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+// ✅ DASHBOARD: User account for personalized dashboard
+#[account]
+pub struct UserAccount {
+    pub creator: Pubkey,
+    pub username: String,
+    pub email: String,
+    pub created_at: i64,
+    pub is_active: bool,
+    pub last_login: i64,
 }
 
-// Separate instruction for creator quiz computation
-#[queue_computation_accounts("quiz_evaluation", payer)]
-#[derive(Accounts)]
-#[instruction(creator_computation_offset: u64)]
-pub struct SubmitQuizCreator<'info> {
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        space = 9,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(
-        mut,
-        address = derive_mempool_pda!()
-    )]
-    /// CHECK: mempool_account, checked by the arcium program.
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_execpool_pda!()
-    )]
-    /// CHECK: executing_pool, checked by the arcium program.
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_comp_pda!(creator_computation_offset)
-    )]
-    /// CHECK: computation_account, checked by the arcium program.
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_EVALUATION)
-    )]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(
-        mut,
-        address = derive_cluster_pda!(mxe_account)
-    )]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(
-        mut,
-        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
-    )]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(
-        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
-    )]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+// ✅ CALLBACK ACCOUNTS: Survey analytics result storage
+#[account]
+#[derive(InitSpace)]
+pub struct SurveyAnalyticsStorage {
+    pub survey: Pubkey,
+    pub creator_analytics: [u8; 32],    // Encrypted full analytics for survey creator
+    pub public_summary: [u8; 32],       // Encrypted limited summary for public viewers
+    pub respondent_feedback: [u8; 32],  // Encrypted personal feedback for respondent
+    pub nonce: u128,                    // Nonce for decryption
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// ✅ CALLBACK ACCOUNTS: Quiz evaluation result storage
+#[account]
+#[derive(InitSpace)]
+pub struct QuizEvaluationStorage {
+    pub quiz: Pubkey,
+    pub student_result: [u8; 32],        // Encrypted quiz result for student
+    pub instructor_analytics: [u8; 32],  // Encrypted analytics for instructor
+    pub creator_feedback: [u8; 32],      // Encrypted feedback for quiz creator
+    pub nonce: u128,                     // Nonce for decryption
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// ✅ CALLBACK ACCOUNTS: Analytics computation result storage
+#[account]
+#[derive(InitSpace)]
+pub struct AnalyticsComputationStorage {
+    pub computation_id: u64,
+    pub analyst_analytics: [u8; 32],      // Encrypted comprehensive analytics for data analyst
+    pub business_metrics: [u8; 32],       // Encrypted business metrics for stakeholders
+    pub research_insights: [u8; 32],      // Encrypted research insights for researchers
+    pub nonce: u128,                      // Nonce for decryption
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// ✅ NEW: Lightweight quiz answers storage (fixes transaction size issue)
+#[account]
+#[derive(InitSpace)]
+pub struct QuizAnswersStorage {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub encrypted_answers: [u8; 64],  // Encrypted answer blob (all answers) - optimized size
+    pub answers_hash: [u8; 32],        // Hash for verification
+    pub submission_timestamp: i64,
+    pub grading_status: GradingStatus,  // Pending, Computing, Completed, Failed
+    pub bump: u8,
+    pub similarity_checked: bool,       // Added to the answer history (see check_answer_similarity)
+    pub shuffle_seed: [u8; 32],         // From the QuizAttempt; unused unless the quiz shuffles answers
+}
+
+// ✅ NICO FIX: Separate account for quiz grading data (enables Argument::Account usage)
+#[account]
+#[derive(InitSpace)]
+pub struct QuizGradingDataStorage {
+    pub quiz: Pubkey,                        // 32 bytes
+    pub encrypted_quiz_data: [u8; 32 * QUIZ_GRADING_VALUES], // 448 bytes - layout documented at QUIZ_GRADING_VALUES
+    pub quiz_data_nonce: u128,               // 16 bytes
+    pub mxe_encryption_pubkey: [u8; 32],     // 32 bytes - Client public key used for ECDH with MXE
+    pub bump: u8,                            // 1 byte
 }
 
-#[derive(Accounts)]
-pub struct CreateSpecialSurvey<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + Survey::INIT_SPACE,
-        seeds = [b"special_survey", creator.key().as_ref(), quiz.key().as_ref()],
-        bump
-    )]
-    pub special_survey: Account<'info, Survey>,
-    pub system_program: Program<'info, System>,
+// ✅ NEW: Grading status enum
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
+pub enum GradingStatus {
+    Pending,
+    Computing,
+    Completed,
+    Failed,
 }
 
-#[queue_computation_accounts("quiz_threshold_check", payer)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct VerifyQuizCompletion<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        space = 8 + QuizCompletionProof::INIT_SPACE,
-        seeds = [b"quiz_completion", payer.key().as_ref(), quiz.key().as_ref()],
-        bump
-    )]
-    pub completion_proof: Account<'info, QuizCompletionProof>,
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(
-        mut,
-        address = derive_mempool_pda!()
-    )]
-    /// CHECK: mempool_account, checked by the arcium program.
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_execpool_pda!()
-    )]
-    /// CHECK: executing_pool, checked by the arcium program.
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_comp_pda!(computation_offset)
-    )]
-    /// CHECK: computation_account, checked by the arcium program.
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_THRESHOLD)
-    )]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(
-        mut,
-        address = derive_cluster_pda!(mxe_account)
-    )]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(
-        mut,
-        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
-    )]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(
-        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
-    )]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+// ✅ CALLBACK ACCOUNTS: Quiz threshold verification result storage
+#[account]
+#[derive(InitSpace)]
+pub struct QuizThresholdStorage {
+    pub quiz: Pubkey,
+    pub user: Pubkey,
+    pub threshold_verification: [u8; 32],  // Encrypted threshold verification result
+    pub access_control: [u8; 32],          // Encrypted access control result
+    pub audit_record: [u8; 32],            // Encrypted audit record
+    pub nonce: u128,                       // Nonce for decryption
+    pub created_at: i64,
+    pub updated_at: i64,
 }
 
-// Separate instruction for student quiz verification
-#[queue_computation_accounts("quiz_threshold_check", payer)]
-#[derive(Accounts)]
-#[instruction(student_computation_offset: u64)]
-pub struct VerifyQuizStudent<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        space = 8 + QuizCompletionProof::INIT_SPACE,
-        seeds = [b"quiz_completion", payer.key().as_ref(), quiz.key().as_ref()],
-        bump
-    )]
-    pub completion_proof: Account<'info, QuizCompletionProof>,
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(
-        mut,
-        address = derive_mempool_pda!()
-    )]
-    /// CHECK: mempool_account, checked by the arcium program.
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_execpool_pda!()
-    )]
-    /// CHECK: executing_pool, checked by the arcium program.
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_comp_pda!(student_computation_offset)
-    )]
-    /// CHECK: computation_account, checked by the arcium program.
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_THRESHOLD)
-    )]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(
-        mut,
-        address = derive_cluster_pda!(mxe_account)
-    )]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(
-        mut,
-        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
-    )]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(
-        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
-    )]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+// ✅ MULTI-USER: Quiz-level aggregation for multiple completion proofs
+#[account]
+#[derive(InitSpace)]
+pub struct QuizAggregation {
+    pub quiz: Pubkey,
+    pub total_attempts: u32,               // Total number of quiz attempts
+    pub successful_completions: u32,       // Number of successful completions
+    pub average_score: u8,                 // Average score (0-100)
+    pub last_updated: i64,                 // Last update timestamp
+    pub created_at: i64,                   // Creation timestamp
 }
 
-// Separate instruction for access controller quiz verification
-#[queue_computation_accounts("quiz_threshold_check", payer)]
-#[derive(Accounts)]
-#[instruction(access_controller_computation_offset: u64)]
-pub struct VerifyQuizAccessController<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        space = 8 + QuizCompletionProof::INIT_SPACE,
-        seeds = [b"quiz_completion", payer.key().as_ref(), quiz.key().as_ref()],
-        bump
-    )]
-    pub completion_proof: Account<'info, QuizCompletionProof>,
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(
-        mut,
-        address = derive_mempool_pda!()
-    )]
-    /// CHECK: mempool_account, checked by the arcium program.
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_execpool_pda!()
-    )]
-    /// CHECK: executing_pool, checked by the arcium program.
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_comp_pda!(access_controller_computation_offset)
-    )]
-    /// CHECK: computation_account, checked by the arcium program.
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_THRESHOLD)
-    )]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(
-        mut,
-        address = derive_cluster_pda!(mxe_account)
-    )]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(
-        mut,
-        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
-    )]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(
-        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
-    )]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+// ✅ FIXED: Application preferences for survey/quiz computations
+// Note: This is application-level metadata, not Arcium MXE configuration
+#[account]
+pub struct SurveyComputationPreferences {
+    pub admin: Pubkey,
+    pub preferred_encryption_scheme: EncryptionScheme,
+    pub preferred_data_provisioning: DataProvisioningMethod,
+    pub preferred_mpc_protocol: MPCProtocol,
+    pub configured_at: i64,
+    pub is_active: bool,
+    pub bump: u8,
 }
 
-// Separate instruction for auditor quiz verification
-#[queue_computation_accounts("quiz_threshold_check", payer)]
-#[derive(Accounts)]
-#[instruction(auditor_computation_offset: u64)]
-pub struct VerifyQuizAuditor<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        space = 8 + QuizCompletionProof::INIT_SPACE,
-        seeds = [b"quiz_completion", payer.key().as_ref(), quiz.key().as_ref()],
-        bump
-    )]
-    pub completion_proof: Account<'info, QuizCompletionProof>,
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(
-        mut,
-        address = derive_mempool_pda!()
-    )]
-    /// CHECK: mempool_account, checked by the arcium program.
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_execpool_pda!()
-    )]
-    /// CHECK: executing_pool, checked by the arcium program.
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_comp_pda!(auditor_computation_offset)
-    )]
-    /// CHECK: computation_account, checked by the arcium program.
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUIZ_THRESHOLD)
-    )]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(
-        mut,
-        address = derive_cluster_pda!(mxe_account)
-    )]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(
-        mut,
-        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
-    )]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(
-        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
-    )]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+// ✅ NEW: Keeps one computation at a time on a per-question tally
+/// Taken when an answer is queued and released by its callback, so two answers never
+/// read the same tally ciphertexts and one overwrite the other.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct TallyLock {
+    pub pending_receipt: Option<Pubkey>,  // Receipt whose computation is in flight
+    pub pending_since: i64,
 }
 
-#[queue_computation_accounts("survey_analytics", payer)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct SubmitSpecialSurveyResponse<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut)]
-    pub special_survey: Account<'info, Survey>,
-    #[account(mut)]
-    pub quiz: Account<'info, Survey>,
-    #[account(mut)]
-    pub completion_proof: Account<'info, QuizCompletionProof>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        space = 9,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    #[account(
-        mut,
-        address = derive_mxe_pda!()
-    )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(
-        mut,
-        address = derive_mempool_pda!()
-    )]
-    /// CHECK: mempool_account, checked by the arcium program.
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_execpool_pda!()
-    )]
-    /// CHECK: executing_pool, checked by the arcium program.
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        address = derive_comp_pda!(computation_offset)
-    )]
-    /// CHECK: computation_account, checked by the arcium program.
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(
-        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SURVEY_ANALYTICS)
-    )]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(
-        mut,
-        address = derive_cluster_pda!(mxe_account)
-    )]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(
-        mut,
-        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
-    )]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(
-        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
-    )]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+impl TallyLock {
+    /// A lock older than TALLY_LOCK_TIMEOUT_SECONDS belongs to a lost computation
+    pub fn is_held(&self, now: i64) -> bool {
+        self.pending_receipt.is_some() && now < self.pending_since + TALLY_LOCK_TIMEOUT_SECONDS
+    }
+
+    pub fn acquire(&mut self, receipt: Pubkey, now: i64) -> Result<()> {
+        require!(!self.is_held(now), ErrorCode::TallyBusy);
+        self.pending_receipt = Some(receipt);
+        self.pending_since = now;
+        Ok(())
+    }
+
+    /// Output for any other receipt was computed over a tally that has since moved on
+    pub fn release(&mut self, receipt: Pubkey) -> Result<()> {
+        require!(
+            self.pending_receipt == Some(receipt),
+            ErrorCode::StaleTallyComputation
+        );
+        self.pending_receipt = None;
+        Ok(())
+    }
 }
 
-#[derive(Accounts)]
-pub struct DeleteSurvey<'info> {
-    #[account(mut)]
-    pub survey: Account<'info, Survey>,
-    pub creator: Signer<'info>,
+/// Each reveal of a running tally must cover at least `min_new` answers the previous
+/// one didn't, so differencing two reveals never isolates fewer respondents than that
+fn check_reveal_interval(submissions: u32, last_reveal_submissions: u32, min_new: u32) -> Result<()> {
+    require!(
+        submissions >= last_reveal_submissions.saturating_add(min_new),
+        ErrorCode::TooFewNewSubmissions
+    );
+    Ok(())
 }
 
-// ✅ FIXED: Account structure for initializing Sign PDA Account
-#[derive(Accounts)]
-pub struct InitSignPda<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init,
-        space = 9,
-        payer = payer,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, SignerAccount>,
-    pub system_program: Program<'info, System>,
+// ✅ NEW: One wallet's answer to a per-question tally (seeded by the tally and the wallet)
+/// Also the per-wallet receipt of a CreatorOnly survey, with `tally` set to the survey.
+#[account]
+#[derive(InitSpace)]
+pub struct TallyReceipt {
+    pub tally: Pubkey,
+    pub respondent: Pubkey,
+    pub status: BallotStatus,
+    pub submitted_at: i64,
+    pub bump: u8,
 }
 
-// ✅ ADDED: Account structures for new multi-recipient computations
+impl TallyReceipt {
+    /// Like PollBallot::queue: an answer can be re-sent only while it was never recorded
+    pub fn queue(&mut self, tally: Pubkey, respondent: Pubkey, now: i64, bump: u8) -> Result<()> {
+        require!(self.status != BallotStatus::Recorded, ErrorCode::AlreadyResponded);
+        self.tally = tally;
+        self.respondent = respondent;
+        self.status = BallotStatus::Queued;
+        self.submitted_at = now;
+        self.bump = bump;
+        Ok(())
+    }
+}
+
+// ✅ NEW: Encrypted running tally for a Matrix question (Enc<Mxe, MatrixTally>)
+#[account]
+#[derive(InitSpace)]
+pub struct MatrixTallyStorage {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub rows: u8,                 // Public grid shape, passed to the circuit as plaintext
+    pub columns: u8,
+    pub submissions: u32,         // Accumulations applied so far
+    pub bump: u8,
+    pub tally_nonce: u128,
+    pub encrypted_tally: [[u8; 32]; MATRIX_TALLY_CIPHERTEXTS],
+    pub lock: TallyLock,
+}
+
+impl MatrixTallyStorage {
+    // Discriminator + survey + question_id + rows + columns + submissions + bump + tally_nonce
+    pub const ENCRYPTED_TALLY_OFFSET: u32 = 8 + 32 + 4 + 1 + 1 + 4 + 1 + 16;
+}
 
+// ✅ NEW: Encrypted running Borda tally for a Ranking question (Enc<Mxe, RankingTally>)
+#[account]
+#[derive(InitSpace)]
+pub struct RankingTallyStorage {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub option_count: u8,         // Public option count, passed to the circuit as plaintext
+    pub submissions: u32,
+    pub bump: u8,
+    pub tally_nonce: u128,
+    pub encrypted_tally: [[u8; 32]; RANKING_TALLY_CIPHERTEXTS],
+    pub lock: TallyLock,
+}
 
+impl RankingTallyStorage {
+    // Discriminator + survey + question_id + option_count + submissions + bump + tally_nonce
+    pub const ENCRYPTED_TALLY_OFFSET: u32 = 8 + 32 + 4 + 1 + 4 + 1 + 16;
+}
 
+// ✅ NEW: Encrypted promoter/passive/detractor counts (Enc<Mxe, NpsTally>)
+#[account]
+#[derive(InitSpace)]
+pub struct NpsTallyStorage {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub min_responses: u32,       // Reveal threshold, enforced inside MPC
+    pub submissions: u32,
+    pub bump: u8,
+    pub tally_nonce: u128,
+    pub encrypted_tally: [[u8; 32]; NPS_TALLY_CIPHERTEXTS],
+    pub lock: TallyLock,
+    pub last_reveal_submissions: u32,  // submissions when reveal_nps was last queued
+}
 
+impl NpsTallyStorage {
+    // Discriminator + survey + question_id + min_responses + submissions + bump + tally_nonce
+    pub const ENCRYPTED_TALLY_OFFSET: u32 = 8 + 32 + 4 + 4 + 4 + 1 + 16;
+}
 
+// ✅ NEW: Encrypted bucket counts behind median / percentile reporting (Enc<Mxe, Histogram>)
+#[account]
+#[derive(InitSpace)]
+pub struct HistogramStorage {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub bucket_min: u32,          // Public lower edge of bucket 0
+    pub bucket_width: u32,        // Public bucket width
+    pub min_responses: u32,       // Reveal threshold, enforced inside MPC
+    pub submissions: u32,
+    pub bump: u8,
+    pub counts_nonce: u128,
+    pub encrypted_counts: [[u8; 32]; HISTOGRAM_BUCKETS],
+    pub lock: TallyLock,
+    pub last_reveal_submissions: u32,  // submissions when reveal_percentiles was last queued
+}
 
+impl HistogramStorage {
+    // Discriminator + survey + question_id + bucket_min + bucket_width + min_responses + submissions + bump + counts_nonce
+    pub const ENCRYPTED_COUNTS_OFFSET: u32 = 8 + 32 + 4 + 4 + 4 + 4 + 4 + 1 + 16;
+}
 
+// ✅ NEW: Encrypted contingency table for a question pair (Enc<Mxe, CrosstabTable>)
+#[account]
+#[derive(InitSpace)]
+pub struct CrosstabStorage {
+    pub survey: Pubkey,
+    pub question_a: u32,          // Rows
+    pub question_b: u32,          // Columns
+    pub offset_a: u32,            // Rating min (0 for MultipleChoice)
+    pub categories_a: u8,
+    pub offset_b: u32,
+    pub categories_b: u8,
+    pub suppression_threshold: u32,  // Smallest non-zero count revealed as-is
+    pub submissions: u32,
+    pub bump: u8,
+    pub table_nonce: u128,
+    pub encrypted_table: [[u8; 32]; CROSSTAB_CELLS],
+    pub lock: TallyLock,
+}
 
-// ✅ REVERTED: Use proper Arcium macro-generated account structures
-// These will be automatically generated by the  macro
+impl CrosstabStorage {
+    // Discriminator + survey + question_a + question_b + offset_a + categories_a + offset_b
+    // + categories_b + suppression_threshold + submissions + bump + table_nonce
+    pub const ENCRYPTED_TABLE_OFFSET: u32 = 8 + 32 + 4 + 4 + 4 + 1 + 4 + 1 + 4 + 4 + 1 + 16;
+}
 
-// ✅ REVERTED: Using proper Arcium macro-based computation definition initialization
+// ✅ NEW: Encrypted running sums behind the Pearson correlation (Enc<Mxe, RunningSums>)
+#[account]
+#[derive(InitSpace)]
+pub struct CorrelationSumsStorage {
+    pub survey: Pubkey,
+    pub bump: u8,
+    pub sums_nonce: u128,
+    pub encrypted_sums: [[u8; 32]; CORRELATION_SUMS_CIPHERTEXTS],
+    pub submissions: u32,  // Respondents folded into the sums
+    pub lock: TallyLock,
+}
 
-// Data structures
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub enum SurveyType {
-    Basic,
-    Quiz {
-        time_per_question: Option<u64>,
-        total_time_limit: Option<u64>,
-        passing_threshold: u8,
-        special_survey_enabled: bool,
-        completion_proof_expiration_value: u32,  // Expiration value
-        completion_proof_expiration_unit: ExpirationUnit,  // Expiration unit (minutes, hours, days)
-    },
-    Special,
+impl CorrelationSumsStorage {
+    // Discriminator + survey + bump + sums_nonce
+    pub const ENCRYPTED_SUMS_OFFSET: u32 = 8 + 32 + 1 + 16;
+}
+
+// ✅ NEW: Encrypted per-question totals behind item analysis (Enc<Mxe, ItemStats>)
+#[account]
+#[derive(InitSpace)]
+pub struct ItemStatsStorage {
+    pub quiz: Pubkey,
+    pub bump: u8,
+    pub stats_nonce: u128,
+    pub encrypted_stats: [[u8; 32]; ITEM_STATS_CIPHERTEXTS],
+    pub graded_attempts: u32,          // Attempts folded into the stats
+    pub lock: TallyLock,               // Held by the attempt being graded
+    pub last_reveal_submissions: u32,  // graded_attempts when reveal_item_analysis was last queued
+}
+
+impl ItemStatsStorage {
+    // Discriminator + quiz + bump + stats_nonce
+    pub const ENCRYPTED_STATS_OFFSET: u32 = 8 + 32 + 1 + 16;
+}
+
+// ✅ NEW: Encrypted score buckets and pseudonymous top K for a quiz (Enc<Mxe, Leaderboard>)
+#[account]
+#[derive(InitSpace)]
+pub struct LeaderboardStorage {
+    pub quiz: Pubkey,
+    pub entries: u32,        // Students on the board (public: every entry is its own PDA)
+    pub bump: u8,
+    pub leaderboard_nonce: u128,
+    pub encrypted_leaderboard: [[u8; 32]; LEADERBOARD_CIPHERTEXTS],
+    pub lock: TallyLock,               // Held by the entry being recorded
+    pub last_reveal_entries: u32,      // entries when publish_leaderboard was last queued
+}
+
+impl LeaderboardStorage {
+    // Discriminator + quiz + entries + bump + leaderboard_nonce
+    pub const ENCRYPTED_LEADERBOARD_OFFSET: u32 = 8 + 32 + 4 + 1 + 16;
+}
+
+// ✅ NEW: One student's encrypted leaderboard score and pseudonym (Enc<Mxe, LeaderboardEntry>)
+#[account]
+#[derive(InitSpace)]
+pub struct LeaderboardEntryStorage {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub bump: u8,
+    pub entry_nonce: u128,
+    pub encrypted_entry: [[u8; 32]; LEADERBOARD_ENTRY_CIPHERTEXTS],
+    pub status: BallotStatus,  // Recorded once the board accepted the entry
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub enum ExpirationUnit {
-    Minutes,
-    Hours,
-    Days,
+impl LeaderboardEntryStorage {
+    // Discriminator + quiz + student + bump + entry_nonce
+    pub const ENCRYPTED_ENTRY_OFFSET: u32 = 8 + 32 + 32 + 1 + 16;
 }
 
-// ✅ SECURE: MXE Configuration enums for Arcium compatibility
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub enum EncryptionScheme {
-    Rescue,  // Arcium's preferred encryption scheme
-    AES,      // Alternative encryption scheme
+// ✅ NEW: MXE-owned copy of the latest submissions (Enc<Mxe, AnswerHistory>)
+#[account]
+#[derive(InitSpace)]
+pub struct AnswerHistoryStorage {
+    pub quiz: Pubkey,
+    pub similarity_threshold: u8,  // Shared wrong answers needed to flag a pair
+    pub checked: u32,              // Submissions compared so far
+    pub bump: u8,
+    pub history_nonce: u128,
+    pub encrypted_history: [[u8; 32]; ANSWER_HISTORY_CIPHERTEXTS],
+    pub lock: TallyLock,           // One comparison in flight at a time
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub enum DataProvisioningMethod {
-    Direct,   // Direct data provisioning for real-time computations
-    Batch,    // Batch data provisioning for efficient processing
+impl AnswerHistoryStorage {
+    // Discriminator + quiz + similarity_threshold + checked + bump + history_nonce
+    pub const ENCRYPTED_HISTORY_OFFSET: u32 = 8 + 32 + 1 + 4 + 1 + 16;
 }
 
-// ✅ FIXED: Add MPC protocol enum for Arcium compatibility
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub enum MPCProtocol {
-    Cerberus,    // Arcium's Cerberus MPC protocol
-    Manticore,   // Arcium's Manticore MPC protocol
+// ✅ NEW: Answer keys for a quiz's question bank (questions[0..pool_size] on the quiz)
+#[account]
+#[derive(InitSpace)]
+pub struct QuestionPoolStorage {
+    pub quiz: Pubkey,
+    pub encrypted_pool_keys: [[u8; 32]; POOL_KEYS_VALUES],  // Enc<Mxe, PoolKeys> - layout at POOL_KEYS_VALUES
+    pub pool_keys_nonce: u128,
+    pub mxe_encryption_pubkey: [u8; 32],  // Client public key used for ECDH with MXE
+    pub pool_size: u8,
+    pub bump: u8,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub enum QuestionType {
-    MultipleChoice { options: Vec<String> },
-    TrueFalse,
-    TextInput,
-    Rating { min: u8, max: u8 },
-    TimeLimit { duration: u64 },
+impl QuestionPoolStorage {
+    // Discriminator + quiz
+    pub const ENCRYPTED_POOL_KEYS_OFFSET: u32 = 8 + 32;
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct QuestionData {
-    pub id: u32,
-    pub question_text: String,
-    pub question_type: QuestionType,
-    pub required: bool,
-    pub points: u8,
+// ✅ NEW: The bank questions one student was dealt; answers follow this order
+#[account]
+#[derive(InitSpace)]
+pub struct QuizAttempt {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub drawn_questions: [u8; QUIZ_ITEMS],  // Indices into the quiz's questions
+    pub started_at: i64,
+    pub bump: u8,
+    pub shuffle_seed: [u8; 32],  // AnswerShuffle seed, fixed when the attempt starts
 }
 
-// Account definitions
+// ✅ NEW: Answer keys for an adaptive quiz, one per (step, tier) cell of the grid
 #[account]
-pub struct Survey {
-    pub creator: Pubkey,
-    pub slug: String,
-    pub title: String,
-    pub description: String,
-    pub survey_type: SurveyType,
-    pub questions: Vec<QuestionData>,  // ✅ FIXED: Questions as plaintext (public for browsing)
-    pub max_responses: u32,
-    pub current_responses: u32,  // Track current number of responses
-    pub is_active: bool,
-    pub is_publicly_browsable: bool,  // Hide special surveys from public browsing
-    pub modification_count: u8,  // Track modifications (max 1)
-    pub created_at: i64,
-    pub mpc_computation_id: u64,  // ✅ FIXED: Reference to MPC computation
-    
-    // ✅ NICO FIX: Arcium encryption keys for quiz grading
-    pub instructor_arcium_pubkey: [u8; 32],  // Arcium encryption key for instructor
-    pub creator_arcium_pubkey: [u8; 32],     // Arcium encryption key for creator
-    
-    // ✅ NICO FIX: Quiz grading data moved to separate QuizGradingDataStorage account
-    // This enables Argument::Account usage and reduces transaction size
+#[derive(InitSpace)]
+pub struct AdaptiveBankStorage {
+    pub quiz: Pubkey,
+    pub encrypted_adaptive_keys: [[u8; 32]; ADAPTIVE_BANK_SIZE],  // Enc<Mxe, AdaptiveKeys>
+    pub adaptive_keys_nonce: u128,
+    pub mxe_encryption_pubkey: [u8; 32],  // Client public key used for ECDH with MXE
+    pub bump: u8,
 }
 
+impl AdaptiveBankStorage {
+    // Discriminator + quiz
+    pub const ENCRYPTED_ADAPTIVE_KEYS_OFFSET: u32 = 8 + 32;
+}
+
+// ✅ NEW: One student's adaptive attempt; score and tier stay MXE-encrypted
 #[account]
-pub struct QuizCompletionProof {
+#[derive(InitSpace)]
+pub struct AdaptiveAttempt {
     pub quiz: Pubkey,
-    pub user: Pubkey,  // ✅ FIXED: Direct user reference (simpler and more secure)
-    pub encrypted_score: [u8; 32],  // ✅ FIXED: Encrypted score as bytes
-    pub threshold: u8,  // ✅ FIXED: Threshold as plaintext (public parameter)
-    pub verified: bool,
-    pub verified_at: i64,
-    pub expires_at: i64,  // Expiration timestamp for access control
-    pub mpc_computation_id: u64,  // ✅ FIXED: Reference to MPC computation
-    pub encrypted_verification_result: [u8; 32],  // ✅ FIXED: Store MPC verification result
+    pub student: Pubkey,
+    pub encrypted_state: [[u8; 32]; ADAPTIVE_STATE_CIPHERTEXTS],  // Enc<Mxe, AdaptiveState>
+    pub state_nonce: u128,
+    pub steps_answered: u8,
+    pub pending: bool,     // A computation is in flight; state is stale until its callback
+    pub finished: bool,
+    pub bump: u8,
 }
 
-// ✅ DASHBOARD: User account for personalized dashboard
-#[account]
-pub struct UserAccount {
-    pub creator: Pubkey,
-    pub username: String,
-    pub email: String,
-    pub created_at: i64,
-    pub is_active: bool,
-    pub last_login: i64,
+impl AdaptiveAttempt {
+    // Discriminator + quiz + student
+    pub const ENCRYPTED_STATE_OFFSET: u32 = 8 + 32 + 32;
 }
 
-// ✅ CALLBACK ACCOUNTS: Survey analytics result storage
+// ✅ NEW: Running encrypted tally of a poll (Enc<Mxe, PollTally>) and, once published, its result
 #[account]
 #[derive(InitSpace)]
-pub struct SurveyAnalyticsStorage {
+pub struct PollTallyStorage {
     pub survey: Pubkey,
-    pub creator_analytics: [u8; 32],    // Encrypted full analytics for survey creator
-    pub public_summary: [u8; 32],       // Encrypted limited summary for public viewers
-    pub respondent_feedback: [u8; 32],  // Encrypted personal feedback for respondent
-    pub nonce: u128,                    // Nonce for decryption
-    pub created_at: i64,
-    pub updated_at: i64,
+    pub bump: u8,
+    pub tally_nonce: u128,
+    pub encrypted_tally: [[u8; 32]; POLL_TALLY_CIPHERTEXTS],
+    pub ballots_recorded: u32,             // Ballots folded into the tally, spoiled ones included
+    pub pending_ballot: Option<Pubkey>,    // Ballot whose computation is in flight
+    pub pending_since: i64,
+    pub results_published: bool,
+    pub total_votes: u32,                  // Valid ballots; ballots_recorded - total_votes were spoiled
+    pub final_counts: [u32; MAX_POLL_OPTIONS],
 }
 
-// ✅ CALLBACK ACCOUNTS: Quiz evaluation result storage
+impl PollTallyStorage {
+    // Discriminator + survey + bump + tally_nonce
+    pub const ENCRYPTED_TALLY_OFFSET: u32 = 8 + 32 + 1 + 16;
+}
+
+/// A pending ballot holds a poll tally until its callback lands or it times out
+fn poll_lock_held(pending_ballot: Option<Pubkey>, pending_since: i64, now: i64) -> bool {
+    pending_ballot.is_some() && now < pending_since + POLL_LOCK_TIMEOUT_SECONDS
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
+pub enum BallotStatus {
+    Queued,    // Sent to MPC; retryable if the computation is lost
+    Recorded,  // Folded into the tally
+}
+
+// ✅ NEW: One wallet's ballot in a poll; the ballot itself only exists inside MPC
 #[account]
 #[derive(InitSpace)]
-pub struct QuizEvaluationStorage {
-    pub quiz: Pubkey,
-    pub student_result: [u8; 32],        // Encrypted quiz result for student
-    pub instructor_analytics: [u8; 32],  // Encrypted analytics for instructor
-    pub creator_feedback: [u8; 32],      // Encrypted feedback for quiz creator
-    pub nonce: u128,                     // Nonce for decryption
-    pub created_at: i64,
-    pub updated_at: i64,
+pub struct PollBallot {
+    pub survey: Pubkey,
+    pub voter: Pubkey,
+    pub ballot_commitment: [u8; 32],  // sha256(voter_pub_key || nonce_le || ciphertext)
+    pub status: BallotStatus,
+    pub cast_at: i64,
+    pub bump: u8,
 }
 
-// ✅ CALLBACK ACCOUNTS: Analytics computation result storage
+impl PollBallot {
+    pub fn commitment(voter_pub_key: &[u8; 32], nonce: u128, ciphertext: &[u8; 32]) -> [u8; 32] {
+        hashv(&[voter_pub_key, &nonce.to_le_bytes(), ciphertext]).to_bytes()
+    }
+
+    /// Record a ballot about to be sent to MPC. A wallet's ballot can be re-sent
+    /// only while it was never recorded (its earlier computation was lost).
+    pub fn queue(&mut self, survey: Pubkey, voter: Pubkey, commitment: [u8; 32], now: i64, bump: u8) -> Result<()> {
+        require!(self.status != BallotStatus::Recorded, ErrorCode::AlreadyVoted);
+        self.survey = survey;
+        self.voter = voter;
+        self.ballot_commitment = commitment;
+        self.status = BallotStatus::Queued;
+        self.cast_at = now;
+        self.bump = bump;
+        Ok(())
+    }
+}
+
+// ✅ NEW: Encrypted ranked-choice ballots, stored as a count per distinct ranking (Enc<Mxe, RankedTally>)
 #[account]
 #[derive(InitSpace)]
-pub struct AnalyticsComputationStorage {
-    pub computation_id: u64,
-    pub analyst_analytics: [u8; 32],      // Encrypted comprehensive analytics for data analyst
-    pub business_metrics: [u8; 32],       // Encrypted business metrics for stakeholders
-    pub research_insights: [u8; 32],      // Encrypted research insights for researchers
-    pub nonce: u128,                      // Nonce for decryption
-    pub created_at: i64,
-    pub updated_at: i64,
+pub struct RankedTallyStorage {
+    pub survey: Pubkey,
+    pub bump: u8,
+    pub tally_nonce: u128,
+    pub encrypted_tally: [[u8; 32]; IRV_PROFILES],
+    pub public_viewer_pubkey: [u8; 32],    // Results are encrypted to this published key
+    pub ballots_recorded: u32,
+    pub pending_ballot: Option<Pubkey>,    // Ballot whose computation is in flight
+    pub pending_since: i64,
+    pub results_published: bool,
 }
 
-// ✅ NEW: Lightweight quiz answers storage (fixes transaction size issue)
+impl RankedTallyStorage {
+    // Discriminator + survey + bump + tally_nonce
+    pub const ENCRYPTED_TALLY_OFFSET: u32 = 8 + 32 + 1 + 16;
+}
+
+// ✅ NEW: Encrypted counts for one TextInput question (Enc<Mxe, TextTally>)
 #[account]
 #[derive(InitSpace)]
-pub struct QuizAnswersStorage {
-    pub quiz: Pubkey,
-    pub student: Pubkey,
-    pub encrypted_answers: [u8; 64],  // Encrypted answer blob (all answers) - optimized size
-    pub answers_hash: [u8; 32],        // Hash for verification
-    pub submission_timestamp: i64,
-    pub grading_status: GradingStatus,  // Pending, Computing, Completed, Failed
+pub struct TextTallyStorage {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub min_responses: u32,       // Reveal threshold, enforced inside MPC
+    pub submissions: u32,
     pub bump: u8,
+    pub tally_nonce: u128,
+    pub encrypted_tally: [[u8; 32]; TEXT_TALLY_CIPHERTEXTS],
+    pub lock: TallyLock,
 }
 
-// ✅ NICO FIX: Separate account for quiz grading data (enables Argument::Account usage)
+impl TextTallyStorage {
+    // Discriminator + survey + question_id + min_responses + submissions + bump + tally_nonce
+    pub const ENCRYPTED_TALLY_OFFSET: u32 = 8 + 32 + 4 + 4 + 4 + 1 + 16;
+}
+
+// Where a text answer's ciphertext lives
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum TextResponseBody {
+    Inline { ciphertexts: Vec<[u8; 32]> },                    // Up to MAX_INLINE_TEXT_CIPHERTEXTS blocks
+    OffChain { content_uri: String, ciphertext_hash: [u8; 32] },
+}
+
+// ✅ NEW: One respondent's answer to a TextInput question, readable only by the creator
+/// Encrypted with Rescue under the x25519 secret shared by `respondent_pub_key` and
+/// `Survey.creator_arcium_pubkey`; see client/text_responses.ts for the block layout.
 #[account]
-#[derive(InitSpace)]
-pub struct QuizGradingDataStorage {
-    pub quiz: Pubkey,                        // 32 bytes
-    pub encrypted_quiz_data: [u8; 256],      // 256 bytes - [correct1, correct2, points1, points2, threshold, stat1, stat2, stat3]
-    pub quiz_data_nonce: u128,               // 16 bytes
-    pub mxe_encryption_pubkey: [u8; 32],     // 32 bytes - Client public key used for ECDH with MXE
-    pub bump: u8,                            // 1 byte
+pub struct TextResponse {
+    pub survey: Pubkey,
+    pub respondent: Pubkey,
+    pub question_id: u32,
+    pub respondent_pub_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertext_hash: [u8; 32],     // sha256 over the ciphertext blocks, inline or off-chain
+    pub content_uri: String,           // Off-chain ciphertext; empty when stored inline
+    pub ciphertexts: Vec<[u8; 32]>,    // Inline ciphertext; empty when stored off-chain
+    pub submitted_at: i64,
+    pub keywords_submitted: bool,      // Set once accumulate_keywords counted this answer
+    pub bump: u8,
 }
 
-// ✅ NEW: Grading status enum
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
-pub enum GradingStatus {
-    Pending,
-    Computing,
-    Completed,
-    Failed,
+impl TextResponse {
+    pub const MAX_SPACE: usize = PUBKEY_LENGTH // survey
+        + PUBKEY_LENGTH // respondent
+        + 4 // question_id
+        + 32 // respondent_pub_key
+        + 16 // nonce
+        + 32 // ciphertext_hash
+        + STRING_LENGTH_PREFIX + MAX_CONTENT_URI_LENGTH // content_uri
+        + 4 + 32 * MAX_INLINE_TEXT_CIPHERTEXTS // ciphertexts
+        + 8 // submitted_at
+        + 1 // keywords_submitted
+        + 1; // bump
+
+    pub fn ciphertext_hash(ciphertexts: &[[u8; 32]]) -> [u8; 32] {
+        let blocks: Vec<&[u8]> = ciphertexts.iter().map(|block| block.as_slice()).collect();
+        hashv(&blocks).to_bytes()
+    }
 }
 
-// ✅ CALLBACK ACCOUNTS: Quiz threshold verification result storage
+// ✅ NEW: Encrypted per-keyword answer counts for one TextInput question (Enc<Mxe, KeywordTally>)
+/// The vocabulary itself stays off-chain; `vocabulary_hash` pins the list clients
+/// tokenize against (see client/text_responses.ts).
 #[account]
 #[derive(InitSpace)]
-pub struct QuizThresholdStorage {
-    pub quiz: Pubkey,
-    pub user: Pubkey,
-    pub threshold_verification: [u8; 32],  // Encrypted threshold verification result
-    pub access_control: [u8; 32],          // Encrypted access control result
-    pub audit_record: [u8; 32],            // Encrypted audit record
-    pub nonce: u128,                       // Nonce for decryption
-    pub created_at: i64,
-    pub updated_at: i64,
+pub struct KeywordTallyStorage {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub vocabulary_size: u8,
+    pub vocabulary_hash: [u8; 32],
+    pub min_responses: u32,       // Reveal threshold, enforced inside MPC
+    pub submissions: u32,
+    pub bump: u8,
+    pub tally_nonce: u128,
+    pub encrypted_tally: [[u8; 32]; KEYWORD_TALLY_CIPHERTEXTS],
+    pub lock: TallyLock,
 }
 
-// ✅ MULTI-USER: Quiz-level aggregation for multiple completion proofs
+impl KeywordTallyStorage {
+    // Discriminator + survey + question_id + vocabulary_size + vocabulary_hash
+    // + min_responses + submissions + bump + tally_nonce
+    pub const ENCRYPTED_TALLY_OFFSET: u32 = 8 + 32 + 4 + 1 + 32 + 4 + 4 + 1 + 16;
+}
+
+// ✅ NEW: A respondent's answers to a CreatorOnly survey, readable only by the creator
+/// Encrypted with Rescue under the x25519 secret shared by `respondent_pub_key` and
+/// `Survey.creator_arcium_pubkey`, one ciphertext per answer in question order (see
+/// client/creator_responses.ts). Sized to the answer count, so rent stays small.
+#[account]
+pub struct CreatorEncryptedResponse {
+    pub survey: Pubkey,
+    pub respondent_pub_key: [u8; 32],
+    pub nonce: u128,
+    pub submitted_at: i64,
+    pub bump: u8,
+    pub ciphertexts: Vec<[u8; 32]>,
+}
+
+impl CreatorEncryptedResponse {
+    pub fn space(ciphertext_count: usize) -> usize {
+        PUBKEY_LENGTH // survey
+            + 32 // respondent_pub_key
+            + 16 // nonce
+            + 8 // submitted_at
+            + 1 // bump
+            + 4 + 32 * ciphertext_count // ciphertexts
+    }
+}
+
+// ✅ NEW: Response buffer and encrypted running totals of a Batch survey
+/// Each slot holds one respondent's Enc<Shared, [u32; 6]>; the circuit reads the
+/// ciphertexts straight from this account. Slots are cleared when their batch lands.
 #[account]
 #[derive(InitSpace)]
-pub struct QuizAggregation {
-    pub quiz: Pubkey,
-    pub total_attempts: u32,               // Total number of quiz attempts
-    pub successful_completions: u32,       // Number of successful completions
-    pub average_score: u8,                 // Average score (0-100)
-    pub last_updated: i64,                 // Last update timestamp
-    pub created_at: i64,                   // Creation timestamp
+pub struct ResponseBatchBuffer {
+    pub survey: Pubkey,
+    pub buffered: u8,                // Slots in use
+    pub processing: bool,            // A batch is queued; buffering waits for its callback or timeout
+    pub processing_since: i64,
+    pub batches_processed: u32,
+    pub processed_responses: u32,
+    pub bump: u8,
+    pub totals_nonce: u128,
+    pub encrypted_totals: [[u8; 32]; BATCH_TOTALS_CIPHERTEXTS],
+    pub respondent_pub_keys: [[u8; 32]; RESPONSE_BATCH_SIZE],
+    pub respondent_nonces: [u128; RESPONSE_BATCH_SIZE],
+    pub encrypted_responses: [[[u8; 32]; BATCH_RESPONSE_CIPHERTEXTS]; RESPONSE_BATCH_SIZE],
+    pub batches_queued: u32,            // Bumped by every start_batch; the circuit echoes it back
+    pub last_revealed_responses: u32,   // processed_responses when reveal_batch_analytics was last queued
+}
+
+impl ResponseBatchBuffer {
+    // Discriminator + survey + buffered + processing + processing_since + batches_processed
+    // + processed_responses + bump + totals_nonce
+    pub const ENCRYPTED_TOTALS_OFFSET: u32 = 8 + 32 + 1 + 1 + 8 + 4 + 4 + 1 + 16;
+    pub const ENCRYPTED_RESPONSES_OFFSET: u32 = Self::ENCRYPTED_TOTALS_OFFSET
+        + (32 * BATCH_TOTALS_CIPHERTEXTS) as u32
+        + (32 * RESPONSE_BATCH_SIZE) as u32  // respondent_pub_keys
+        + (16 * RESPONSE_BATCH_SIZE) as u32; // respondent_nonces
+
+    /// A queued batch holds the buffer until its callback lands or it times out
+    pub fn is_busy(&self, now: i64) -> bool {
+        self.processing && now < self.processing_since + BATCH_LOCK_TIMEOUT_SECONDS
+    }
+
+    /// Store one response in the next free slot. A batch that timed out is abandoned: its
+    /// responses stay buffered for the next start_batch and its late output is stale.
+    pub fn buffer_response(
+        &mut self,
+        respondent_pub_key: [u8; 32],
+        respondent_nonce: u128,
+        encrypted_response: [[u8; 32]; BATCH_RESPONSE_CIPHERTEXTS],
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            !self.is_busy(now) && (self.buffered as usize) < RESPONSE_BATCH_SIZE,
+            ErrorCode::ResponseBatchBusy
+        );
+
+        let slot = self.buffered as usize;
+        self.respondent_pub_keys[slot] = respondent_pub_key;
+        self.respondent_nonces[slot] = respondent_nonce;
+        self.encrypted_responses[slot] = encrypted_response;
+        self.buffered += 1;
+        self.processing = false;
+        Ok(())
+    }
+
+    /// Pad empty slots with copies of slot 0 (ignored by the circuit) and lock the buffer
+    pub fn start_batch(&mut self, now: i64) -> Result<()> {
+        require!(self.buffered >= MIN_RESPONSE_BATCH_SIZE, ErrorCode::ResponseBatchTooSmall);
+        require!(!self.is_busy(now), ErrorCode::ResponseBatchBusy);
+
+        for slot in self.buffered as usize..RESPONSE_BATCH_SIZE {
+            self.respondent_pub_keys[slot] = self.respondent_pub_keys[0];
+            self.respondent_nonces[slot] = self.respondent_nonces[0];
+            self.encrypted_responses[slot] = self.encrypted_responses[0];
+        }
+        self.processing = true;
+        self.processing_since = now;
+        self.batches_queued = self.batches_queued.saturating_add(1);
+        Ok(())
+    }
+
+    /// Arguments of process_response_batch, with the ciphertexts read from this
+    /// account at `key`
+    pub fn batch_args(&self, key: Pubkey) -> Vec<Argument> {
+        let mut args = Vec::with_capacity(3 * RESPONSE_BATCH_SIZE + 4);
+        for slot in 0..RESPONSE_BATCH_SIZE {
+            args.push(Argument::ArcisPubkey(self.respondent_pub_keys[slot]));
+            args.push(Argument::PlaintextU128(self.respondent_nonces[slot]));
+            args.push(Argument::Account(
+                key,
+                Self::ENCRYPTED_RESPONSES_OFFSET + (slot * 32 * BATCH_RESPONSE_CIPHERTEXTS) as u32,
+                (32 * BATCH_RESPONSE_CIPHERTEXTS) as u32,
+            ));
+        }
+        args.push(Argument::PlaintextU8(self.buffered));
+        args.push(Argument::PlaintextU32(self.batches_queued));
+        args.push(Argument::PlaintextU128(self.totals_nonce));
+        args.push(Argument::Account(
+            key,
+            Self::ENCRYPTED_TOTALS_OFFSET,
+            (32 * BATCH_TOTALS_CIPHERTEXTS) as u32,
+        ));
+        args
+    }
+
+    /// Store the new totals and clear the slots. Only the output of the latest
+    /// start_batch counts, once.
+    pub fn finish_batch(
+        &mut self,
+        encrypted_totals: [[u8; 32]; BATCH_TOTALS_CIPHERTEXTS],
+        totals_nonce: u128,
+        queued_batch: u32,
+    ) -> Result<()> {
+        require!(
+            self.processing && queued_batch == self.batches_queued,
+            ErrorCode::StaleBatchComputation
+        );
+
+        let batch_number = self.batches_processed;
+        let responses = self.buffered;
+        self.encrypted_totals = encrypted_totals;
+        self.totals_nonce = totals_nonce;
+        self.processed_responses = self.processed_responses.saturating_add(responses as u32);
+        self.batches_processed = self.batches_processed.saturating_add(1);
+        self.buffered = 0;
+        self.processing = false;
+        self.respondent_pub_keys = [[0u8; 32]; RESPONSE_BATCH_SIZE];
+        self.respondent_nonces = [0u128; RESPONSE_BATCH_SIZE];
+        self.encrypted_responses = [[[0u8; 32]; BATCH_RESPONSE_CIPHERTEXTS]; RESPONSE_BATCH_SIZE];
+
+        emit!(ResponseBatchProcessed {
+            survey: self.survey,
+            batch_number,
+            responses,
+            processed_responses: self.processed_responses,
+        });
+        Ok(())
+    }
+}
+
+// ✅ NEW: Where a survey's responses go, after per-survey overrides
+#[derive(Clone, Debug)]
+pub struct ComputationRoute {
+    pub encryption_scheme: EncryptionScheme,
+    pub data_provisioning: DataProvisioningMethod,
+    pub mpc_protocol: MPCProtocol,
+}
+
+impl ComputationRoute {
+    /// Whether computation definitions exist for this combination. Manticore has none:
+    /// the MXE only runs Cerberus (Arcium.toml `backends`), so surveys routed there are rejected.
+    pub fn is_deployed(&self) -> bool {
+        match (&self.encryption_scheme, &self.data_provisioning, &self.mpc_protocol) {
+            (_, DataProvisioningMethod::CreatorOnly, _) => true,  // Nothing runs in MPC
+            (EncryptionScheme::Rescue, _, MPCProtocol::Cerberus) => true,
+            _ => false,
+        }
+    }
+
+    /// Fails unless responses go through `expected`
+    pub fn require_provisioning(&self, expected: DataProvisioningMethod) -> Result<()> {
+        if self.data_provisioning == expected {
+            return Ok(());
+        }
+        let error = match (expected, &self.data_provisioning) {
+            (DataProvisioningMethod::Direct, DataProvisioningMethod::Batch) => ErrorCode::BatchSubmissionRequired,
+            (DataProvisioningMethod::Direct, _) => ErrorCode::MpcDisabledForSurvey,
+            (DataProvisioningMethod::Batch, _) => ErrorCode::BatchModeDisabled,
+            (DataProvisioningMethod::CreatorOnly, _) => ErrorCode::CreatorOnlyDisabled,
+        };
+        Err(error.into())
+    }
+
+    /// Fails unless the instruction's comp defs are the backend this survey runs on
+    pub fn require_protocol(&self, variant: MPCProtocol) -> Result<()> {
+        require!(self.mpc_protocol == variant, ErrorCode::ComputationVariantMismatch);
+        Ok(())
+    }
+}
+
+impl Survey {
+    /// Resolve overrides against the global preferences, rejecting undeployed combinations
+    pub fn computation_route(&self, preferences: &SurveyComputationPreferences) -> Result<ComputationRoute> {
+        let data_provisioning = match (&self.data_provisioning, &self.survey_type) {
+            (Some(method), _) => method.clone(),
+            (None, SurveyType::Basic) => preferences.preferred_data_provisioning.clone(),
+            // Grading and tallies need a computation per response
+            (None, _) => DataProvisioningMethod::Direct,
+        };
+        let route = ComputationRoute {
+            encryption_scheme: preferences.preferred_encryption_scheme.clone(),
+            data_provisioning,
+            mpc_protocol: self
+                .mpc_protocol
+                .clone()
+                .unwrap_or_else(|| preferences.preferred_mpc_protocol.clone()),
+        };
+        require!(route.is_deployed(), ErrorCode::ComputationVariantNotDeployed);
+        Ok(route)
+    }
+
+    /// Responses already stored assume this provisioning, so later preference changes must not move it
+    pub fn pin_data_provisioning(&mut self, route: &ComputationRoute) {
+        self.data_provisioning = Some(route.data_provisioning.clone());
+    }
+
+    /// For instructions that queue one Cerberus computation per response
+    pub fn route_direct(&mut self, preferences: &SurveyComputationPreferences) -> Result<()> {
+        let route = self.computation_route(preferences)?;
+        route.require_provisioning(DataProvisioningMethod::Direct)?;
+        route.require_protocol(MPCProtocol::Cerberus)?;
+        self.pin_data_provisioning(&route);
+        Ok(())
+    }
 }
 
-// ✅ FIXED: Application preferences for survey/quiz computations
-// Note: This is application-level metadata, not Arcium MXE configuration
-#[account]
-pub struct SurveyComputationPreferences {
-    pub admin: Pubkey,
-    pub preferred_encryption_scheme: EncryptionScheme,
-    pub preferred_data_provisioning: DataProvisioningMethod,
-    pub preferred_mpc_protocol: MPCProtocol,
-    pub configured_at: i64,
-    pub is_active: bool,
+/// Shared start of compute_quiz_grade and grade_pool_attempt: the quiz must route to
+/// direct computations and the attempt must not be graded yet. `retry_lost` lets a
+/// Computing attempt whose computation was lost be graded again.
+fn begin_grading(
+    quiz: &mut Survey,
+    preferences: &SurveyComputationPreferences,
+    answers: &mut QuizAnswersStorage,
+    retry_lost: bool,
+) -> Result<()> {
+    quiz.route_direct(preferences)?;
+    require!(
+        answers.grading_status == GradingStatus::Pending
+            || (answers.grading_status == GradingStatus::Computing && retry_lost),
+        ErrorCode::AlreadyGraded
+    );
+    answers.grading_status = GradingStatus::Computing;
+    Ok(())
 }
 
 impl QuizCompletionProof {
@@ -2661,7 +13424,52 @@ impl SurveyComputationPreferences {
         + 1 // preferred_data_provisioning (enum)
         + 1 // preferred_mpc_protocol (enum)
         + 8 // configured_at
-        + 1; // is_active
+        + 1 // is_active
+        + 1; // bump
+
+    /// Validate and store new preferences
+    pub fn configure(
+        &mut self,
+        preferred_encryption_scheme: EncryptionScheme,
+        preferred_data_provisioning: DataProvisioningMethod,
+        preferred_mpc_protocol: MPCProtocol,
+    ) -> Result<()> {
+        require!(
+            matches!(preferred_encryption_scheme, EncryptionScheme::Rescue | EncryptionScheme::AES),
+            ErrorCode::UnsupportedEncryptionScheme
+        );
+        // CreatorOnly is a per-survey choice, never a global default
+        require!(
+            matches!(preferred_data_provisioning, DataProvisioningMethod::Direct | DataProvisioningMethod::Batch),
+            ErrorCode::UnsupportedDataProvisioning
+        );
+        require!(
+            matches!(preferred_mpc_protocol, MPCProtocol::Cerberus | MPCProtocol::Manticore),
+            ErrorCode::UnsupportedMPCProtocol
+        );
+        // Every survey without overrides routes through these, so they must be runnable
+        let route = ComputationRoute {
+            encryption_scheme: preferred_encryption_scheme.clone(),
+            data_provisioning: preferred_data_provisioning.clone(),
+            mpc_protocol: preferred_mpc_protocol.clone(),
+        };
+        require!(route.is_deployed(), ErrorCode::ComputationVariantNotDeployed);
+
+        self.preferred_encryption_scheme = preferred_encryption_scheme;
+        self.preferred_data_provisioning = preferred_data_provisioning;
+        self.preferred_mpc_protocol = preferred_mpc_protocol;
+        self.configured_at = Clock::get()?.unix_timestamp;
+        self.is_active = true;
+
+        emit!(SurveyComputationPreferencesSet {
+            preferred_encryption_scheme: format!("{:?}", self.preferred_encryption_scheme),
+            preferred_data_provisioning: format!("{:?}", self.preferred_data_provisioning),
+            preferred_mpc_protocol: format!("{:?}", self.preferred_mpc_protocol),
+            configured_at: self.configured_at,
+        });
+
+        Ok(())
+    }
 }
 
 impl QuizAggregation {
@@ -2757,6 +13565,14 @@ pub struct QuestionsAdded {
     pub question_count: u32,
 }
 
+#[event]
+pub struct QuestionContentCommitted {
+    pub survey: Pubkey,
+    pub scheme: String,
+    pub content_hash: [u8; 32],
+    pub question_count: u32,
+}
+
 #[event]
 pub struct ResponseSubmitted {
     pub survey: Pubkey,
@@ -2883,10 +13699,15 @@ pub struct QuizPassed {
     pub passed_at: i64,
 }
 
-// #[event]
-// pub struct AnalyticsComputed {
-//     pub computation_data_ct: AnalyticsComputationOutputStruct0,
-// }
+// research_insights decrypts to ResearchInsights: sample_size, correlation_plus_one
+// ((r + 1) * 10^4), t_statistic (|t| * 10^4, saturating) and positive_correlation
+#[event]
+pub struct AnalyticsComputed {
+    pub survey: Pubkey,
+    pub analyst_analytics: SharedEncryptedStruct<4>,
+    pub business_metrics: SharedEncryptedStruct<4>,
+    pub research_insights: SharedEncryptedStruct<4>,
+}
 
 
 // Admin control events for audit logging
@@ -2920,6 +13741,12 @@ pub struct SurveyModified {
 }
 
 // ✅ FIXED: Application preferences event
+#[event]
+pub struct PreferencesAdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
 #[event]
 pub struct SurveyComputationPreferencesSet {
     pub preferred_encryption_scheme: String,
@@ -2928,6 +13755,227 @@ pub struct SurveyComputationPreferencesSet {
     pub configured_at: i64,
 }
 
+#[event]
+pub struct MatrixTallyRevealed {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; MATRIX_TALLY_CIPHERTEXTS],
+}
+
+#[event]
+pub struct RankingTallyRevealed {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; RANKING_TALLY_CIPHERTEXTS],
+}
+
+#[event]
+pub struct NpsRevealed {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; NPS_REPORT_CIPHERTEXTS],
+}
+
+#[event]
+pub struct PercentilesRevealed {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; PERCENTILE_REPORT_CIPHERTEXTS],
+}
+
+#[event]
+pub struct CrosstabRevealed {
+    pub survey: Pubkey,
+    pub question_a: u32,
+    pub question_b: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; CROSSTAB_REPORT_CIPHERTEXTS],
+}
+
+#[event]
+pub struct ReportPrecisionUpdated {
+    pub survey: Pubkey,
+    pub report_decimals: u8,
+}
+
+#[event]
+pub struct ComputationOverridesUpdated {
+    pub survey: Pubkey,
+    pub data_provisioning: String,
+    pub mpc_protocol: String,
+}
+
+#[event]
+pub struct AnswerShufflingUpdated {
+    pub quiz: Pubkey,
+    pub enabled: bool,
+}
+
+// Decrypts to ItemAnalysisReport: threshold_met, graded_attempts, then answered, correct,
+// difficulty (× 10^4) and discrimination_plus_one ((r + 1) × 10^4) for each question
+#[event]
+pub struct ItemAnalysisRevealed {
+    pub quiz: Pubkey,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; ITEM_ANALYSIS_REPORT_CIPHERTEXTS],
+}
+
+// Decrypts to RankReport: threshold_met, entries, rank_best, rank_worst,
+// percentile (× 10^4, share of students in lower 10% bands) and top_k_position
+#[event]
+pub struct RankRevealed {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; RANK_REPORT_CIPHERTEXTS],
+}
+
+// Plaintext top K, best first. Scores are percentages × 100; pseudonym 0 is an empty slot.
+// Everything is zero while threshold_met is false.
+#[event]
+pub struct LeaderboardPublished {
+    pub quiz: Pubkey,
+    pub threshold_met: bool,
+    pub pseudonyms: [u32; LEADERBOARD_TOP_K],
+    pub scores: [u32; LEADERBOARD_TOP_K],
+}
+
+// The chosen pseudonym is already on the board; the student can join again with another
+#[event]
+pub struct LeaderboardPseudonymTaken {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+}
+
+// Decrypts (instructor key) to the number of recent submissions that matched this one
+#[event]
+pub struct SimilarityChecked {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub flagged_matches: [u8; 32],
+}
+
+#[event]
+pub struct QuizAttemptStarted {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub drawn_questions: [u8; QUIZ_ITEMS],
+}
+
+#[event]
+pub struct PoolQuizGraded {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub student_result: SharedEncryptedStruct<5>,     // QuizResult
+    pub creator_feedback: SharedEncryptedStruct<4>,   // StudentFeedback
+}
+
+// Next question index (Enc<Shared, u32>, u32::MAX once the attempt is done)
+#[event]
+pub struct AdaptiveQuestionRevealed {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub step: u8,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub next_question: [u8; 32],
+}
+
+#[event]
+pub struct AdaptivePlacementRevealed {
+    pub quiz: Pubkey,
+    pub student: Pubkey,
+    pub student_result: SharedEncryptedStruct<3>,   // AdaptiveResult
+    pub creator_result: SharedEncryptedStruct<3>,
+}
+
+// Receipt for the voter: BallotReceipt encrypted to the key the ballot was cast with
+#[event]
+pub struct BallotRecorded {
+    pub survey: Pubkey,
+    pub voter: Pubkey,
+    pub ballot_commitment: [u8; 32],
+    pub ballots_recorded: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub receipt: [[u8; 32]; BALLOT_RECEIPT_CIPHERTEXTS],
+}
+
+#[event]
+pub struct PollResultsPublished {
+    pub survey: Pubkey,
+    pub ballots_recorded: u32,
+    pub total_votes: u32,
+    pub counts: [u32; MAX_POLL_OPTIONS],
+}
+
+// IrvResult encrypted to the poll's public viewer key
+#[event]
+pub struct RankedPollResultsPublished {
+    pub survey: Pubkey,
+    pub ballots_recorded: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; IRV_RESULT_CIPHERTEXTS],
+}
+
+#[event]
+pub struct TextResponseSubmitted {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub respondent: Pubkey,
+    pub ciphertext_hash: [u8; 32],
+}
+
+#[event]
+pub struct TextStatsRevealed {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; TEXT_REPORT_CIPHERTEXTS],
+}
+
+#[event]
+pub struct KeywordTableRevealed {
+    pub survey: Pubkey,
+    pub question_id: u32,
+    pub vocabulary_hash: [u8; 32],
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; KEYWORD_TABLE_CIPHERTEXTS],
+}
+
+#[event]
+pub struct ResponseBatchProcessed {
+    pub survey: Pubkey,
+    pub batch_number: u32,
+    pub responses: u8,
+    pub processed_responses: u32,
+}
+
+#[event]
+pub struct BatchAnalyticsRevealed {
+    pub survey: Pubkey,
+    pub processed_responses: u32,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: [[u8; 32]; BATCH_ANALYTICS_CIPHERTEXTS],
+}
+
 // ✅ ADD: Missing event structures for comprehensive error tracking
 #[event]
 pub struct ComputationAborted {
@@ -2968,8 +14016,123 @@ const MAX_QUESTIONS: usize = 50;
 const MAX_QUESTION_TEXT_LENGTH: usize = 500;
 const MAX_OPTIONS_PER_QUESTION: usize = 10;
 const MAX_OPTION_TEXT_LENGTH: usize = 100;
+const MAX_TRANSLATIONS_PER_QUESTION: usize = 5;  // Translations are paid for by add_questions' realloc (Survey::questions_space)
+const MAX_LANGUAGE_TAG_LENGTH: usize = 35;
+const MIN_LANGUAGE_TAG_LENGTH: usize = 2;
+const MAX_DISPLAY_CONDITIONS: usize = 4;
+const MIN_CHOICE_LABELS: usize = 2;
+
+// Matrix/ranking limits (must match the aggregation circuits' array sizes)
+const MAX_MATRIX_ROWS: usize = 5;
+const MAX_MATRIX_COLUMNS: usize = 5;
+const MAX_RANKING_OPTIONS: usize = 6;
+const MATRIX_TALLY_CIPHERTEXTS: usize = MAX_MATRIX_ROWS * MAX_MATRIX_COLUMNS + 1;    // cell_counts + respondents
+const RANKING_TALLY_CIPHERTEXTS: usize = 2 * MAX_RANKING_OPTIONS + 1;              // borda_scores + first_place_counts + valid_ballots
+const MIN_TALLY_REVEAL_SUBMISSIONS: u32 = 5;  // Aggregates over fewer respondents could expose individual answers
+
+// Net Promoter Score (must match nps_computation)
+const NPS_SCALE_MIN: u8 = 0;
+const NPS_SCALE_MAX: u8 = 10;
+const NPS_TALLY_CIPHERTEXTS: usize = 3;   // promoters + passives + detractors
+const NPS_REPORT_CIPHERTEXTS: usize = 6;  // threshold_met + nps_plus_100 + total + 3 buckets
+
+// Polls (must match poll_tally)
+const MIN_POLL_OPTIONS: usize = 2;
+const MAX_POLL_OPTIONS: usize = 8;
+const POLL_TALLY_CIPHERTEXTS: usize = MAX_POLL_OPTIONS;
+const BALLOT_RECEIPT_CIPHERTEXTS: usize = 2;  // counted + choice
+const POLL_LOCK_TIMEOUT_SECONDS: i64 = 300;   // A ballot computation older than this is presumed lost
+const BATCH_LOCK_TIMEOUT_SECONDS: i64 = 300;  // Same for a queued response batch
+const TALLY_LOCK_TIMEOUT_SECONDS: i64 = 300;  // And for an answer queued on a per-question tally
+// Ranked ballots pack candidates 4 bits per rank, first choice lowest, 0xF = unranked
+const MAX_IRV_CANDIDATES: usize = 4;
+const IRV_PROFILES: usize = 64;               // Distinct rankings of 1..=4 candidates, one counter each
+const IRV_RESULT_CIPHERTEXTS: usize = MAX_IRV_CANDIDATES;  // winner + eliminated per round
+
+// Free-text answers (must match text_response_stats and client/text_responses.ts)
+const MAX_INLINE_TEXT_CIPHERTEXTS: usize = 16;  // Length block + 15 × 31 text bytes
+const TEXT_PAD_BLOCKS: usize = 4;               // Ciphertexts are padded to a multiple of this
+const MAX_CREATOR_RESPONSE_CIPHERTEXTS: usize = 64;  // One per answer in a CreatorOnly response
+
+// Batched survey responses (must match batch_aggregation)
+const RESPONSE_BATCH_SIZE: usize = 4;           // K: responses per process_response_batch
+const MIN_RESPONSE_BATCH_SIZE: u8 = 2;          // A lone response is never folded into the totals on its own
+const BATCH_RESPONSE_CIPHERTEXTS: usize = 6;    // Same [u32; 6] as submit_survey_analytics
+const BATCH_TOTALS_CIPHERTEXTS: usize = 4;      // responses + answered_questions + answer_sum + completion_sum
+const BATCH_ANALYTICS_CIPHERTEXTS: usize = 4;   // total_responses + answered_questions + average_rating + average_completion
+const TEXT_TALLY_CIPHERTEXTS: usize = 3;        // responses + non_empty + total_length
+const TEXT_REPORT_CIPHERTEXTS: usize = 4;       // threshold_met + responses + non_empty + average_length
+const MAX_KEYWORD_VOCABULARY: usize = 32;       // Keyword ids per TextInput question
+const MAX_ANSWER_KEYWORDS: usize = 8;           // Token ids per answer, padded with NO_KEYWORD
+const KEYWORD_TALLY_CIPHERTEXTS: usize = 1 + MAX_KEYWORD_VOCABULARY;  // answers + counts
+const KEYWORD_TABLE_CIPHERTEXTS: usize = 12;    // threshold_met + answers + 5 keywords + 5 counts
+
+// Median / percentile histogram (must match percentile_computation)
+const HISTOGRAM_BUCKETS: usize = 16;
+const PERCENTILE_REPORT_CIPHERTEXTS: usize = 7;  // threshold_met + total + q1 + median + q3 + 2 custom
+
+// Cross-tabulation (must match crosstab_computation)
+const MAX_CROSSTAB_CATEGORIES: usize = 8;
+const CROSSTAB_CELLS: usize = MAX_CROSSTAB_CATEGORIES * MAX_CROSSTAB_CATEGORIES;
+const CROSSTAB_REPORT_CIPHERTEXTS: usize = CROSSTAB_CELLS + 1;  // cells + suppressed_cells
+
+// n, Σx, Σy, Σxy, Σx², Σy² for analytics_computation
+const CORRELATION_SUMS_CIPHERTEXTS: usize = 6;
+const MIN_CROSSTAB_SUPPRESSION_THRESHOLD: u32 = 3;
+
+// Encrypted answer value for questions hidden by skip logic (must match the circuits)
+pub const NOT_ANSWERED: u32 = u32::MAX;
 const MIN_MAX_RESPONSES: u32 = 1;
 const MAX_MAX_RESPONSES: u32 = 1000000;
+const MAX_CONTENT_URI_LENGTH: usize = 200;
+const MAX_COMMITTED_QUESTIONS: u32 = 1024;
+const CONTENT_URI_SCHEMES: &[&str] = &["https://", "ipfs://", "ar://"];
+
+// Domain separation prefixes for question content Merkle trees
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+// Domain separation for the answer shuffle seed (kept apart from the pool draw)
+const SHUFFLE_SEED_DOMAIN: &[u8] = b"answer_shuffle";
+
+// Fixed-point precision for circuit averages/percentages (must match fixed_point::MAX_DECIMALS)
+const MAX_REPORT_DECIMALS: u8 = 4;
+
+// Quiz grading schemes (must match quiz_evaluation)
+const GRADING_SCHEME_EXACT: u32 = 0;
+const GRADING_SCHEME_MULTI_SELECT: u32 = 1;
+const GRADING_SCHEME_NUMERIC_TOLERANCE: u32 = 2;
+// Enc<Mxe, [u32; 14]>: [correct1, correct2, points1, points2, threshold, stat1, stat2, stat3,
+//                       scheme1, scheme2, tolerance1, tolerance2, penalty1, penalty2]
+const QUIZ_GRADING_VALUES: usize = 14;
+
+// Item analysis (must match quiz_evaluation)
+const QUIZ_ITEMS: usize = 2;
+const ITEM_STATS_CIPHERTEXTS: usize = 3 + 3 * QUIZ_ITEMS;            // attempts + Σscore + Σscore² + answered/correct/Σscore-if-correct per item
+const ITEM_ANALYSIS_REPORT_CIPHERTEXTS: usize = 2 + 4 * QUIZ_ITEMS;  // threshold_met + attempts + answered/correct/difficulty/discrimination per item
+
+// Quiz leaderboard (must match quiz_evaluation)
+const LEADERBOARD_TOP_K: usize = 3;
+const LEADERBOARD_BUCKETS: usize = 10;  // 10% score bands
+const LEADERBOARD_CIPHERTEXTS: usize = 1 + LEADERBOARD_BUCKETS + 2 * LEADERBOARD_TOP_K;  // entries + buckets + top scores/pseudonyms
+const LEADERBOARD_ENTRY_CIPHERTEXTS: usize = 2;  // score + pseudonym
+const RANK_REPORT_CIPHERTEXTS: usize = 6;
+
+// Answer similarity checks (must match quiz_evaluation)
+const SIMILARITY_HISTORY: usize = 8;  // Latest submissions each new one is compared against
+const ANSWER_HISTORY_CIPHERTEXTS: usize = SIMILARITY_HISTORY * QUIZ_ITEMS;
+
+// Question pools: each attempt draws QUIZ_ITEMS of up to MAX_POOL_QUESTIONS (must match quiz_evaluation)
+const MAX_POOL_QUESTIONS: usize = 8;
+// Enc<Mxe, PoolKeys>: [passing_threshold, key0..key7], each key a u128 packed as
+// correct (bits 0-31) | tolerance (32-63) | points (64-71) | scheme (72-79) | penalty (80-87)
+const POOL_KEYS_VALUES: usize = 1 + MAX_POOL_QUESTIONS;
+
+// Adaptive quizzes: step s asks on-chain question s * ADAPTIVE_TIERS + tier (must match quiz_evaluation)
+const ADAPTIVE_TIERS: usize = 3;
+const ADAPTIVE_STEPS: usize = 3;
+const ADAPTIVE_BANK_SIZE: usize = ADAPTIVE_STEPS * ADAPTIVE_TIERS;  // Enc<Mxe, AdaptiveKeys>, packed like PoolKeys
+const ADAPTIVE_STATE_CIPHERTEXTS: usize = 3;   // score + tier + correct_answers
 
 // Reserved words that cannot be used as slugs
 const RESERVED_SLUGS: &[&str] = &[
@@ -3051,48 +14214,321 @@ const RESERVED_SLUGS: &[&str] = &[
     "federation", "federations", "union", "unions", "coalition", "coalitions"
 ];
 
-// Input validation helper functions
-fn validate_string_length(s: &str, max_length: usize, _field_name: &str) -> Result<()> {
+// Input validation helper functions
+fn validate_string_length(s: &str, max_length: usize, _field_name: &str) -> Result<()> {
+    require!(
+        s.len() <= max_length,
+        ErrorCode::StringTooLong
+    );
+    require!(
+        !s.is_empty(),
+        ErrorCode::StringEmpty
+    );
+    Ok(())
+}
+
+fn validate_max_responses(max_responses: u32) -> Result<()> {
+    require!(
+        max_responses >= MIN_MAX_RESPONSES && max_responses <= MAX_MAX_RESPONSES,
+        ErrorCode::InvalidMaxResponses
+    );
+    Ok(())
+}
+
+fn validate_questions(
+    questions: &Vec<QuestionData>,
+    translations: &[QuestionTranslations],
+    display_conditions: &[QuestionDisplayConditions],
+    grading: &[QuestionGrading],
+    existing_questions: &[QuestionData],
+    default_language: &str,
+) -> Result<()> {
+    // add_questions reallocs on every call, so the cap covers the whole survey, not one batch
+    require!(
+        existing_questions.len() + questions.len() <= MAX_QUESTIONS,
+        ErrorCode::TooManyQuestions
+    );
+    validate_question_keys(translations.iter().map(|entry| entry.question_id), questions)?;
+    validate_question_keys(display_conditions.iter().map(|entry| entry.question_id), questions)?;
+    validate_question_keys(grading.iter().map(|entry| entry.question_id), questions)?;
+    
+    for (i, question) in questions.iter().enumerate() {
+        // Everything before this question in survey order: existing questions, then this batch
+        let earlier = existing_questions.iter().chain(questions[..i].iter());
+        require!(
+            !earlier.clone().any(|other| other.id == question.id),
+            ErrorCode::DuplicateQuestionId
+        );
+        let conditions = display_conditions
+            .iter()
+            .find(|entry| entry.question_id == question.id)
+            .map_or(&[][..], |entry| &entry.conditions[..]);
+        validate_display_conditions(conditions, earlier)?;
+
+        validate_string_length(&question.question_text, MAX_QUESTION_TEXT_LENGTH, "question_text")?;
+        validate_no_xss(&question.question_text)?;
+        
+        if let QuestionType::MultipleChoice { options } = &question.question_type {
+            require!(
+                options.len() <= MAX_OPTIONS_PER_QUESTION,
+                ErrorCode::TooManyOptions
+            );
+            
+            for option in options {
+                validate_string_length(option, MAX_OPTION_TEXT_LENGTH, "option")?;
+                validate_no_xss(option)?;
+            }
+        }
+
+        validate_grid_and_ranking_shape(&question.question_type)?;
+        validate_grading_scheme(question, &QuestionGrading::scheme_for(grading, question.id))?;
+
+        let question_translations = translations
+            .iter()
+            .find(|entry| entry.question_id == question.id)
+            .map_or(&[][..], |entry| &entry.translations[..]);
+        validate_translations(question, question_translations, default_language)?;
+    }
+    
+    Ok(())
+}
+
+// Entries stored beside the questions must each name a question of the same batch, once
+fn validate_question_keys<I>(question_ids: I, questions: &[QuestionData]) -> Result<()>
+where
+    I: Iterator<Item = u32>,
+{
+    let mut seen = Vec::new();
+    for question_id in question_ids {
+        require!(
+            questions.iter().any(|question| question.id == question_id) && !seen.contains(&question_id),
+            ErrorCode::InvalidQuestionEntry
+        );
+        seen.push(question_id);
+    }
+    Ok(())
+}
+
+// Conditions may only reference questions that come earlier in the survey. Every edge of the
+// skip-logic graph therefore points backwards, so survey order is a topological order and
+// the graph is acyclic by construction (this also rules out self-references).
+fn validate_display_conditions<'a, I>(conditions: &[DisplayCondition], earlier: I) -> Result<()>
+where
+    I: Iterator<Item = &'a QuestionData> + Clone,
+{
+    require!(
+        conditions.len() <= MAX_DISPLAY_CONDITIONS,
+        ErrorCode::TooManyDisplayConditions
+    );
+
+    for condition in conditions {
+        let target = earlier
+            .clone()
+            .find(|other| other.id == condition.question_id)
+            .ok_or(ErrorCode::InvalidDisplayCondition)?;
+
+        let is_ordering = matches!(
+            condition.operator,
+            ConditionOperator::GreaterThan | ConditionOperator::LessThan
+        );
+        let value_in_range = match &target.question_type {
+            QuestionType::MultipleChoice { options } => {
+                !is_ordering && (condition.value as usize) < options.len()
+            }
+            QuestionType::TrueFalse => !is_ordering && condition.value <= 1,
+            QuestionType::Rating { min, max } => {
+                condition.value >= *min as u32 && condition.value <= *max as u32
+            }
+            // Free text and timers have no comparable encrypted answer value
+            _ => false,
+        };
+        require!(value_in_range, ErrorCode::InvalidDisplayCondition);
+    }
+
+    Ok(())
+}
+
+// Matrix and ranking shapes are bounded by the fixed-size arrays in the aggregation circuits
+fn validate_grid_and_ranking_shape(question_type: &QuestionType) -> Result<()> {
+    match question_type {
+        QuestionType::Matrix { rows, columns } => {
+            require!(
+                (1..=MAX_MATRIX_ROWS).contains(&rows.len()),
+                ErrorCode::InvalidMatrixShape
+            );
+            require!(
+                (MIN_CHOICE_LABELS..=MAX_MATRIX_COLUMNS).contains(&columns.len()),
+                ErrorCode::InvalidMatrixShape
+            );
+            for label in rows.iter().chain(columns.iter()) {
+                validate_string_length(label, MAX_OPTION_TEXT_LENGTH, "matrix_label")?;
+                validate_no_xss(label)?;
+            }
+        }
+        QuestionType::Ranking { options } => {
+            require!(
+                (MIN_CHOICE_LABELS..=MAX_RANKING_OPTIONS).contains(&options.len()),
+                ErrorCode::InvalidRankingOptions
+            );
+            for option in options {
+                validate_string_length(option, MAX_OPTION_TEXT_LENGTH, "ranking_option")?;
+                validate_no_xss(option)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// Multi-select needs option bitmasks, tolerance needs a numeric answer, and no
+// penalty may exceed what the question is worth
+fn validate_grading_scheme(question: &QuestionData, grading: &GradingScheme) -> Result<()> {
+    let fits = match (grading, &question.question_type) {
+        (GradingScheme::Exact { negative_marks }, _) => *negative_marks <= question.points,
+        (GradingScheme::MultiSelect { wrong_pick_penalty }, QuestionType::MultipleChoice { .. }) => {
+            *wrong_pick_penalty <= question.points
+        }
+        (
+            GradingScheme::NumericTolerance { tolerance, negative_marks },
+            QuestionType::Rating { .. } | QuestionType::TextInput,
+        ) => *tolerance < NOT_ANSWERED && *negative_marks <= question.points,
+        _ => false,
+    };
+    require!(fits, ErrorCode::InvalidGradingScheme);
+
+    Ok(())
+}
+
+fn validate_histogram_layout(bucket_min: u32, bucket_width: u32) -> Result<()> {
+    require!(bucket_width > 0, ErrorCode::InvalidHistogramLayout);
+
+    // The upper edge of the last bucket must be representable (and stay below NOT_ANSWERED)
+    let span = (bucket_width as u64) * (HISTOGRAM_BUCKETS as u64);
     require!(
-        s.len() <= max_length,
-        ErrorCode::StringTooLong
+        (bucket_min as u64) + span < NOT_ANSWERED as u64,
+        ErrorCode::InvalidHistogramLayout
     );
+
+    Ok(())
+}
+
+// (answer offset, category count) of a question used as one side of a cross-tab
+fn crosstab_dimension(question: &QuestionData) -> Result<(u32, u8)> {
+    let (offset, categories) = match &question.question_type {
+        QuestionType::MultipleChoice { options } => (0u32, options.len()),
+        QuestionType::Rating { min, max } if min <= max => (*min as u32, (max - min) as usize + 1),
+        _ => return Err(ErrorCode::InvalidCrosstabQuestion.into()),
+    };
     require!(
-        !s.is_empty(),
-        ErrorCode::StringEmpty
+        (MIN_CHOICE_LABELS..=MAX_CROSSTAB_CATEGORIES).contains(&categories),
+        ErrorCode::InvalidCrosstabQuestion
+    );
+
+    Ok((offset, categories as u8))
+}
+
+// Number of translatable option labels a question type carries
+fn option_label_count(question_type: &QuestionType) -> usize {
+    match question_type {
+        QuestionType::MultipleChoice { options } => options.len(),
+        QuestionType::Matrix { rows, columns } => rows.len() + columns.len(),
+        QuestionType::Ranking { options } => options.len(),
+        _ => 0,
+    }
+}
+
+fn validate_translations(
+    question: &QuestionData,
+    translations: &[QuestionTranslation],
+    default_language: &str,
+) -> Result<()> {
+    require!(
+        translations.len() <= MAX_TRANSLATIONS_PER_QUESTION,
+        ErrorCode::TooManyTranslations
     );
+
+    let expected_options = option_label_count(&question.question_type);
+    for (i, translation) in translations.iter().enumerate() {
+        validate_language_tag(&translation.language)?;
+
+        // The default language lives in question_text itself, and each locale appears once
+        require!(
+            !translation.language.eq_ignore_ascii_case(default_language),
+            ErrorCode::DuplicateTranslation
+        );
+        require!(
+            !translations[..i]
+                .iter()
+                .any(|other| other.language.eq_ignore_ascii_case(&translation.language)),
+            ErrorCode::DuplicateTranslation
+        );
+
+        validate_string_length(&translation.question_text, MAX_QUESTION_TEXT_LENGTH, "question_text")?;
+        validate_no_xss(&translation.question_text)?;
+
+        // Answers are option indices, so every locale must label the same options
+        require!(
+            translation.options.len() == expected_options,
+            ErrorCode::TranslationOptionMismatch
+        );
+        for option in &translation.options {
+            validate_string_length(option, MAX_OPTION_TEXT_LENGTH, "option")?;
+            validate_no_xss(option)?;
+        }
+    }
+
     Ok(())
 }
 
-fn validate_max_responses(max_responses: u32) -> Result<()> {
+// Accepts BCP 47-style tags: a 2-3 letter primary language plus optional alphanumeric subtags
+fn validate_language_tag(tag: &str) -> Result<()> {
     require!(
-        max_responses >= MIN_MAX_RESPONSES && max_responses <= MAX_MAX_RESPONSES,
-        ErrorCode::InvalidMaxResponses
+        tag.len() >= MIN_LANGUAGE_TAG_LENGTH && tag.len() <= MAX_LANGUAGE_TAG_LENGTH,
+        ErrorCode::InvalidLanguageTag
+    );
+
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    require!(
+        (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_alphabetic()),
+        ErrorCode::InvalidLanguageTag
+    );
+    require!(
+        subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        }),
+        ErrorCode::InvalidLanguageTag
     );
+
     Ok(())
 }
 
-fn validate_questions(questions: &Vec<QuestionData>) -> Result<()> {
+fn validate_content_commitment(content_hash: &[u8; 32], content_uri: &str, question_count: u32) -> Result<()> {
+    require!(
+        *content_hash != [0u8; 32],
+        ErrorCode::InvalidContentCommitment
+    );
     require!(
-        questions.len() <= MAX_QUESTIONS,
+        question_count > 0 && question_count <= MAX_COMMITTED_QUESTIONS,
         ErrorCode::TooManyQuestions
     );
-    
-    for question in questions {
-        validate_string_length(&question.question_text, MAX_QUESTION_TEXT_LENGTH, "question_text")?;
-        
-        if let QuestionType::MultipleChoice { options } = &question.question_type {
-            require!(
-                options.len() <= MAX_OPTIONS_PER_QUESTION,
-                ErrorCode::TooManyOptions
-            );
-            
-            for option in options {
-                validate_string_length(option, MAX_OPTION_TEXT_LENGTH, "option")?;
-            }
-        }
-    }
-    
+
+    validate_content_uri(content_uri)
+}
+
+fn validate_content_uri(content_uri: &str) -> Result<()> {
+    validate_string_length(content_uri, MAX_CONTENT_URI_LENGTH, "content_uri")?;
+    validate_no_xss(content_uri)?;
+    require!(
+        CONTENT_URI_SCHEMES.iter().any(|scheme| content_uri.starts_with(scheme)),
+        ErrorCode::InvalidContentUri
+    );
+    require!(
+        !content_uri.chars().any(|c| c.is_whitespace()),
+        ErrorCode::InvalidContentUri
+    );
+
     Ok(())
 }
 
@@ -3279,20 +14715,31 @@ fn validate_slug(slug: &str) -> Result<()> {
 }
 
 impl Survey {
+    // Questions and committed content are not reserved up front: add_questions and
+    // set_question_content_commitment realloc for what they store, so a survey only
+    // pays rent for the storage mode it uses.
     pub const INIT_SPACE: usize = DISCRIMINATOR_LENGTH
         + PUBKEY_LENGTH // creator
-        + STRING_LENGTH_PREFIX + 40 // slug (max length)
-        + STRING_LENGTH_PREFIX + 100 // title (max length)
-        + STRING_LENGTH_PREFIX + 200 // description (max length)
-        + 4 // survey_type
-        + 4 + (10 * 200) // questions (Vec<QuestionData> with max 10 questions)
+        + STRING_LENGTH_PREFIX + MAX_SLUG_LENGTH // slug (max length)
+        + STRING_LENGTH_PREFIX + MAX_TITLE_LENGTH // title (max length)
+        + STRING_LENGTH_PREFIX + MAX_DESCRIPTION_LENGTH // description (max length)
+        + SurveyType::MAX_SPACE // survey_type
+        + 4 // questions (empty Vec)
         + 4 // max_responses
         + 4 // current_responses
         + 1 // is_active
         + 1 // is_publicly_browsable
         + 1 // modification_count
         + 8 // created_at
-        + 8; // mpc_computation_id
+        + 8 // mpc_computation_id
+        + 32 // instructor_arcium_pubkey
+        + 32 // creator_arcium_pubkey
+        + 1 // question_content (None)
+        + STRING_LENGTH_PREFIX + MAX_LANGUAGE_TAG_LENGTH // default_language
+        + 1 // report_decimals
+        + 1 // shuffle_answers
+        + 2 // data_provisioning (Option<enum>)
+        + 2; // mpc_protocol (Option<enum>)
     
     // Dynamic space calculation for actual string lengths
     pub fn calculate_space(slug: &str, title: &str) -> usize {
@@ -3301,8 +14748,8 @@ impl Survey {
         + STRING_LENGTH_PREFIX + slug.len() // dynamic slug size
         + STRING_LENGTH_PREFIX + title.len() // dynamic title size
         + STRING_LENGTH_PREFIX + 200 // description (max length) - FIXED: was missing
-        + 4 // survey_type - FIXED: was missing
-        + 4 + (10 * 200) // questions (Vec<QuestionData> with max 10 questions) - FIXED: was missing
+        + SurveyType::MAX_SPACE // survey_type
+        + 4 // questions (empty Vec; add_questions reallocs)
         + 4 // max_responses
         + 4 // current_responses
         + 1 // is_active
@@ -3310,6 +14757,60 @@ impl Survey {
         + 1 // modification_count
         + 8 // created_at
         + 8 // mpc_computation_id - FIXED: was missing
+        + 32 // instructor_arcium_pubkey
+        + 32 // creator_arcium_pubkey
+        + 1 // question_content (None; set_question_content_commitment reallocs)
+        + STRING_LENGTH_PREFIX + MAX_LANGUAGE_TAG_LENGTH // default_language
+        + 1 // report_decimals
+        + 1 // shuffle_answers
+        + 2 // data_provisioning (Option<enum>)
+        + 2 // mpc_protocol (Option<enum>)
+        + 4 // question_translations (empty Vec; add_questions reallocs)
+        + 4 // question_display_conditions (empty Vec; add_questions reallocs)
+        + 4 // question_grading (empty Vec; add_questions reallocs)
+    }
+
+    /// Bytes `entries` (questions, or the entries stored beside them) add to the account
+    pub fn questions_space<T: AnchorSerialize>(entries: &[T]) -> usize {
+        entries
+            .iter()
+            .map(|entry| entry.try_to_vec().map_or(0, |bytes| bytes.len()))
+            .sum()
+    }
+
+    /// Account size once `content_uri` replaces the current commitment, if any
+    pub fn space_with_commitment(&self, data_len: usize, content_uri: &str) -> usize {
+        let current = self
+            .question_content
+            .as_ref()
+            .map_or(0, |content| QuestionContentCommitment::space(&content.content_uri));
+        data_len - current + QuestionContentCommitment::space(content_uri)
+    }
+}
+
+impl Survey {
+    /// Grading scheme of `question_id` (see QuestionGrading::scheme_for)
+    pub fn grading_for(&self, question_id: u32) -> GradingScheme {
+        QuestionGrading::scheme_for(&self.question_grading, question_id)
+    }
+
+    /// `answer_shuffle` argument for one attempt (0 = canonical order)
+    pub fn answer_shuffle(&self, answers: &QuizAnswersStorage) -> u128 {
+        if self.shuffle_answers {
+            AnswerShuffle::for_attempt(&answers.shuffle_seed, self).code()
+        } else {
+            0
+        }
+    }
+
+    /// Poll settings; any other survey type is rejected
+    pub fn poll_config(&self) -> Result<PollConfig> {
+        match self.survey_type {
+            SurveyType::Poll { option_count, closes_at, eligible_voters_root, ranked_choice } => {
+                Ok(PollConfig { option_count, closes_at, eligible_voters_root, ranked_choice })
+            }
+            _ => Err(ErrorCode::InvalidSurveyType.into()),
+        }
     }
 }
 
@@ -3396,6 +14897,140 @@ pub enum ErrorCode {
     ComputationFailed,
     #[msg("Computation timed out")]
     ComputationTimeout,
+    #[msg("Survey questions are stored in a different mode")]
+    QuestionStorageModeConflict,
+    #[msg("Question content cannot change after responses exist")]
+    QuestionContentFrozen,
+    #[msg("Invalid question content commitment")]
+    InvalidContentCommitment,
+    #[msg("Invalid question content URI")]
+    InvalidContentUri,
+    #[msg("Invalid language tag")]
+    InvalidLanguageTag,
+    #[msg("Too many translations for question")]
+    TooManyTranslations,
+    #[msg("Duplicate translation language")]
+    DuplicateTranslation,
+    #[msg("Translation options do not match question options")]
+    TranslationOptionMismatch,
+    #[msg("Duplicate question id")]
+    DuplicateQuestionId,
+    #[msg("Too many display conditions for question")]
+    TooManyDisplayConditions,
+    #[msg("Display condition must reference an earlier question with a valid answer value")]
+    InvalidDisplayCondition,
+    #[msg("Matrix questions need 1-5 rows and 2-5 columns")]
+    InvalidMatrixShape,
+    #[msg("Ranking questions need 2-6 options")]
+    InvalidRankingOptions,
+    #[msg("Question type does not support this aggregation")]
+    QuestionTypeMismatch,
+    #[msg("Not enough submissions to reveal this tally")]
+    InsufficientTallySubmissions,
+    #[msg("NPS requires a 0-10 Rating question")]
+    InvalidNpsQuestion,
+    #[msg("Histogram bucket width must be non-zero and the range must fit in u32")]
+    InvalidHistogramLayout,
+    #[msg("Percentiles must be between 0 and 100")]
+    InvalidPercentile,
+    #[msg("Cross-tabs need two different MultipleChoice or Rating questions with 2-8 categories")]
+    InvalidCrosstabQuestion,
+    #[msg("Small-cell suppression threshold is too low")]
+    InvalidSuppressionThreshold,
+    #[msg("Report precision must be between 0 and 4 decimal places")]
+    InvalidReportPrecision,
+    #[msg("Grading scheme does not fit the question type or its points")]
+    InvalidGradingScheme,
+    #[msg("Similarity threshold must be between 1 and the number of graded questions")]
+    InvalidSimilarityThreshold,
+    #[msg("These answers have already been checked for similarity")]
+    SimilarityAlreadyChecked,
+    #[msg("Answer shuffling needs on-chain questions and can't change once responses exist")]
+    ShuffleUnavailable,
+    #[msg("Question pool needs between 2 and 8 on-chain questions and no responses yet")]
+    InvalidQuestionPool,
+    #[msg("Answers were submitted before this quiz attempt started")]
+    AnswersBeforeAttempt,
+    #[msg("Adaptive quiz needs at least 9 on-chain questions and no responses yet")]
+    InvalidAdaptiveBank,
+    #[msg("Previous adaptive answer is still being graded")]
+    AdaptiveStepPending,
+    #[msg("Every adaptive question has already been answered")]
+    AdaptiveAttemptComplete,
+    #[msg("Adaptive attempt is not finished yet")]
+    AdaptiveAttemptIncomplete,
+    #[msg("Poll needs 2 to 8 options and a closing time in the future")]
+    InvalidPollConfig,
+    #[msg("Poll is closed")]
+    PollClosed,
+    #[msg("Poll is still open")]
+    PollStillOpen,
+    #[msg("Wallet is not on the poll's voter list")]
+    NotEligibleVoter,
+    #[msg("Wallet has already voted in this poll")]
+    AlreadyVoted,
+    #[msg("Another ballot is being counted; retry shortly")]
+    PollTallyBusy,
+    #[msg("Computation output is for a ballot that is no longer pending")]
+    StaleBallotComputation,
+    #[msg("Poll results have already been published")]
+    PollResultsPublished,
+    #[msg("Ballot type does not match the poll (plurality vs ranked-choice)")]
+    WrongBallotType,
+    #[msg("Question is not a TextInput question")]
+    InvalidTextQuestion,
+    #[msg("Text response must be padded inline ciphertext or an off-chain URI with its hash")]
+    InvalidTextResponse,
+    #[msg("Keyword vocabulary must hold 1 to 32 keywords and commit to a hash")]
+    InvalidKeywordVocabulary,
+    #[msg("Keywords for this answer were already counted")]
+    KeywordsAlreadySubmitted,
+    #[msg("Data provisioning can't change once responses exist")]
+    DataProvisioningFrozen,
+    #[msg("Responses to this survey are encrypted to the creator; MPC submission is disabled")]
+    MpcDisabledForSurvey,
+    #[msg("Survey does not take creator-only responses")]
+    CreatorOnlyDisabled,
+    #[msg("Creator-only response must hold 1 to 64 ciphertexts")]
+    InvalidCreatorResponse,
+    #[msg("Survey takes responses in batches; use buffer_survey_response")]
+    BatchSubmissionRequired,
+    #[msg("Survey does not take batched responses")]
+    BatchModeDisabled,
+    #[msg("Response batch is full or being processed")]
+    ResponseBatchBusy,
+    #[msg("Too few buffered responses to process a batch")]
+    ResponseBatchTooSmall,
+    #[msg("Computation result is for a batch that was already applied")]
+    StaleBatchComputation,
+    #[msg("No computation definitions are deployed for this encryption scheme, provisioning and MPC protocol")]
+    ComputationVariantNotDeployed,
+    #[msg("Survey runs on a different MPC backend; use its variant of this instruction")]
+    ComputationVariantMismatch,
+    #[msg("Committed question content needs the question and its Merkle proof")]
+    QuestionProofRequired,
+    #[msg("Question does not match the survey's content commitment")]
+    InvalidQuestionProof,
+    #[msg("Another answer to this question is being tallied; retry shortly")]
+    TallyBusy,
+    #[msg("Wallet has already answered this question")]
+    AlreadyResponded,
+    #[msg("Computation output is for an answer that is no longer pending")]
+    StaleTallyComputation,
+    #[msg("Not enough new answers since the last reveal")]
+    TooFewNewSubmissions,
+    #[msg("Another attempt is being graded; retry shortly")]
+    GradingBusy,
+    #[msg("Wallet is already on this leaderboard")]
+    AlreadyOnLeaderboard,
+    #[msg("Wallet has no entry on this leaderboard")]
+    NotOnLeaderboard,
+    #[msg("Start the quiz attempt first: shuffled answers follow the order it dealt")]
+    AttemptNotStarted,
+    #[msg("Entry must name a question added in the same call, at most once")]
+    InvalidQuestionEntry,
+    #[msg("A quiz can shuffle answers or draw from a question pool, not both")]
+    ShuffledQuestionPool,
 }
 
 // Account structures for admin controls
@@ -3467,9 +15102,48 @@ pub struct SetSurveyComputationPreferences<'info> {
         bump
     )]
     pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    // Only the program's upgrade authority may create the preferences
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SeQure>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+// ✅ NEW: Upgrade-authority repair of preferences created before `admin` and `bump`
+#[derive(Accounts)]
+pub struct MigrateSurveyComputationPreferences<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"survey_computation_preferences"],
+        bump,
+        realloc = 8 + SurveyComputationPreferences::INIT_SPACE,
+        realloc::payer = authority,
+        realloc::zero = false
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SeQure>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
     pub system_program: Program<'info, System>,
 }
 
+// ✅ NEW: Admin-only access to the global computation preferences
+#[derive(Accounts)]
+pub struct ManageSurveyComputationPreferences<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"survey_computation_preferences"],
+        bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub app_preferences: Account<'info, SurveyComputationPreferences>,
+}
+
 // Admin control functions for creators
 pub fn suspend_my_survey(ctx: Context<SuspendSurvey>) -> Result<()> {
     let survey = &mut ctx.accounts.survey;
@@ -3572,3 +15246,633 @@ pub fn modify_my_survey(
     Ok(())
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: ErrorCode) -> anchor_lang::error::Error {
+        code.into()
+    }
+
+    fn question(id: u32, question_type: QuestionType) -> QuestionData {
+        QuestionData {
+            id,
+            question_text: format!("Question {}", id),
+            question_type,
+            required: false,
+            points: 1,
+        }
+    }
+
+    fn multiple_choice(id: u32, option_count: usize) -> QuestionData {
+        let options = (0..option_count).map(|i| format!("Option {}", i)).collect();
+        question(id, QuestionType::MultipleChoice { options })
+    }
+
+    fn translation(language: &str, option_count: usize) -> QuestionTranslation {
+        QuestionTranslation {
+            language: language.to_string(),
+            question_text: "Pregunta".to_string(),
+            options: (0..option_count).map(|i| format!("Opción {}", i)).collect(),
+        }
+    }
+
+    fn survey(survey_type: SurveyType) -> Survey {
+        Survey {
+            creator: Pubkey::new_unique(),
+            slug: "survey".to_string(),
+            title: "Survey".to_string(),
+            description: String::new(),
+            survey_type,
+            questions: vec![],
+            max_responses: 100,
+            current_responses: 0,
+            is_active: true,
+            is_publicly_browsable: true,
+            modification_count: 0,
+            created_at: 0,
+            mpc_computation_id: 0,
+            instructor_arcium_pubkey: [0u8; 32],
+            creator_arcium_pubkey: [0u8; 32],
+            question_content: None,
+            default_language: "en".to_string(),
+            report_decimals: 0,
+            shuffle_answers: false,
+            data_provisioning: None,
+            mpc_protocol: None,
+            question_translations: vec![],
+            question_display_conditions: vec![],
+            question_grading: vec![],
+        }
+    }
+
+    #[test]
+    fn test_question_cap_covers_whole_survey() {
+        let existing: Vec<QuestionData> = (0..MAX_QUESTIONS as u32 - 1).map(|id| multiple_choice(id, 2)).collect();
+        let last = vec![multiple_choice(MAX_QUESTIONS as u32, 2)];
+        assert!(validate_questions(&last, &[], &[], &[], &existing, "en").is_ok());
+
+        let two_more = vec![multiple_choice(100, 2), multiple_choice(101, 2)];
+        assert_eq!(
+            validate_questions(&two_more, &[], &[], &[], &existing, "en").unwrap_err(),
+            error(ErrorCode::TooManyQuestions)
+        );
+    }
+
+    #[test]
+    fn test_survey_space_covers_largest_fields() {
+        let slug = "s".repeat(MAX_SLUG_LENGTH);
+        let title = "t".repeat(MAX_TITLE_LENGTH);
+        let mut largest = survey(SurveyType::Poll {
+            option_count: 4,
+            closes_at: 0,
+            eligible_voters_root: Some([0u8; 32]),
+            ranked_choice: true,
+        });
+        largest.slug = slug.clone();
+        largest.title = title.clone();
+        largest.description = "d".repeat(200);
+        largest.default_language = "l".repeat(MAX_LANGUAGE_TAG_LENGTH);
+        largest.data_provisioning = Some(DataProvisioningMethod::CreatorOnly);
+        largest.mpc_protocol = Some(MPCProtocol::Manticore);
+
+        assert_eq!(
+            DISCRIMINATOR_LENGTH + largest.try_to_vec().unwrap().len(),
+            Survey::calculate_space(&slug, &title)
+        );
+    }
+
+    #[test]
+    fn test_questions_space_matches_serialized_questions() {
+        let empty = survey(SurveyType::Basic);
+        let questions = vec![
+            multiple_choice(1, 4),
+            question(2, QuestionType::Rating { min: 1, max: 5 }),
+            question(3, QuestionType::Matrix {
+                rows: vec!["Row".to_string(); 2],
+                columns: vec!["Column".to_string(); 3],
+            }),
+        ];
+        let mut filled = empty.clone();
+        filled.questions = questions.clone();
+
+        assert_eq!(
+            filled.try_to_vec().unwrap().len() - empty.try_to_vec().unwrap().len(),
+            Survey::questions_space(&questions)
+        );
+    }
+
+    #[test]
+    fn test_translations_label_every_option() {
+        let choice = multiple_choice(1, 3);
+        assert!(validate_translations(&choice, &[translation("es", 3), translation("pt-BR", 3)], "en").is_ok());
+
+        let matrix = question(2, QuestionType::Matrix {
+            rows: vec!["Row".to_string(); 2],
+            columns: vec!["Column".to_string(); 3],
+        });
+        assert!(validate_translations(&matrix, &[translation("es", 5)], "en").is_ok());
+
+        let text = question(3, QuestionType::TextInput);
+        assert!(validate_translations(&text, &[translation("es", 0)], "en").is_ok());
+
+        assert_eq!(
+            validate_translations(&choice, &[translation("es", 2)], "en").unwrap_err(),
+            error(ErrorCode::TranslationOptionMismatch)
+        );
+        assert_eq!(
+            validate_translations(&text, &[translation("es", 1)], "en").unwrap_err(),
+            error(ErrorCode::TranslationOptionMismatch)
+        );
+    }
+
+    #[test]
+    fn test_translations_unique_per_locale() {
+        let choice = multiple_choice(1, 2);
+
+        assert_eq!(
+            validate_translations(&choice, &[translation("EN", 2)], "en").unwrap_err(),
+            error(ErrorCode::DuplicateTranslation)
+        );
+        assert_eq!(
+            validate_translations(&choice, &[translation("pt-BR", 2), translation("pt-br", 2)], "en").unwrap_err(),
+            error(ErrorCode::DuplicateTranslation)
+        );
+
+        for tag in ["e", "e5", "english", "es-", "es_MX", "es-toolongsubtag"] {
+            assert_eq!(
+                validate_translations(&choice, &[translation(tag, 2)], "en").unwrap_err(),
+                error(ErrorCode::InvalidLanguageTag),
+                "{}",
+                tag
+            );
+        }
+
+        let six: Vec<_> = ["es", "fr", "de", "it", "pt", "ja"]
+            .iter()
+            .map(|language| translation(language, 2))
+            .collect();
+        assert_eq!(
+            validate_translations(&choice, &six, "en").unwrap_err(),
+            error(ErrorCode::TooManyTranslations)
+        );
+    }
+
+    #[test]
+    fn test_translations_keyed_by_question_of_batch() {
+        let questions = vec![multiple_choice(1, 2), multiple_choice(2, 3)];
+        let entry = |question_id, option_count| QuestionTranslations {
+            question_id,
+            translations: vec![translation("es", option_count)],
+        };
+
+        assert!(validate_questions(&questions, &[entry(2, 3)], &[], &[], &[], "en").is_ok());
+        // Checked against its own question, not the first one
+        assert_eq!(
+            validate_questions(&questions, &[entry(2, 2)], &[], &[], &[], "en").unwrap_err(),
+            error(ErrorCode::TranslationOptionMismatch)
+        );
+        assert_eq!(
+            validate_questions(&questions, &[entry(3, 2)], &[], &[], &[], "en").unwrap_err(),
+            error(ErrorCode::InvalidQuestionEntry)
+        );
+        assert_eq!(
+            validate_questions(&questions, &[entry(1, 2), entry(1, 2)], &[], &[], &[], "en").unwrap_err(),
+            error(ErrorCode::InvalidQuestionEntry)
+        );
+    }
+
+    #[test]
+    fn test_translations_space_matches_serialized_entries() {
+        let empty = survey(SurveyType::Basic);
+        let translations = vec![QuestionTranslations {
+            question_id: 1,
+            translations: vec![translation("es", 3), translation("pt-BR", 3)],
+        }];
+        let mut filled = empty.clone();
+        filled.question_translations = translations.clone();
+
+        assert_eq!(
+            filled.try_to_vec().unwrap().len() - empty.try_to_vec().unwrap().len(),
+            Survey::questions_space(&translations)
+        );
+    }
+
+    fn condition(question_id: u32, operator: ConditionOperator, value: u32) -> DisplayCondition {
+        DisplayCondition { question_id, operator, value }
+    }
+
+    #[test]
+    fn test_display_condition_on_earlier_choice() {
+        let earlier = [multiple_choice(1, 3), question(2, QuestionType::TrueFalse)];
+
+        let on_choice = [condition(1, ConditionOperator::Equals, 2)];
+        assert!(validate_display_conditions(&on_choice, earlier.iter()).is_ok());
+        let on_true_false = [condition(2, ConditionOperator::NotEquals, 1)];
+        assert!(validate_display_conditions(&on_true_false, earlier.iter()).is_ok());
+
+        // Option index past the end, and a TrueFalse answer other than 0/1
+        for invalid in [condition(1, ConditionOperator::Equals, 3), condition(2, ConditionOperator::Equals, 2)] {
+            assert_eq!(
+                validate_display_conditions(&[invalid], earlier.iter()).unwrap_err(),
+                error(ErrorCode::InvalidDisplayCondition)
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_condition_ordering_only_on_rating() {
+        let earlier = [multiple_choice(1, 3), question(2, QuestionType::Rating { min: 1, max: 5 })];
+
+        assert!(validate_display_conditions(&[condition(2, ConditionOperator::GreaterThan, 3)], earlier.iter()).is_ok());
+        assert!(validate_display_conditions(&[condition(2, ConditionOperator::LessThan, 5)], earlier.iter()).is_ok());
+
+        for invalid in [condition(2, ConditionOperator::Equals, 0), condition(1, ConditionOperator::GreaterThan, 0)] {
+            assert_eq!(
+                validate_display_conditions(&[invalid], earlier.iter()).unwrap_err(),
+                error(ErrorCode::InvalidDisplayCondition)
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_condition_target_must_be_earlier_and_comparable() {
+        let earlier = [question(1, QuestionType::TextInput), question(2, QuestionType::TimeLimit { duration: 30 })];
+
+        for target in [1, 2, 3, 4] {
+            assert_eq!(
+                validate_display_conditions(&[condition(target, ConditionOperator::Equals, 0)], earlier.iter()).unwrap_err(),
+                error(ErrorCode::InvalidDisplayCondition)
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_condition_limit() {
+        let earlier = [multiple_choice(1, 3)];
+        let mut conditions = vec![condition(1, ConditionOperator::NotEquals, 0); MAX_DISPLAY_CONDITIONS];
+        assert!(validate_display_conditions(&conditions, earlier.iter()).is_ok());
+
+        conditions.push(condition(1, ConditionOperator::NotEquals, 0));
+        assert_eq!(
+            validate_display_conditions(&conditions, earlier.iter()).unwrap_err(),
+            error(ErrorCode::TooManyDisplayConditions)
+        );
+    }
+
+    #[test]
+    fn test_display_conditions_see_earlier_questions_of_batch() {
+        let questions = vec![multiple_choice(1, 2), multiple_choice(2, 2)];
+        let entry = |question_id, target| QuestionDisplayConditions {
+            question_id,
+            conditions: vec![condition(target, ConditionOperator::Equals, 1)],
+        };
+
+        assert!(validate_questions(&questions, &[], &[entry(2, 1)], &[], &[], "en").is_ok());
+        assert!(validate_questions(&questions[1..].to_vec(), &[], &[entry(2, 1)], &[], &questions[..1], "en").is_ok());
+        assert_eq!(
+            validate_questions(&questions, &[], &[entry(1, 2)], &[], &[], "en").unwrap_err(),
+            error(ErrorCode::InvalidDisplayCondition)
+        );
+        assert_eq!(
+            validate_questions(&questions, &[], &[entry(3, 1)], &[], &[], "en").unwrap_err(),
+            error(ErrorCode::InvalidQuestionEntry)
+        );
+    }
+
+    #[test]
+    fn test_grading_scheme_fits_question() {
+        let choice = multiple_choice(1, 3);
+        let rating = question(2, QuestionType::Rating { min: 1, max: 5 });
+
+        assert!(validate_grading_scheme(&choice, &GradingScheme::MultiSelect { wrong_pick_penalty: 1 }).is_ok());
+        assert!(validate_grading_scheme(&rating, &GradingScheme::NumericTolerance { tolerance: 1, negative_marks: 0 }).is_ok());
+        for (question, grading) in [
+            (&choice, GradingScheme::Exact { negative_marks: 2 }),
+            (&rating, GradingScheme::MultiSelect { wrong_pick_penalty: 0 }),
+            (&choice, GradingScheme::NumericTolerance { tolerance: 1, negative_marks: 0 }),
+            (&rating, GradingScheme::NumericTolerance { tolerance: NOT_ANSWERED, negative_marks: 0 }),
+        ] {
+            assert_eq!(
+                validate_grading_scheme(question, &grading).unwrap_err(),
+                error(ErrorCode::InvalidGradingScheme)
+            );
+        }
+    }
+
+    #[test]
+    fn test_grading_defaults_to_exact() {
+        let mut quiz = survey(SurveyType::Basic);
+        quiz.questions = vec![multiple_choice(1, 3), multiple_choice(2, 3)];
+        quiz.question_grading = vec![QuestionGrading {
+            question_id: 2,
+            grading: GradingScheme::MultiSelect { wrong_pick_penalty: 0 },
+        }];
+
+        assert!(quiz.grading_for(1) == GradingScheme::Exact { negative_marks: 0 });
+        let shuffle = AnswerShuffle::for_attempt(&[7u8; 32], &quiz);
+        assert_eq!(shuffle.bitmask_answers, [false, true]);
+    }
+
+    #[test]
+    fn test_shuffling_and_question_pool_exclude_each_other() {
+        assert!(require_single_order_mode(false, false).is_ok());
+        assert!(require_single_order_mode(true, false).is_ok());
+        assert!(require_single_order_mode(false, true).is_ok());
+        assert_eq!(
+            require_single_order_mode(true, true).unwrap_err(),
+            error(ErrorCode::ShuffledQuestionPool)
+        );
+    }
+
+    fn batch_buffer() -> ResponseBatchBuffer {
+        ResponseBatchBuffer {
+            survey: Pubkey::new_unique(),
+            buffered: 0,
+            processing: false,
+            processing_since: 0,
+            batches_processed: 0,
+            processed_responses: 0,
+            bump: 255,
+            totals_nonce: 0,
+            encrypted_totals: [[0u8; 32]; BATCH_TOTALS_CIPHERTEXTS],
+            respondent_pub_keys: [[0u8; 32]; RESPONSE_BATCH_SIZE],
+            respondent_nonces: [0u128; RESPONSE_BATCH_SIZE],
+            encrypted_responses: [[[0u8; 32]; BATCH_RESPONSE_CIPHERTEXTS]; RESPONSE_BATCH_SIZE],
+            batches_queued: 0,
+            last_revealed_responses: 0,
+        }
+    }
+
+    fn buffer_responses(buffer: &mut ResponseBatchBuffer, count: u8, now: i64) {
+        for _ in 0..count {
+            let tag = buffer.buffered + 1;
+            buffer
+                .buffer_response([tag; 32], tag as u128, [[tag; 32]; BATCH_RESPONSE_CIPHERTEXTS], now)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_start_batch_pads_from_first_slot() {
+        let mut buffer = batch_buffer();
+        buffer_responses(&mut buffer, 2, 1_000);
+        buffer.start_batch(1_000).unwrap();
+
+        assert!(buffer.processing);
+        assert_eq!(buffer.batches_queued, 1);
+        assert_eq!(buffer.respondent_pub_keys[1], [2u8; 32]);
+        for slot in 2..RESPONSE_BATCH_SIZE {
+            assert_eq!(buffer.respondent_pub_keys[slot], buffer.respondent_pub_keys[0]);
+            assert_eq!(buffer.respondent_nonces[slot], buffer.respondent_nonces[0]);
+            assert_eq!(buffer.encrypted_responses[slot], buffer.encrypted_responses[0]);
+        }
+        assert_eq!(buffer.batch_args(Pubkey::new_unique()).len(), 3 * RESPONSE_BATCH_SIZE + 4);
+    }
+
+    #[test]
+    fn test_start_batch_needs_minimum_size() {
+        let mut buffer = batch_buffer();
+        for _ in 0..MIN_RESPONSE_BATCH_SIZE {
+            assert_eq!(buffer.start_batch(1_000).unwrap_err(), error(ErrorCode::ResponseBatchTooSmall));
+            buffer_responses(&mut buffer, 1, 1_000);
+        }
+        buffer.start_batch(1_000).unwrap();
+    }
+
+    #[test]
+    fn test_batch_lock_times_out() {
+        let mut buffer = batch_buffer();
+        buffer_responses(&mut buffer, 2, 1_000);
+        buffer.start_batch(1_000).unwrap();
+
+        let before_timeout = 1_000 + BATCH_LOCK_TIMEOUT_SECONDS - 1;
+        assert_eq!(buffer.start_batch(before_timeout).unwrap_err(), error(ErrorCode::ResponseBatchBusy));
+        assert_eq!(
+            buffer
+                .buffer_response([9u8; 32], 9, [[9u8; 32]; BATCH_RESPONSE_CIPHERTEXTS], before_timeout)
+                .unwrap_err(),
+            error(ErrorCode::ResponseBatchBusy)
+        );
+
+        // A lost computation abandons its batch; the responses stay buffered
+        buffer_responses(&mut buffer, 1, 1_000 + BATCH_LOCK_TIMEOUT_SECONDS);
+        assert!(!buffer.processing);
+        assert_eq!(buffer.buffered, 3);
+        assert_eq!(
+            buffer.finish_batch([[7u8; 32]; BATCH_TOTALS_CIPHERTEXTS], 7, 1).unwrap_err(),
+            error(ErrorCode::StaleBatchComputation)
+        );
+    }
+
+    #[test]
+    fn test_finish_batch_counts_latest_queue_once() {
+        let mut buffer = batch_buffer();
+        buffer_responses(&mut buffer, 3, 1_000);
+        buffer.start_batch(1_000).unwrap();
+        // Requeued after the first computation was lost
+        buffer.start_batch(1_000 + BATCH_LOCK_TIMEOUT_SECONDS).unwrap();
+
+        assert_eq!(
+            buffer.finish_batch([[7u8; 32]; BATCH_TOTALS_CIPHERTEXTS], 7, 1).unwrap_err(),
+            error(ErrorCode::StaleBatchComputation)
+        );
+        buffer.finish_batch([[8u8; 32]; BATCH_TOTALS_CIPHERTEXTS], 8, 2).unwrap();
+
+        assert!(!buffer.processing);
+        assert_eq!(buffer.buffered, 0);
+        assert_eq!(buffer.batches_processed, 1);
+        assert_eq!(buffer.processed_responses, 3);
+        assert_eq!(buffer.totals_nonce, 8);
+        assert_eq!(buffer.encrypted_totals, [[8u8; 32]; BATCH_TOTALS_CIPHERTEXTS]);
+        assert_eq!(buffer.respondent_pub_keys, [[0u8; 32]; RESPONSE_BATCH_SIZE]);
+
+        assert_eq!(
+            buffer.finish_batch([[8u8; 32]; BATCH_TOTALS_CIPHERTEXTS], 8, 2).unwrap_err(),
+            error(ErrorCode::StaleBatchComputation)
+        );
+        assert_eq!(buffer.processed_responses, 3);
+    }
+
+    #[test]
+    fn test_buffer_holds_one_batch() {
+        let mut buffer = batch_buffer();
+        buffer_responses(&mut buffer, RESPONSE_BATCH_SIZE as u8, 1_000);
+        assert_eq!(
+            buffer
+                .buffer_response([9u8; 32], 9, [[9u8; 32]; BATCH_RESPONSE_CIPHERTEXTS], 1_000)
+                .unwrap_err(),
+            error(ErrorCode::ResponseBatchBusy)
+        );
+    }
+
+    fn preferences(data_provisioning: DataProvisioningMethod, mpc_protocol: MPCProtocol) -> SurveyComputationPreferences {
+        SurveyComputationPreferences {
+            admin: Pubkey::new_unique(),
+            preferred_encryption_scheme: EncryptionScheme::Rescue,
+            preferred_data_provisioning: data_provisioning,
+            preferred_mpc_protocol: mpc_protocol,
+            configured_at: 0,
+            is_active: true,
+            bump: 255,
+        }
+    }
+
+    fn quiz() -> SurveyType {
+        SurveyType::Quiz {
+            time_per_question: None,
+            total_time_limit: None,
+            passing_threshold: 70,
+            special_survey_enabled: false,
+            completion_proof_expiration_value: 1,
+            completion_proof_expiration_unit: ExpirationUnit::Days,
+        }
+    }
+
+    #[test]
+    fn test_computation_route_defaults() {
+        let batch_preferences = preferences(DataProvisioningMethod::Batch, MPCProtocol::Cerberus);
+
+        // Only basic surveys follow the preferred provisioning
+        let route = survey(SurveyType::Basic).computation_route(&batch_preferences).unwrap();
+        assert_eq!(route.data_provisioning, DataProvisioningMethod::Batch);
+        assert_eq!(route.mpc_protocol, MPCProtocol::Cerberus);
+        let route = survey(quiz()).computation_route(&batch_preferences).unwrap();
+        assert_eq!(route.data_provisioning, DataProvisioningMethod::Direct);
+
+        let mut overridden = survey(SurveyType::Basic);
+        overridden.data_provisioning = Some(DataProvisioningMethod::Direct);
+        let route = overridden.computation_route(&batch_preferences).unwrap();
+        assert_eq!(route.data_provisioning, DataProvisioningMethod::Direct);
+    }
+
+    #[test]
+    fn test_computation_route_rejects_undeployed_protocol() {
+        let mut pinned_manticore = survey(SurveyType::Basic);
+        pinned_manticore.mpc_protocol = Some(MPCProtocol::Manticore);
+        assert_eq!(
+            pinned_manticore
+                .computation_route(&preferences(DataProvisioningMethod::Direct, MPCProtocol::Cerberus))
+                .unwrap_err(),
+            error(ErrorCode::ComputationVariantNotDeployed)
+        );
+
+        // Nothing runs in MPC for creator-only surveys
+        pinned_manticore.data_provisioning = Some(DataProvisioningMethod::CreatorOnly);
+        assert!(pinned_manticore
+            .computation_route(&preferences(DataProvisioningMethod::Direct, MPCProtocol::Cerberus))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_route_requirements() {
+        let route = |data_provisioning| ComputationRoute {
+            encryption_scheme: EncryptionScheme::Rescue,
+            data_provisioning,
+            mpc_protocol: MPCProtocol::Cerberus,
+        };
+        let cases = [
+            (DataProvisioningMethod::Batch, DataProvisioningMethod::Direct, ErrorCode::BatchSubmissionRequired),
+            (DataProvisioningMethod::CreatorOnly, DataProvisioningMethod::Direct, ErrorCode::MpcDisabledForSurvey),
+            (DataProvisioningMethod::Direct, DataProvisioningMethod::Batch, ErrorCode::BatchModeDisabled),
+            (DataProvisioningMethod::Direct, DataProvisioningMethod::CreatorOnly, ErrorCode::CreatorOnlyDisabled),
+        ];
+        for (actual, expected, code) in cases {
+            assert!(route(expected.clone()).require_provisioning(expected.clone()).is_ok());
+            assert_eq!(route(actual).require_provisioning(expected).unwrap_err(), error(code));
+        }
+
+        assert!(route(DataProvisioningMethod::Direct).require_protocol(MPCProtocol::Cerberus).is_ok());
+        assert_eq!(
+            route(DataProvisioningMethod::Direct).require_protocol(MPCProtocol::Manticore).unwrap_err(),
+            error(ErrorCode::ComputationVariantMismatch)
+        );
+    }
+
+    #[test]
+    fn test_route_direct_pins_provisioning() {
+        let mut basic = survey(SurveyType::Basic);
+        basic.route_direct(&preferences(DataProvisioningMethod::Direct, MPCProtocol::Cerberus)).unwrap();
+        assert_eq!(basic.data_provisioning, Some(DataProvisioningMethod::Direct));
+
+        // Later preference changes don't move a pinned survey
+        assert!(basic.route_direct(&preferences(DataProvisioningMethod::Batch, MPCProtocol::Cerberus)).is_ok());
+
+        let mut batched = survey(SurveyType::Basic);
+        assert_eq!(
+            batched
+                .route_direct(&preferences(DataProvisioningMethod::Batch, MPCProtocol::Cerberus))
+                .unwrap_err(),
+            error(ErrorCode::BatchSubmissionRequired)
+        );
+        assert_eq!(batched.data_provisioning, None);
+    }
+
+    #[test]
+    fn test_preferences_only_accept_deployed_defaults() {
+        let mut current = preferences(DataProvisioningMethod::Direct, MPCProtocol::Cerberus);
+        for (encryption_scheme, mpc_protocol) in [
+            (EncryptionScheme::AES, MPCProtocol::Cerberus),
+            (EncryptionScheme::Rescue, MPCProtocol::Manticore),
+        ] {
+            assert_eq!(
+                current
+                    .configure(encryption_scheme, DataProvisioningMethod::Direct, mpc_protocol)
+                    .unwrap_err(),
+                error(ErrorCode::ComputationVariantNotDeployed)
+            );
+        }
+        // Rejected before anything is written
+        assert_eq!(current.preferred_mpc_protocol, MPCProtocol::Cerberus);
+        assert_eq!(current.preferred_encryption_scheme, EncryptionScheme::Rescue);
+    }
+
+    #[test]
+    fn test_tally_lock_single_computation() {
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let mut lock = TallyLock::default();
+        assert!(!lock.is_held(0));
+
+        lock.acquire(first, 1_000).unwrap();
+        assert!(lock.is_held(1_000 + TALLY_LOCK_TIMEOUT_SECONDS - 1));
+        assert_eq!(lock.acquire(second, 1_001).unwrap_err(), error(ErrorCode::TallyBusy));
+        assert_eq!(lock.release(second).unwrap_err(), error(ErrorCode::StaleTallyComputation));
+
+        lock.release(first).unwrap();
+        assert!(!lock.is_held(1_001));
+        assert_eq!(lock.release(first).unwrap_err(), error(ErrorCode::StaleTallyComputation));
+        lock.acquire(second, 1_001).unwrap();
+    }
+
+    #[test]
+    fn test_tally_lock_expires() {
+        let lost = Pubkey::new_unique();
+        let retry = Pubkey::new_unique();
+        let mut lock = TallyLock::default();
+        lock.acquire(lost, 1_000).unwrap();
+
+        assert!(!lock.is_held(1_000 + TALLY_LOCK_TIMEOUT_SECONDS));
+        lock.acquire(retry, 1_000 + TALLY_LOCK_TIMEOUT_SECONDS).unwrap();
+        // The lost computation landing late must not overwrite the retry
+        assert_eq!(lock.release(lost).unwrap_err(), error(ErrorCode::StaleTallyComputation));
+        lock.release(retry).unwrap();
+    }
+
+    #[test]
+    fn test_reveal_interval() {
+        assert!(check_reveal_interval(5, 0, 5).is_ok());
+        assert!(check_reveal_interval(15, 5, 10).is_ok());
+        assert_eq!(
+            check_reveal_interval(14, 5, 10).unwrap_err(),
+            error(ErrorCode::TooFewNewSubmissions)
+        );
+        // Saturates instead of overflowing when the previous reveal is near the limit
+        assert!(check_reveal_interval(u32::MAX, u32::MAX - 1, 5).is_ok());
+        assert_eq!(
+            check_reveal_interval(u32::MAX - 1, u32::MAX - 1, 5).unwrap_err(),
+            error(ErrorCode::TooFewNewSubmissions)
+        );
+    }
+}
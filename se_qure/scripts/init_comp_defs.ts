@@ -1,11 +1,54 @@
 /**
  * Initialize Computation Definitions Script
  * 
- * This script initializes all 4 computation definitions for the SeQure program:
+ * This script initializes all 47 computation definitions for the SeQure program:
  * 1. survey_analytics
  * 2. quiz_evaluation  
  * 3. analytics_computation
  * 4. quiz_threshold_check
+ * 5. init_matrix_tally
+ * 6. accumulate_matrix_response
+ * 7. reveal_matrix_tally
+ * 8. init_ranking_tally
+ * 9. accumulate_ranking_response
+ * 10. reveal_ranking_tally
+ * 11. init_nps_tally
+ * 12. accumulate_nps_response
+ * 13. reveal_nps
+ * 14. init_histogram
+ * 15. accumulate_histogram_response
+ * 16. reveal_percentiles
+ * 17. init_crosstab
+ * 18. accumulate_crosstab_response
+ * 19. reveal_crosstab
+ * 20. init_correlation_sums
+ * 21. init_item_stats
+ * 22. reveal_item_analysis
+ * 23. init_leaderboard
+ * 24. record_leaderboard_score
+ * 25. reveal_my_rank
+ * 26. reveal_leaderboard
+ * 27. init_answer_history
+ * 28. check_answer_similarity
+ * 29. pool_quiz_evaluation
+ * 30. init_adaptive_attempt
+ * 31. adaptive_step
+ * 32. reveal_adaptive_result
+ * 33. init_poll_tally
+ * 34. cast_poll_vote
+ * 35. reveal_poll_tally
+ * 36. init_ranked_tally
+ * 37. cast_ranked_ballot
+ * 38. run_instant_runoff
+ * 39. init_text_tally
+ * 40. accumulate_text_response
+ * 41. reveal_text_stats
+ * 42. init_keyword_tally
+ * 43. accumulate_keywords
+ * 44. reveal_keyword_table
+ * 45. init_batch_totals
+ * 46. process_response_batch
+ * 47. reveal_batch_analytics
  * 
  * Usage:
  * ANCHOR_PROVIDER_URL=https://api.devnet.solana.com ANCHOR_WALLET=~/.config/solana/id.json npx ts-node scripts/init_comp_defs.ts
@@ -34,7 +77,50 @@ const COMP_DEF_NAMES = [
   "survey_analytics",
   "quiz_evaluation", 
   "analytics_computation",
-  "quiz_threshold_check"
+  "quiz_threshold_check",
+  "init_matrix_tally",
+  "accumulate_matrix_response",
+  "reveal_matrix_tally",
+  "init_ranking_tally",
+  "accumulate_ranking_response",
+  "reveal_ranking_tally",
+  "init_nps_tally",
+  "accumulate_nps_response",
+  "reveal_nps",
+  "init_histogram",
+  "accumulate_histogram_response",
+  "reveal_percentiles",
+  "init_crosstab",
+  "accumulate_crosstab_response",
+  "reveal_crosstab",
+  "init_correlation_sums",
+  "init_item_stats",
+  "reveal_item_analysis",
+  "init_leaderboard",
+  "record_leaderboard_score",
+  "reveal_my_rank",
+  "reveal_leaderboard",
+  "init_answer_history",
+  "check_answer_similarity",
+  "pool_quiz_evaluation",
+  "init_adaptive_attempt",
+  "adaptive_step",
+  "reveal_adaptive_result",
+  "init_poll_tally",
+  "cast_poll_vote",
+  "reveal_poll_tally",
+  "init_ranked_tally",
+  "cast_ranked_ballot",
+  "run_instant_runoff",
+  "init_text_tally",
+  "accumulate_text_response",
+  "reveal_text_stats",
+  "init_keyword_tally",
+  "accumulate_keywords",
+  "reveal_keyword_table",
+  "init_batch_totals",
+  "process_response_batch",
+  "reveal_batch_analytics"
 ] as const;
 
 type CompDefName = typeof COMP_DEF_NAMES[number];
@@ -152,6 +238,522 @@ async function initializeCompDef(
           });
         break;
         
+      case "init_matrix_tally":
+        txSignature = await program.methods
+          .initMatrixTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "accumulate_matrix_response":
+        txSignature = await program.methods
+          .initAccumulateMatrixResponseCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_matrix_tally":
+        txSignature = await program.methods
+          .initRevealMatrixTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_ranking_tally":
+        txSignature = await program.methods
+          .initRankingTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "accumulate_ranking_response":
+        txSignature = await program.methods
+          .initAccumulateRankingResponseCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_ranking_tally":
+        txSignature = await program.methods
+          .initRevealRankingTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_nps_tally":
+        txSignature = await program.methods
+          .initNpsTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "accumulate_nps_response":
+        txSignature = await program.methods
+          .initAccumulateNpsResponseCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_nps":
+        txSignature = await program.methods
+          .initRevealNpsCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_histogram":
+        txSignature = await program.methods
+          .initHistogramCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "accumulate_histogram_response":
+        txSignature = await program.methods
+          .initAccumulateHistogramResponseCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_percentiles":
+        txSignature = await program.methods
+          .initRevealPercentilesCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_crosstab":
+        txSignature = await program.methods
+          .initCrosstabCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "accumulate_crosstab_response":
+        txSignature = await program.methods
+          .initAccumulateCrosstabResponseCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_crosstab":
+        txSignature = await program.methods
+          .initRevealCrosstabCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_correlation_sums":
+        txSignature = await program.methods
+          .initCorrelationSumsCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_item_stats":
+        txSignature = await program.methods
+          .initItemStatsCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_item_analysis":
+        txSignature = await program.methods
+          .initRevealItemAnalysisCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_leaderboard":
+        txSignature = await program.methods
+          .initLeaderboardCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "record_leaderboard_score":
+        txSignature = await program.methods
+          .initRecordLeaderboardScoreCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_my_rank":
+        txSignature = await program.methods
+          .initRevealMyRankCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_leaderboard":
+        txSignature = await program.methods
+          .initRevealLeaderboardCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_answer_history":
+        txSignature = await program.methods
+          .initAnswerHistoryCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "check_answer_similarity":
+        txSignature = await program.methods
+          .initCheckAnswerSimilarityCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "pool_quiz_evaluation":
+        txSignature = await program.methods
+          .initPoolQuizEvaluationCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_adaptive_attempt":
+        txSignature = await program.methods
+          .initAdaptiveAttemptCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "adaptive_step":
+        txSignature = await program.methods
+          .initAdaptiveStepCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_adaptive_result":
+        txSignature = await program.methods
+          .initRevealAdaptiveResultCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_poll_tally":
+        txSignature = await program.methods
+          .initPollTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "cast_poll_vote":
+        txSignature = await program.methods
+          .initCastPollVoteCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_poll_tally":
+        txSignature = await program.methods
+          .initRevealPollTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_ranked_tally":
+        txSignature = await program.methods
+          .initRankedTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "cast_ranked_ballot":
+        txSignature = await program.methods
+          .initCastRankedBallotCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "run_instant_runoff":
+        txSignature = await program.methods
+          .initRunInstantRunoffCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_text_tally":
+        txSignature = await program.methods
+          .initTextTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "accumulate_text_response":
+        txSignature = await program.methods
+          .initAccumulateTextResponseCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_text_stats":
+        txSignature = await program.methods
+          .initRevealTextStatsCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_keyword_tally":
+        txSignature = await program.methods
+          .initKeywordTallyCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "accumulate_keywords":
+        txSignature = await program.methods
+          .initAccumulateKeywordsCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_keyword_table":
+        txSignature = await program.methods
+          .initRevealKeywordTableCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "init_batch_totals":
+        txSignature = await program.methods
+          .initBatchTotalsCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "process_response_batch":
+        txSignature = await program.methods
+          .initProcessResponseBatchCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
+      case "reveal_batch_analytics":
+        txSignature = await program.methods
+          .initRevealBatchAnalyticsCompDef()
+          .accounts(accounts)
+          .signers([payer])
+          .rpc({ 
+            skipPreflight: true, 
+            commitment: "confirmed",
+            preflightCommitment: "confirmed"
+          });
+        break;
+        
       default:
         throw new Error(`Unknown computation definition: ${compDefName}`);
     }
@@ -233,6 +835,49 @@ async function initializeAllCompDefs(): Promise<void> {
       quiz_evaluation: false,
       analytics_computation: false,
       quiz_threshold_check: false,
+      init_matrix_tally: false,
+      accumulate_matrix_response: false,
+      reveal_matrix_tally: false,
+      init_ranking_tally: false,
+      accumulate_ranking_response: false,
+      reveal_ranking_tally: false,
+      init_nps_tally: false,
+      accumulate_nps_response: false,
+      reveal_nps: false,
+      init_histogram: false,
+      accumulate_histogram_response: false,
+      reveal_percentiles: false,
+      init_crosstab: false,
+      accumulate_crosstab_response: false,
+      reveal_crosstab: false,
+      init_correlation_sums: false,
+      init_item_stats: false,
+      reveal_item_analysis: false,
+      init_leaderboard: false,
+      record_leaderboard_score: false,
+      reveal_my_rank: false,
+      reveal_leaderboard: false,
+      init_answer_history: false,
+      check_answer_similarity: false,
+      pool_quiz_evaluation: false,
+      init_adaptive_attempt: false,
+      adaptive_step: false,
+      reveal_adaptive_result: false,
+      init_poll_tally: false,
+      cast_poll_vote: false,
+      reveal_poll_tally: false,
+      init_ranked_tally: false,
+      cast_ranked_ballot: false,
+      run_instant_runoff: false,
+      init_text_tally: false,
+      accumulate_text_response: false,
+      reveal_text_stats: false,
+      init_keyword_tally: false,
+      accumulate_keywords: false,
+      reveal_keyword_table: false,
+      init_batch_totals: false,
+      process_response_batch: false,
+      reveal_batch_analytics: false,
     };
     
     for (const compDefName of COMP_DEF_NAMES) {
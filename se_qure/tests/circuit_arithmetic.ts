@@ -0,0 +1,204 @@
+import { expect } from "chai";
+
+// Plaintext reference for encrypted-ixs/src/safe_math.rs and fixed_point.rs.
+//
+// Each helper is ported line by line onto fixed-width integers that throw on
+// overflow or underflow, then compared against exact BigInt arithmetic. A port
+// that throws means the circuit would silently wrap inside MPC.
+
+const U32_MAX = (1n << 32n) - 1n;
+const U64_MAX = (1n << 64n) - 1n;
+const U128_MAX = (1n << 128n) - 1n;
+
+class Overflow extends Error {}
+
+// Checked fixed-width arithmetic (Rust debug-build semantics)
+const width = (max: bigint) => ({
+  max,
+  add: (a: bigint, b: bigint) => check(a + b, max),
+  sub: (a: bigint, b: bigint) => check(a - b, max),
+  mul: (a: bigint, b: bigint) => check(a * b, max),
+  div: (a: bigint, b: bigint) => {
+    if (b === 0n) throw new Overflow("division by zero");
+    return a / b;
+  },
+});
+const check = (value: bigint, max: bigint) => {
+  if (value < 0n || value > max) throw new Overflow(`${value} out of range`);
+  return value;
+};
+const u32 = width(U32_MAX);
+const u64 = width(U64_MAX);
+const u128 = width(U128_MAX);
+const flag = (cond: boolean) => (cond ? 1n : 0n);
+
+// ---- safe_math port ----
+
+const eqU32 = (a: bigint, b: bigint) => flag(a === b);
+const selectU32 = (cond: bigint, ifTrue: bigint, ifFalse: bigint) =>
+  u32.add(u32.mul(ifTrue, cond), u32.mul(ifFalse, u32.sub(1n, cond)));
+const clampToU32 = (value: bigint) => {
+  const over = flag(value > U32_MAX);
+  return u64.add(u64.mul(value, u64.sub(1n, over)), u64.mul(U32_MAX, over));
+};
+const satAddU32 = (a: bigint, b: bigint) => clampToU32(u64.add(a, b));
+const satSubU32 = (a: bigint, b: bigint) =>
+  u32.sub(a, selectU32(flag(b <= a), b, a));
+const wideningMulU32 = (a: bigint, b: bigint) => u64.mul(a, b);
+const satMulU32 = (a: bigint, b: bigint) => clampToU32(wideningMulU32(a, b));
+const absDiffU32 = (a: bigint, b: bigint) => {
+  const aGeB = flag(a >= b);
+  return u32.sub(selectU32(aGeB, a, b), selectU32(aGeB, b, a));
+};
+const satAddU64 = (a: bigint, b: bigint) => {
+  const wide = u128.add(a, b);
+  const over = flag(wide > U64_MAX);
+  return u128.add(u128.mul(wide, u128.sub(1n, over)), u128.mul(U64_MAX, over));
+};
+const satMulU64 = (a: bigint, b: bigint) => {
+  const wide = u128.mul(a, b);
+  const over = flag(wide > U64_MAX);
+  return u128.add(u128.mul(wide, u128.sub(1n, over)), u128.mul(U64_MAX, over));
+};
+const satMulU128 = (a: bigint, b: bigint) => {
+  const aZero = flag(a === 0n);
+  const over = flag(b > u128.div(U128_MAX, u128.add(a, aZero)));
+  return u128.add(
+    u128.mul(a, u128.mul(b, u128.sub(1n, over))),
+    u128.mul(U128_MAX, over)
+  );
+};
+
+// ---- fixed_point port ----
+
+const scaleFor = (decimals: number) => 10n ** BigInt(Math.min(decimals, 4));
+const clampU64 = (value: bigint) => {
+  const over = flag(value > U64_MAX);
+  return u128.add(u128.mul(value, u128.sub(1n, over)), u128.mul(U64_MAX, over));
+};
+const divRound = (numerator: bigint, denominator: bigint, scale: bigint) => {
+  const isZero = flag(denominator === 0n);
+  const safeDenominator = u128.add(denominator, isZero);
+  const quotient = u128.div(
+    u128.add(u128.mul(numerator, scale), u128.div(safeDenominator, 2n)),
+    safeDenominator
+  );
+  return clampU64(u128.mul(quotient, u128.sub(1n, isZero)));
+};
+const percentage = (part: bigint, whole: bigint, scale: bigint) =>
+  divRound(satMulU64(part, 100n), whole, scale);
+
+// ---- exact references ----
+
+const clamp = (value: bigint, max: bigint) => (value > max ? max : value);
+const roundHalfUp = (numerator: bigint, denominator: bigint) =>
+  denominator === 0n ? 0n : (2n * numerator + denominator) / (2n * denominator);
+
+// Boundary values plus a deterministic pseudo-random sample
+const samples = (max: bigint, count: number): bigint[] => {
+  const values = new Set<bigint>([
+    0n, 1n, 2n, 3n, 69n, 70n, 99n, 100n, 255n, 256n,
+  ]);
+  for (let shift = 8n; (1n << shift) <= max; shift += 8n) {
+    values.add((1n << shift) - 1n);
+    values.add(1n << shift);
+    values.add((1n << shift) + 1n);
+  }
+  [max, max - 1n, max / 2n, max / 2n + 1n].forEach((v) => values.add(v));
+  let state = 0x5eedc0den;
+  for (let i = 0; i < count; i++) {
+    state = (state * 6364136223846793005n + 1442695040888963407n) & U64_MAX;
+    values.add(((state << 64n) | (state ^ 0x9e3779b97f4a7c15n)) % (max + 1n));
+  }
+  return [...values].filter((v) => v <= max);
+};
+
+const pairs = (values: bigint[]) =>
+  values.flatMap((a) => values.map((b) => [a, b] as const));
+
+describe("Circuit arithmetic (plaintext reference)", () => {
+  const small = samples(U32_MAX, 60);
+  const wide = samples(U64_MAX, 60);
+  const huge = samples(U128_MAX, 40);
+
+  it("u32 helpers never overflow and match exact arithmetic", () => {
+    for (const [a, b] of pairs(small)) {
+      expect(eqU32(a, b)).to.equal(flag(a === b));
+      expect(satAddU32(a, b)).to.equal(clamp(a + b, U32_MAX));
+      expect(satSubU32(a, b)).to.equal(a > b ? a - b : 0n);
+      expect(satMulU32(a, b)).to.equal(clamp(a * b, U32_MAX));
+      expect(absDiffU32(a, b)).to.equal(a > b ? a - b : b - a);
+      expect(wideningMulU32(a, b)).to.equal(a * b);
+    }
+  });
+
+  it("u64 and u128 helpers saturate instead of wrapping", () => {
+    for (const [a, b] of pairs(wide)) {
+      expect(satAddU64(a, b)).to.equal(clamp(a + b, U64_MAX));
+      expect(satMulU64(a, b)).to.equal(clamp(a * b, U64_MAX));
+    }
+    for (const [a, b] of pairs(huge)) {
+      expect(satMulU128(a, b)).to.equal(clamp(a * b, U128_MAX));
+    }
+  });
+
+  it("div_round and percentage round half up at every precision", () => {
+    for (const [a, b] of pairs(wide)) {
+      for (let decimals = 0; decimals <= 4; decimals++) {
+        const scale = scaleFor(decimals);
+        expect(divRound(a, b, scale)).to.equal(
+          clamp(roundHalfUp(a * scale, b), U64_MAX)
+        );
+        expect(percentage(a, b, scale)).to.equal(
+          clamp(roundHalfUp(clamp(a * 100n, U64_MAX) * scale, b), U64_MAX)
+        );
+      }
+      // rescale passes an arbitrary target scale through div_round
+      const divisor = b === 0n ? 1n : b;
+      expect(divRound(a, divisor, a)).to.equal(
+        clamp(roundHalfUp(a * a, divisor), U64_MAX)
+      );
+    }
+  });
+
+  it("quiz grading compares answers without subtracting", () => {
+    // Old circuit: `ua - ca == 0`
+    const oldIsCorrect = (ua: bigint, ca: bigint) =>
+      flag(u32.sub(ua, ca) === 0n);
+    expect(() => oldIsCorrect(1n, 2n)).to.throw(Overflow);
+
+    for (const [ua, ca] of pairs(small)) {
+      expect(eqU32(ua, ca)).to.equal(flag(ua === ca));
+    }
+  });
+
+  it("quiz percentages and pass/fail match exact arithmetic", () => {
+    const points = samples(U32_MAX, 10);
+    for (const [p1, p2] of pairs(points)) {
+      for (const [correct1, correct2] of [
+        [0n, 0n],
+        [0n, 1n],
+        [1n, 0n],
+        [1n, 1n],
+      ]) {
+        const totalPoints = satAddU32(p1, p2);
+        const earned = satAddU32(u32.mul(correct1, p1), u32.mul(correct2, p2));
+        const safeDivisor = u32.add(totalPoints, flag(totalPoints === 0n));
+        for (const threshold of [0n, 50n, 70n, 100n]) {
+          const passed =
+            u64.mul(earned, 100n) >= u64.mul(threshold, safeDivisor);
+          if (p1 + p2 <= U32_MAX && p1 + p2 > 0n) {
+            const exact = correct1 * p1 + correct2 * p2;
+            expect(passed).to.equal(exact * 100n >= threshold * (p1 + p2));
+          }
+        }
+        for (let decimals = 0; decimals <= 4; decimals++) {
+          const scale = scaleFor(decimals);
+          expect(clampToU32(percentage(earned, totalPoints, scale))).to.equal(
+            clamp(roundHalfUp(earned * 100n * scale, totalPoints), U32_MAX)
+          );
+        }
+      }
+    }
+  });
+});